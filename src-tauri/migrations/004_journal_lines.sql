@@ -0,0 +1,26 @@
+-- 仕訳明細テーブル（複合仕訳：1つの仕訳に借方N行・貸方N行）
+CREATE TABLE IF NOT EXISTS journal_lines (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    entry_id    INTEGER NOT NULL,
+    line_no     INTEGER NOT NULL,               -- 仕訳内の行番号 (1始まり)
+    side        TEXT    NOT NULL CHECK (side IN ('借方', '貸方')),
+    account_id  INTEGER NOT NULL,
+    amount      INTEGER NOT NULL,
+    FOREIGN KEY (entry_id)   REFERENCES journal_entries(id) ON DELETE CASCADE,
+    FOREIGN KEY (account_id) REFERENCES accounts(id),
+    CHECK (amount > 0),
+    UNIQUE (entry_id, line_no)
+);
+
+CREATE INDEX IF NOT EXISTS idx_journal_lines_account ON journal_lines(account_id);
+
+-- 複合仕訳フラグ (1=複合仕訳, 0=単一行仕訳)
+-- 複合仕訳の debit_*/credit_* 列は先頭行の科目と合計金額を保持する
+ALTER TABLE journal_entries ADD COLUMN is_compound INTEGER NOT NULL DEFAULT 0;
+
+-- 既存の単一行仕訳を明細へ移行
+INSERT INTO journal_lines (entry_id, line_no, side, account_id, amount)
+SELECT id, 1, '借方', debit_account_id, debit_amount FROM journal_entries;
+
+INSERT INTO journal_lines (entry_id, line_no, side, account_id, amount)
+SELECT id, 2, '貸方', credit_account_id, credit_amount FROM journal_entries;
@@ -0,0 +1,11 @@
+-- 期首残高テーブル（貸借対照表科目の年度別期首残高）
+-- 残高は資産は借方残、負債・純資産は貸方残を正とする
+-- 行がない年度は前年の期末残高から自動で繰り越す
+CREATE TABLE IF NOT EXISTS opening_balances (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    year        INTEGER NOT NULL,
+    account_id  INTEGER NOT NULL,
+    amount      INTEGER NOT NULL,
+    FOREIGN KEY (account_id) REFERENCES accounts(id),
+    UNIQUE (year, account_id)
+);
@@ -0,0 +1,15 @@
+-- 年度締め（元入金の再計算結果）
+-- 翌期首元入金 = 期首元入金 + 青色申告特別控除前の所得 + 事業主借 − 事業主貸
+CREATE TABLE IF NOT EXISTS year_closings (
+    year              INTEGER PRIMARY KEY,
+    prior_capital     INTEGER NOT NULL,   -- 元入金（締め前）
+    income            INTEGER NOT NULL,   -- 青色申告特別控除前の所得金額
+    owner_borrowings  INTEGER NOT NULL,   -- 事業主借
+    owner_drawings    INTEGER NOT NULL,   -- 事業主貸
+    next_capital      INTEGER NOT NULL,   -- 翌期首の元入金
+    closed_at         TEXT    NOT NULL DEFAULT (datetime('now', 'localtime'))
+);
+
+-- 期首残高の出所 (手入力 / 年度締めによる繰越)
+ALTER TABLE opening_balances ADD COLUMN source TEXT NOT NULL DEFAULT '手入力'
+    CHECK (source IN ('手入力', '繰越'));
@@ -0,0 +1,14 @@
+-- 期間ロック（締め済みの月・年度に対する変更を禁止する）
+-- 解除してもレコードは残し、unlocked_at に解除日時を記録する
+CREATE TABLE IF NOT EXISTS period_locks (
+    id           INTEGER PRIMARY KEY AUTOINCREMENT,
+    year         INTEGER NOT NULL,
+    month        INTEGER,                 -- NULL = 年度全体
+    locked_at    TEXT    NOT NULL DEFAULT (datetime('now', 'localtime')),
+    unlocked_at  TEXT,
+    CHECK (month IS NULL OR (month >= 1 AND month <= 12))
+);
+
+-- 同じ期間に有効なロックは1つだけ
+CREATE UNIQUE INDEX IF NOT EXISTS idx_period_locks_active
+    ON period_locks(year, IFNULL(month, 0)) WHERE unlocked_at IS NULL;
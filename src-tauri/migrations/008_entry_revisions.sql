@@ -0,0 +1,49 @@
+-- 仕訳の取消（論理削除）日時。NULL = 有効な仕訳
+ALTER TABLE journal_entries ADD COLUMN voided_at TEXT;
+
+-- 仕訳の訂正・削除履歴（電子帳簿保存法：優良な電子帳簿の要件）
+-- 変更前・変更後の内容を JSON で保持し、登録後は変更・削除できない
+CREATE TABLE IF NOT EXISTS journal_entry_revisions (
+    id           INTEGER PRIMARY KEY AUTOINCREMENT,
+    entry_id     INTEGER NOT NULL,
+    revision_no  INTEGER NOT NULL,                -- 仕訳ごとの版番号 (1始まり)
+    action       TEXT    NOT NULL CHECK (action IN ('登録', '訂正', '取消')),
+    before_json  TEXT,                            -- 変更前の内容（登録時は NULL）
+    after_json   TEXT,                            -- 変更後の内容（取消時は NULL）
+    changed_by   TEXT    NOT NULL DEFAULT '',
+    changed_at   TEXT    NOT NULL DEFAULT (datetime('now', 'localtime')),
+    FOREIGN KEY (entry_id) REFERENCES journal_entries(id),
+    UNIQUE (entry_id, revision_no)
+);
+
+CREATE TRIGGER IF NOT EXISTS trg_entry_revisions_no_update
+BEFORE UPDATE ON journal_entry_revisions
+BEGIN
+    SELECT RAISE(ABORT, '訂正・削除履歴は変更できません');
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_entry_revisions_no_delete
+BEFORE DELETE ON journal_entry_revisions
+BEGIN
+    SELECT RAISE(ABORT, '訂正・削除履歴は削除できません');
+END;
+
+-- 既存の仕訳は登録時点の内容を初版として記録する
+INSERT INTO journal_entry_revisions (entry_id, revision_no, action, after_json, changed_at)
+SELECT j.id, 1, '登録',
+       json_object(
+           'date', j.date,
+           'description', j.description,
+           'is_compound', json(CASE WHEN j.is_compound THEN 'true' ELSE 'false' END),
+           'lines', (
+               SELECT json_group_array(json(x.line))
+               FROM (
+                   SELECT json_object('side', l.side, 'account_id', l.account_id, 'amount', l.amount) AS line
+                   FROM journal_lines l
+                   WHERE l.entry_id = j.id
+                   ORDER BY l.line_no
+               ) x
+           )
+       ),
+       j.created_at
+FROM journal_entries j;
@@ -0,0 +1,6 @@
+-- 赤黒訂正：取消仕訳（赤）から元の仕訳への参照
+ALTER TABLE journal_entries ADD COLUMN reversal_of INTEGER REFERENCES journal_entries(id);
+
+-- 1つの仕訳に対する有効な取消仕訳は1つだけ
+CREATE UNIQUE INDEX IF NOT EXISTS idx_journal_entries_reversal
+    ON journal_entries(reversal_of) WHERE reversal_of IS NOT NULL AND voided_at IS NULL;
@@ -0,0 +1,16 @@
+-- 伝票番号（年度ごとの連番。登録時に採番し、訂正しても変わらない）
+ALTER TABLE journal_entries ADD COLUMN voucher_no INTEGER;
+
+-- 既存の仕訳は年度ごとに日付・登録順で採番する
+UPDATE journal_entries
+SET voucher_no = (
+    SELECT n.rn
+    FROM (
+        SELECT id, ROW_NUMBER() OVER (PARTITION BY substr(date, 1, 4) ORDER BY date, id) AS rn
+        FROM journal_entries
+    ) n
+    WHERE n.id = journal_entries.id
+);
+
+CREATE INDEX IF NOT EXISTS idx_journal_entries_voucher
+    ON journal_entries(substr(date, 1, 4), voucher_no);
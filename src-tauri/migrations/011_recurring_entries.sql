@@ -0,0 +1,31 @@
+-- 定期仕訳（家賃・通信費・保険料・返済など毎回同じ内容の仕訳）
+CREATE TABLE IF NOT EXISTS recurring_entries (
+    id                   INTEGER PRIMARY KEY AUTOINCREMENT,
+    name                 TEXT    NOT NULL,
+    debit_account_id     INTEGER NOT NULL,
+    credit_account_id    INTEGER NOT NULL,
+    amount               INTEGER NOT NULL,
+    description_template TEXT    NOT NULL DEFAULT '', -- {year} {month} {day} を計上日で置き換える
+    frequency            TEXT    NOT NULL,            -- 毎月/隔月/毎年/指定日/月末
+    day_of_month         INTEGER NOT NULL DEFAULT 1,  -- 計上日（月の日数を超える場合は月末）
+    start_date           TEXT    NOT NULL,            -- 開始日 (YYYY-MM-DD)。毎年は開始月、指定日はこの日に1回だけ
+    end_date             TEXT,                        -- 終了日 (NULL = 期限なし)
+    is_active            INTEGER NOT NULL DEFAULT 1,
+    FOREIGN KEY (debit_account_id) REFERENCES accounts(id),
+    FOREIGN KEY (credit_account_id) REFERENCES accounts(id),
+    CHECK (amount > 0),
+    CHECK (frequency IN ('毎月', '隔月', '毎年', '指定日', '月末')),
+    CHECK (day_of_month >= 1 AND day_of_month <= 31)
+);
+
+-- 定期仕訳の計上履歴（同じ計上日に二重に作成しない）
+CREATE TABLE IF NOT EXISTS recurring_entry_runs (
+    id            INTEGER PRIMARY KEY AUTOINCREMENT,
+    recurring_id  INTEGER NOT NULL,
+    due_date      TEXT    NOT NULL,
+    entry_id      INTEGER NOT NULL,
+    created_at    TEXT    NOT NULL DEFAULT (datetime('now', 'localtime')),
+    FOREIGN KEY (recurring_id) REFERENCES recurring_entries(id) ON DELETE CASCADE,
+    FOREIGN KEY (entry_id) REFERENCES journal_entries(id),
+    UNIQUE (recurring_id, due_date)
+);
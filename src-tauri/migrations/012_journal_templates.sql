@@ -0,0 +1,64 @@
+-- 仕訳テンプレート（かんたん入力の項目。勘定科目は ID で参照する）
+CREATE TABLE IF NOT EXISTS journal_templates (
+    id                      INTEGER PRIMARY KEY AUTOINCREMENT,
+    label                   TEXT    NOT NULL,
+    category                TEXT    NOT NULL DEFAULT 'common', -- monthly / common / income
+    help_text               TEXT    NOT NULL DEFAULT '',       -- 項目の説明
+    debit_account_id        INTEGER NOT NULL,
+    credit_account_id       INTEGER NOT NULL,
+    default_description     TEXT    NOT NULL DEFAULT '',
+    description_placeholder TEXT    NOT NULL DEFAULT '',
+    default_amount          INTEGER,                           -- NULL = 毎回入力
+    tax_category            TEXT    NOT NULL DEFAULT '',       -- 税区分 (課税仕入 10% 等)
+    sort_order              INTEGER NOT NULL DEFAULT 0,
+    FOREIGN KEY (debit_account_id) REFERENCES accounts(id),
+    FOREIGN KEY (credit_account_id) REFERENCES accounts(id),
+    CHECK (default_amount IS NULL OR default_amount > 0)
+);
+
+-- 従来のかんたん入力のプリセットを初期データとして登録する（科目が見つからないものは登録しない）
+INSERT INTO journal_templates (label, category, help_text, debit_account_id, credit_account_id,
+        default_description, description_placeholder, tax_category, sort_order)
+SELECT p.label, p.category, p.help_text, d.id, c.id, p.default_description, p.placeholder, p.tax_category, p.sort_order
+FROM (
+    SELECT 1 AS sort_order, '家賃（振込）' AS label, 'monthly' AS category,
+           '事務所・店舗の家賃を口座振込で支払い' AS help_text, 5300 AS debit_code, 1112 AS credit_code,
+           '家賃' AS default_description, '例: ○月分 事務所家賃' AS placeholder, '課税仕入 10%' AS tax_category
+    UNION ALL SELECT 2, '電気代', 'monthly', '電気料金を口座振替で支払い', 5700, 1112,
+           '電気代', '例: ○月分 電気代', '課税仕入 10%'
+    UNION ALL SELECT 3, 'ガス・水道代', 'monthly', 'ガス・水道料金を口座振替で支払い', 5700, 1112,
+           'ガス・水道代', '例: ○月分 水道代', '課税仕入 10%'
+    UNION ALL SELECT 4, '通信費（携帯・ネット）', 'monthly', '携帯電話やインターネット回線の月額料金を口座振替で支払い', 5400, 1112,
+           '通信費', '例: ○月分 携帯電話料金', '課税仕入 10%'
+    UNION ALL SELECT 5, '借入金の返済', 'monthly', '銀行などへの借入金を口座振替で返済', 2200, 1112,
+           '借入金返済', '例: ○月分 事業ローン返済', '対象外'
+    UNION ALL SELECT 6, '交通費（現金）', 'common', '電車・バス・タクシーなどの交通費を現金で支払い', 5500, 1111,
+           '交通費', '例: △△駅まで電車代', '課税仕入 10%'
+    UNION ALL SELECT 7, '交通費（口座）', 'common', '交通費をICカードチャージや口座引落で支払い', 5500, 1112,
+           '交通費', '例: ICカードチャージ', '課税仕入 10%'
+    UNION ALL SELECT 8, '消耗品（現金）', 'common', '文房具やUSBメモリなど少額の消耗品を現金で購入', 5600, 1111,
+           '消耗品購入', '例: コピー用紙、ボールペン', '課税仕入 10%'
+    UNION ALL SELECT 9, '消耗品（口座）', 'common', '消耗品をネット通販などで購入し口座から支払い', 5600, 1112,
+           '消耗品購入', '例: Amazon ○○購入', '課税仕入 10%'
+    UNION ALL SELECT 10, '接待・会食', 'common', '取引先との飲食代を現金で支払い', 5800, 1111,
+           '接待交際費', '例: ○○社 △△氏と会食', '課税仕入 10%'
+    UNION ALL SELECT 11, '外注費（振込）', 'common', '外部業者やフリーランスへの委託費用を口座振込で支払い', 5210, 1112,
+           '外注費', '例: ○○氏 デザイン制作費', '課税仕入 10%'
+    UNION ALL SELECT 12, '書籍・資料', 'common', '業務に必要な書籍や資料を現金で購入', 5930, 1111,
+           '新聞図書費', '例: ○○入門書', '課税仕入 10%'
+    UNION ALL SELECT 13, '振込手数料', 'common', '銀行の振込手数料が口座から引かれた', 5940, 1112,
+           '振込手数料', '例: ○月○日分 振込手数料', '課税仕入 10%'
+    UNION ALL SELECT 14, '自腹立替（事業主借）', 'common', '個人のお金で事業の経費を立て替えた場合', 5600, 3300,
+           '立替（事業主借）', '例: 個人カードで○○購入', '課税仕入 10%'
+    UNION ALL SELECT 15, '売上（振込入金）', 'income', 'お客さんからの代金が口座に振り込まれた', 1112, 4100,
+           '売上入金', '例: ○○社 △月分報酬', '課税売上 10%'
+    UNION ALL SELECT 16, '売上（現金入金）', 'income', 'お客さんから現金で代金を受け取った', 1111, 4100,
+           '売上入金', '例: 店頭売上', '課税売上 10%'
+    UNION ALL SELECT 17, '売上（売掛発生）', 'income', '請求書を送ったが、まだ入金されていない（ツケ）', 1131, 4100,
+           '売上計上（売掛）', '例: ○○社 △月分請求', '課税売上 10%'
+    UNION ALL SELECT 18, '売掛金の回収', 'income', '以前のツケが口座に振り込まれた', 1112, 1131,
+           '売掛金回収', '例: ○○社 △月分入金', '対象外'
+) p
+JOIN accounts d ON d.code = p.debit_code
+JOIN accounts c ON c.code = p.credit_code
+ORDER BY p.sort_order;
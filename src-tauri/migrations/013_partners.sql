@@ -0,0 +1,18 @@
+-- 取引先マスタ
+CREATE TABLE IF NOT EXISTS partners (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    name            TEXT    NOT NULL,
+    kana            TEXT    NOT NULL DEFAULT '',      -- フリガナ（並び順・検索用）
+    address         TEXT    NOT NULL DEFAULT '',
+    invoice_number  TEXT    NOT NULL DEFAULT '',      -- インボイス登録番号 (T + 13桁)
+    partner_type    TEXT    NOT NULL DEFAULT '両方',  -- 得意先/仕入先/両方
+    memo            TEXT    NOT NULL DEFAULT '',
+    created_at      TEXT    NOT NULL DEFAULT (datetime('now', 'localtime')),
+    CHECK (partner_type IN ('得意先', '仕入先', '両方'))
+);
+
+-- 仕訳・地代家賃の内訳から取引先を参照する（NULL = 取引先なし）
+ALTER TABLE journal_entries ADD COLUMN partner_id INTEGER REFERENCES partners(id);
+ALTER TABLE rent_details ADD COLUMN partner_id INTEGER REFERENCES partners(id);
+
+CREATE INDEX IF NOT EXISTS idx_journal_entries_partner ON journal_entries(partner_id);
@@ -0,0 +1,14 @@
+-- 補助科目（普通預金の口座別など、勘定科目をさらに細分する）
+CREATE TABLE IF NOT EXISTS sub_accounts (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    account_id  INTEGER NOT NULL,
+    name        TEXT    NOT NULL,
+    is_active   INTEGER NOT NULL DEFAULT 1,
+    FOREIGN KEY (account_id) REFERENCES accounts(id),
+    UNIQUE (account_id, name)
+);
+
+-- 仕訳明細の補助科目（NULL = 補助科目なし）
+ALTER TABLE journal_lines ADD COLUMN sub_account_id INTEGER REFERENCES sub_accounts(id);
+
+CREATE INDEX IF NOT EXISTS idx_journal_lines_sub_account ON journal_lines(sub_account_id);
@@ -0,0 +1,15 @@
+-- セグメント（部門・プロジェクト・任意のタグ）
+CREATE TABLE IF NOT EXISTS segments (
+    id            INTEGER PRIMARY KEY AUTOINCREMENT,
+    name          TEXT    NOT NULL UNIQUE,
+    segment_type  TEXT    NOT NULL DEFAULT 'プロジェクト',  -- 部門/プロジェクト/タグ
+    memo          TEXT    NOT NULL DEFAULT '',
+    is_active     INTEGER NOT NULL DEFAULT 1,
+    created_at    TEXT    NOT NULL DEFAULT (datetime('now', 'localtime')),
+    CHECK (segment_type IN ('部門', 'プロジェクト', 'タグ'))
+);
+
+-- 仕訳のセグメント（NULL = 未割当）
+ALTER TABLE journal_entries ADD COLUMN segment_id INTEGER REFERENCES segments(id);
+
+CREATE INDEX IF NOT EXISTS idx_journal_entries_segment ON journal_entries(segment_id);
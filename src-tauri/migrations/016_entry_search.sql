@@ -0,0 +1,31 @@
+-- 仕訳の摘要の全文検索インデックス（trigram: 日本語を分かち書きせずに部分一致で検索する）
+CREATE VIRTUAL TABLE IF NOT EXISTS journal_entries_fts USING fts5(
+    description,
+    content = 'journal_entries',
+    content_rowid = 'id',
+    tokenize = 'trigram'
+);
+
+INSERT INTO journal_entries_fts (journal_entries_fts) VALUES ('rebuild');
+
+CREATE TRIGGER IF NOT EXISTS journal_entries_fts_insert
+AFTER INSERT ON journal_entries
+BEGIN
+    INSERT INTO journal_entries_fts (rowid, description) VALUES (NEW.id, NEW.description);
+END;
+
+CREATE TRIGGER IF NOT EXISTS journal_entries_fts_update
+AFTER UPDATE OF description ON journal_entries
+BEGIN
+    INSERT INTO journal_entries_fts (journal_entries_fts, rowid, description) VALUES ('delete', OLD.id, OLD.description);
+    INSERT INTO journal_entries_fts (rowid, description) VALUES (NEW.id, NEW.description);
+END;
+
+CREATE TRIGGER IF NOT EXISTS journal_entries_fts_delete
+AFTER DELETE ON journal_entries
+BEGIN
+    INSERT INTO journal_entries_fts (journal_entries_fts, rowid, description) VALUES ('delete', OLD.id, OLD.description);
+END;
+
+-- 金額範囲での検索用
+CREATE INDEX IF NOT EXISTS idx_journal_entries_amount ON journal_entries(debit_amount);
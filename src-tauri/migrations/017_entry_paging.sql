@@ -0,0 +1,3 @@
+-- 仕訳帳のページ送り・並び替え用（期間での絞り込みと日付順・登録順の並び替え）
+CREATE INDEX IF NOT EXISTS idx_journal_entries_date ON journal_entries(date, id);
+CREATE INDEX IF NOT EXISTS idx_journal_entries_created ON journal_entries(created_at, id);
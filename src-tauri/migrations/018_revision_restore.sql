@@ -0,0 +1,37 @@
+-- 取消した仕訳の復元（元に戻す操作）を訂正・削除履歴に記録できるようにする
+-- CHECK 制約は変更できないため、履歴テーブルを作り直して内容を移す
+DROP TRIGGER IF EXISTS trg_entry_revisions_no_update;
+DROP TRIGGER IF EXISTS trg_entry_revisions_no_delete;
+
+CREATE TABLE journal_entry_revisions_new (
+    id           INTEGER PRIMARY KEY AUTOINCREMENT,
+    entry_id     INTEGER NOT NULL,
+    revision_no  INTEGER NOT NULL,                -- 仕訳ごとの版番号 (1始まり)
+    action       TEXT    NOT NULL CHECK (action IN ('登録', '訂正', '取消', '復元')),
+    before_json  TEXT,                            -- 変更前の内容（登録・復元時は NULL）
+    after_json   TEXT,                            -- 変更後の内容（取消時は NULL）
+    changed_by   TEXT    NOT NULL DEFAULT '',
+    changed_at   TEXT    NOT NULL DEFAULT (datetime('now', 'localtime')),
+    FOREIGN KEY (entry_id) REFERENCES journal_entries(id),
+    UNIQUE (entry_id, revision_no)
+);
+
+INSERT INTO journal_entry_revisions_new
+       (id, entry_id, revision_no, action, before_json, after_json, changed_by, changed_at)
+SELECT id, entry_id, revision_no, action, before_json, after_json, changed_by, changed_at
+FROM journal_entry_revisions;
+
+DROP TABLE journal_entry_revisions;
+ALTER TABLE journal_entry_revisions_new RENAME TO journal_entry_revisions;
+
+CREATE TRIGGER IF NOT EXISTS trg_entry_revisions_no_update
+BEFORE UPDATE ON journal_entry_revisions
+BEGIN
+    SELECT RAISE(ABORT, '訂正・削除履歴は変更できません');
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_entry_revisions_no_delete
+BEFORE DELETE ON journal_entry_revisions
+BEGIN
+    SELECT RAISE(ABORT, '訂正・削除履歴は削除できません');
+END;
@@ -0,0 +1,2 @@
+-- 勘定科目の使用状態（0 = 非表示。過去の仕訳・帳票には残るが入力時の選択肢に出さない）
+ALTER TABLE accounts ADD COLUMN is_active INTEGER NOT NULL DEFAULT 1;
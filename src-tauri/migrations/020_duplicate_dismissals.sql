@@ -0,0 +1,10 @@
+-- 「重複ではない」と確認した仕訳の組（entry_id < duplicate_id の順で保存する）
+CREATE TABLE IF NOT EXISTS duplicate_dismissals (
+    entry_id      INTEGER NOT NULL,
+    duplicate_id  INTEGER NOT NULL,
+    created_at    TEXT    NOT NULL DEFAULT (datetime('now', 'localtime')),
+    PRIMARY KEY (entry_id, duplicate_id),
+    FOREIGN KEY (entry_id) REFERENCES journal_entries(id),
+    FOREIGN KEY (duplicate_id) REFERENCES journal_entries(id),
+    CHECK (entry_id < duplicate_id)
+);
@@ -0,0 +1,36 @@
+-- 証憑（領収書・請求書などのファイル）
+-- ファイルはデータフォルダの attachments/ に「SHA-256 + 拡張子」の名前でコピーし、登録後は内容を変更・削除できない
+CREATE TABLE IF NOT EXISTS attachments (
+    id             INTEGER PRIMARY KEY AUTOINCREMENT,
+    file_name      TEXT    NOT NULL,                -- 元のファイル名
+    stored_name    TEXT    NOT NULL UNIQUE,         -- attachments/ 内のファイル名
+    mime_type      TEXT    NOT NULL,
+    size           INTEGER NOT NULL,
+    sha256         TEXT    NOT NULL UNIQUE,         -- 登録時のファイルのハッシュ（16進小文字）
+    received_date  TEXT    NOT NULL,                -- 受領日
+    memo           TEXT    NOT NULL DEFAULT '',
+    created_at     TEXT    NOT NULL DEFAULT (datetime('now', 'localtime'))
+);
+
+CREATE TRIGGER IF NOT EXISTS trg_attachments_no_update
+BEFORE UPDATE OF file_name, stored_name, mime_type, size, sha256, received_date, created_at ON attachments
+BEGIN
+    SELECT RAISE(ABORT, '証憑の登録内容は変更できません');
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_attachments_no_delete
+BEFORE DELETE ON attachments
+BEGIN
+    SELECT RAISE(ABORT, '証憑は削除できません');
+END;
+
+-- 証憑と仕訳の対応（1つの証憑を複数の仕訳に、1つの仕訳に複数の証憑を紐付けられる）
+CREATE TABLE IF NOT EXISTS entry_attachments (
+    entry_id       INTEGER NOT NULL,
+    attachment_id  INTEGER NOT NULL,
+    PRIMARY KEY (entry_id, attachment_id),
+    FOREIGN KEY (entry_id) REFERENCES journal_entries(id),
+    FOREIGN KEY (attachment_id) REFERENCES attachments(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_entry_attachments_attachment ON entry_attachments(attachment_id);
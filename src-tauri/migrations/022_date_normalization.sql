@@ -0,0 +1,87 @@
+-- 保存済みの日付を YYYY-MM-DD にそろえる
+-- 2024/3/1・2024.3.1・2024-3-1 のように区切りや桁数が違うだけの値は直し、
+-- 2024-02-30 のように暦にない日付や読み取れない値はそのまま残す（get_invalid_dates で一覧できる）
+-- 証憑の受領日は変更できないため対象外
+-- 仕訳の日付を直した場合は、訂正・削除履歴に変更前・変更後の内容を記録する
+CREATE TEMP TABLE date_fix AS
+WITH malformed (tbl, id, col, value) AS (
+    SELECT 'journal_entries', id, 'date', date
+    FROM journal_entries WHERE date(date) IS NOT date
+    UNION ALL
+    SELECT 'fixed_assets', id, 'acquisition_date', acquisition_date
+    FROM fixed_assets WHERE date(acquisition_date) IS NOT acquisition_date
+    UNION ALL
+    SELECT 'recurring_entries', id, 'start_date', start_date
+    FROM recurring_entries WHERE date(start_date) IS NOT start_date
+    UNION ALL
+    SELECT 'recurring_entries', id, 'end_date', end_date
+    FROM recurring_entries WHERE end_date IS NOT NULL AND date(end_date) IS NOT end_date
+),
+-- 区切りを '-' にそろえ、末尾にも '-' を付けて年・月・日を順に切り出す
+year_part AS (
+    SELECT tbl, id, col, value, t, substr(t, 1, instr(t, '-') - 1) AS y, substr(t, instr(t, '-') + 1) AS rest
+    FROM (SELECT tbl, id, col, value, replace(replace(trim(value), '/', '-'), '.', '-') || '-' AS t FROM malformed)
+),
+month_part AS (
+    SELECT tbl, id, col, value, y, substr(rest, 1, instr(rest, '-') - 1) AS m, substr(rest, instr(rest, '-') + 1) AS rest
+    FROM year_part
+),
+day_part AS (
+    SELECT tbl, id, col, value, y, m, substr(rest, 1, instr(rest, '-') - 1) AS d, substr(rest, instr(rest, '-') + 1) AS tail
+    FROM month_part
+)
+SELECT tbl, id, col, value, printf('%04d-%02d-%02d', y, m, d) AS fixed
+FROM day_part
+WHERE length(y) = 4 AND length(m) BETWEEN 1 AND 2 AND length(d) BETWEEN 1 AND 2 AND tail = ''
+  AND (y || m || d) NOT GLOB '*[^0-9]*';
+
+-- 暦にない日付（2024-2-30 など）は直さない
+DELETE FROM date_fix WHERE date(fixed) IS NOT fixed;
+
+INSERT INTO journal_entry_revisions (entry_id, revision_no, action, before_json, after_json)
+SELECT id,
+       (SELECT COALESCE(MAX(r.revision_no), 0) + 1 FROM journal_entry_revisions r WHERE r.entry_id = s.id),
+       '訂正',
+       json_set(snapshot, '$.date', value),
+       json_set(snapshot, '$.date', fixed)
+FROM (
+    SELECT j.id, f.value, f.fixed,
+           json_object(
+               'date', j.date,
+               'description', j.description,
+               'is_compound', json(CASE WHEN j.is_compound THEN 'true' ELSE 'false' END),
+               'partner_id', j.partner_id,
+               'segment_id', j.segment_id,
+               'lines', (
+                   SELECT json_group_array(json(x.line))
+                   FROM (
+                       SELECT json_object('side', l.side, 'account_id', l.account_id,
+                                          'sub_account_id', l.sub_account_id, 'amount', l.amount) AS line
+                       FROM journal_lines l
+                       WHERE l.entry_id = j.id
+                       ORDER BY l.line_no
+                   ) x
+               )
+           ) AS snapshot
+    FROM date_fix f
+    JOIN journal_entries j ON j.id = f.id
+    WHERE f.tbl = 'journal_entries'
+) s;
+
+UPDATE journal_entries
+SET date = (SELECT fixed FROM date_fix f WHERE f.tbl = 'journal_entries' AND f.id = journal_entries.id)
+WHERE id IN (SELECT id FROM date_fix WHERE tbl = 'journal_entries');
+
+UPDATE fixed_assets
+SET acquisition_date = (SELECT fixed FROM date_fix f WHERE f.tbl = 'fixed_assets' AND f.id = fixed_assets.id)
+WHERE id IN (SELECT id FROM date_fix WHERE tbl = 'fixed_assets');
+
+UPDATE recurring_entries
+SET start_date = (SELECT fixed FROM date_fix f WHERE f.tbl = 'recurring_entries' AND f.col = 'start_date' AND f.id = recurring_entries.id)
+WHERE id IN (SELECT id FROM date_fix WHERE tbl = 'recurring_entries' AND col = 'start_date');
+
+UPDATE recurring_entries
+SET end_date = (SELECT fixed FROM date_fix f WHERE f.tbl = 'recurring_entries' AND f.col = 'end_date' AND f.id = recurring_entries.id)
+WHERE id IN (SELECT id FROM date_fix WHERE tbl = 'recurring_entries' AND col = 'end_date');
+
+DROP TABLE date_fix;
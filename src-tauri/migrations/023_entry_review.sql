@@ -0,0 +1,7 @@
+-- 仕訳の確認状況と、摘要とは別に仕訳帳へ出力するメモ
+-- 既存の仕訳は確認済みとして扱う
+ALTER TABLE journal_entries ADD COLUMN review_status TEXT NOT NULL DEFAULT '確認済'
+    CHECK (review_status IN ('未確認', '確認済', '要質問'));
+ALTER TABLE journal_entries ADD COLUMN note TEXT NOT NULL DEFAULT '';
+
+CREATE INDEX IF NOT EXISTS idx_journal_entries_review ON journal_entries(review_status);
@@ -0,0 +1,18 @@
+-- 家事按分のルール（科目ごとの事業割合。家事分は年末に事業主貸へ振り替える）
+CREATE TABLE IF NOT EXISTS apportionment_rules (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    account_id      INTEGER NOT NULL UNIQUE REFERENCES accounts(id) ON DELETE CASCADE,
+    business_ratio  INTEGER NOT NULL,                -- 事業割合（%）
+    basis           TEXT    NOT NULL DEFAULT '',     -- 按分の根拠（床面積・使用時間など）
+    created_at      TEXT    NOT NULL DEFAULT (datetime('now', 'localtime')),
+    CHECK (business_ratio BETWEEN 0 AND 100)
+);
+
+-- 家事按分で作成した振替仕訳（作り直すときに取り消す対象。取消済みの仕訳も残す）
+CREATE TABLE IF NOT EXISTS apportionment_entries (
+    entry_id    INTEGER PRIMARY KEY REFERENCES journal_entries(id),
+    year        INTEGER NOT NULL,
+    account_id  INTEGER NOT NULL                     -- 按分した費用科目
+);
+
+CREATE INDEX IF NOT EXISTS idx_apportionment_entries_year ON apportionment_entries(year);
@@ -0,0 +1,27 @@
+-- 外貨建取引（currency が NULL の仕訳は円建て）
+-- 円換算額は debit_amount / credit_amount に保存し、外貨額と換算レートは記録として残す
+-- 浮動小数点の誤差を避けるため、外貨額は100倍、レートは10000倍した整数で保存する
+ALTER TABLE journal_entries ADD COLUMN currency TEXT;             -- 通貨コード（USD など）
+ALTER TABLE journal_entries ADD COLUMN foreign_amount INTEGER;    -- 外貨額 × 100（小数2桁まで）
+ALTER TABLE journal_entries ADD COLUMN exchange_rate INTEGER;     -- 1通貨単位あたりの円 × 10000（小数4桁まで）
+-- 外貨建ての科目がある側（外貨預金で円の売上を受け取るなら 借方、外貨預金同士の振替なら 両方）
+-- 外貨残高の集計では、この側の科目にだけ外貨額を計上する
+ALTER TABLE journal_entries ADD COLUMN foreign_side TEXT CHECK (foreign_side IN ('借方', '貸方', '両方'));
+
+CREATE INDEX IF NOT EXISTS idx_journal_entries_currency ON journal_entries(currency);
+
+-- 為替レート（TTM。CSV から取り込む）
+CREATE TABLE IF NOT EXISTS exchange_rates (
+    currency    TEXT    NOT NULL,
+    date        TEXT    NOT NULL,
+    ttm         INTEGER NOT NULL,                    -- 1通貨単位あたりの円 × 10000
+    imported_at TEXT    NOT NULL DEFAULT (datetime('now', 'localtime')),
+    PRIMARY KEY (currency, date),
+    CHECK (ttm > 0)
+);
+
+-- 外貨建取引で使う科目
+INSERT OR IGNORE INTO accounts (code, name, classification) VALUES
+    (1114, '外貨預金', '資産'),
+    (4300, '為替差益', '収益'),
+    (5960, '為替差損', '費用');
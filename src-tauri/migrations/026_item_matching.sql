@@ -0,0 +1,41 @@
+-- 消込の対象科目（売掛金・立替金・仮払金）はコードではなく is_matching で判定する
+-- （利用者が 1132・1133 に別の科目を作っていても、その科目を消込の対象にしない）
+ALTER TABLE accounts ADD COLUMN is_matching INTEGER NOT NULL DEFAULT 0;
+
+UPDATE accounts SET is_matching = 1
+WHERE classification = '資産' AND name IN ('売掛金', '立替金', '仮払金');
+
+-- 立替金・仮払金がなければ追加する（既定のコードが使われていれば、その後ろで最初に空いているコードにする）
+INSERT INTO accounts (code, name, classification, is_matching)
+SELECT CASE
+           WHEN NOT EXISTS (SELECT 1 FROM accounts WHERE code = 1132) THEN 1132
+           ELSE (SELECT MIN(a.code) + 1 FROM accounts a
+                 WHERE a.code >= 1132 AND NOT EXISTS (SELECT 1 FROM accounts b WHERE b.code = a.code + 1))
+       END,
+       '立替金', '資産', 1
+WHERE NOT EXISTS (SELECT 1 FROM accounts WHERE name = '立替金' AND classification = '資産');
+
+INSERT INTO accounts (code, name, classification, is_matching)
+SELECT CASE
+           WHEN NOT EXISTS (SELECT 1 FROM accounts WHERE code = 1133) THEN 1133
+           ELSE (SELECT MIN(a.code) + 1 FROM accounts a
+                 WHERE a.code >= 1133 AND NOT EXISTS (SELECT 1 FROM accounts b WHERE b.code = a.code + 1))
+       END,
+       '仮払金', '資産', 1
+WHERE NOT EXISTS (SELECT 1 FROM accounts WHERE name = '仮払金' AND classification = '資産');
+
+-- 消込（借方に計上した債権・立替・仮払を、後日の入金・精算の貸方と結び付ける。一部ずつの消込も可）
+-- 仕訳を取り消しても消込は残し、集計では取消済みの仕訳との消込を除く
+CREATE TABLE IF NOT EXISTS item_matches (
+    id               INTEGER PRIMARY KEY AUTOINCREMENT,
+    account_id       INTEGER NOT NULL REFERENCES accounts(id),
+    debit_entry_id   INTEGER NOT NULL REFERENCES journal_entries(id),
+    credit_entry_id  INTEGER NOT NULL REFERENCES journal_entries(id),
+    amount           INTEGER NOT NULL,
+    created_at       TEXT    NOT NULL DEFAULT (datetime('now', 'localtime')),
+    CHECK (amount > 0),
+    CHECK (debit_entry_id <> credit_entry_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_item_matches_debit ON item_matches(debit_entry_id);
+CREATE INDEX IF NOT EXISTS idx_item_matches_credit ON item_matches(credit_entry_id);
@@ -0,0 +1,10 @@
+-- 地代家賃の内訳を決算書の年度ごとに持つ（これまでは全年度の決算書に同じ内訳が載っていた）
+ALTER TABLE rent_details ADD COLUMN year INTEGER NOT NULL DEFAULT 0;
+
+-- 既存の内訳は、仕訳のある最新の年度（仕訳がなければ今年）の内訳とする
+UPDATE rent_details SET year = COALESCE(
+    (SELECT CAST(substr(MAX(date), 1, 4) AS INTEGER) FROM journal_entries WHERE voided_at IS NULL),
+    CAST(strftime('%Y', 'now', 'localtime') AS INTEGER)
+);
+
+CREATE INDEX IF NOT EXISTS idx_rent_details_year ON rent_details(year);
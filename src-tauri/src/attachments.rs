@@ -0,0 +1,96 @@
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// データフォルダ内の証憑の保存先
+pub fn attachments_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = app_handle
+        .path_resolver()
+        .app_data_dir()
+        .ok_or("failed to resolve app data dir")?
+        .join("attachments");
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+/// ファイルの SHA-256（16進小文字）
+pub fn sha256_file(path: &Path) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// 拡張子から MIME タイプを判定する（証憑として扱う形式以外は application/octet-stream）
+pub fn mime_type(path: &Path) -> &'static str {
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_ascii_lowercase();
+    match ext.as_str() {
+        "pdf" => "application/pdf",
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "heic" => "image/heic",
+        "tif" | "tiff" => "image/tiff",
+        "txt" => "text/plain",
+        "csv" => "text/csv",
+        "xml" => "application/xml",
+        "html" | "htm" => "text/html",
+        _ => "application/octet-stream",
+    }
+}
+
+/// 保存する前のファイルの情報（保存先のファイル名はハッシュから決める）
+pub struct StoredFile {
+    pub file_name: String,
+    pub stored_name: String,
+    pub mime_type: String,
+    pub size: i64,
+    pub sha256: String,
+}
+
+/// 証憑として保存するファイルのハッシュ・保存名などを求める（まだコピーはしない）
+pub fn inspect_file(source: &Path) -> Result<StoredFile, String> {
+    let file_name = source
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or("ファイル名を取得できません")?
+        .to_string();
+    let sha256 = sha256_file(source).map_err(|e| format!("ファイルを読み込めません: {}", e))?;
+    let stored_name = match source.extension().and_then(|e| e.to_str()) {
+        Some(ext) => format!("{}.{}", sha256, ext.to_ascii_lowercase()),
+        None => sha256.clone(),
+    };
+    let size = std::fs::metadata(source).map_err(|e| e.to_string())?.len() as i64;
+    Ok(StoredFile { file_name, stored_name, mime_type: mime_type(source).to_string(), size, sha256 })
+}
+
+/// ファイルを stored_name で保存先にコピーする（同じ内容のファイルが保存済みならコピーしない）
+/// 保存済みのファイルが無い・ハッシュが一致しない場合は上書きして復元する
+pub fn store_file(dir: &Path, source: &Path, stored_name: &str, sha256: &str) -> Result<(), String> {
+    let target = dir.join(stored_name);
+    if verify(dir, stored_name, sha256) != "正常" {
+        // 途中で失敗しても壊れたファイルが残らないよう、一時ファイルに書いてから名前を変える
+        let partial = dir.join(format!("{}.partial", stored_name));
+        std::fs::copy(source, &partial).map_err(|e| e.to_string())?;
+        std::fs::rename(&partial, &target).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// 保存済みのファイルが登録時のハッシュと一致するか（"正常" / "不一致" / "ファイルなし"）
+pub fn verify(dir: &Path, stored_name: &str, sha256: &str) -> &'static str {
+    match sha256_file(&dir.join(stored_name)) {
+        Ok(actual) if actual == sha256 => "正常",
+        Ok(_) => "不一致",
+        Err(_) => "ファイルなし",
+    }
+}
@@ -1,15 +1,112 @@
+use std::collections::HashSet;
 use std::sync::Mutex;
 
 use rusqlite::Connection;
 use tauri::State;
 
+use crate::attachments;
 use crate::db;
+use crate::history::{Operation, UndoHistory, UndoStep};
 use crate::models::*;
 
 pub struct DbState(pub Mutex<Connection>);
 
+/// 元に戻す・やり直しの履歴（DbState のロックを取ってから取得する）
+pub struct HistoryState(pub Mutex<UndoHistory>);
+
+// ── 締め済みチェック ──
+
+/// 年度締めが済んでいる年度があればエラーにする
+/// （締めた年度を変えると翌年の繰越期首残高と合わなくなるため、先に年度締めを取り消す必要がある）
+fn ensure_years_not_closed(conn: &Connection, from_year: i32, to_year: i32) -> Result<(), String> {
+    if let Some(year) = db::find_closed_year(conn, from_year, to_year).map_err(|e| e.to_string())? {
+        return Err(format!("{}年は年度締め済みのため変更できません。先に年度締めを取り消してください", year));
+    }
+    Ok(())
+}
+
+/// 日付が締め済みの月・年度に含まれていればエラーにする
+fn ensure_date_open(conn: &Connection, date: Date) -> Result<(), String> {
+    let (year, month) = (date.year(), date.month());
+    if db::is_period_locked(conn, year, month).map_err(|e| e.to_string())? {
+        return Err(format!("{}年{}月は締め済みのため変更できません", year, month));
+    }
+    ensure_years_not_closed(conn, year, year)
+}
+
+/// from_year〜to_year にロックされた年度があればエラーにする（年度締めは見ない）
+fn ensure_years_unlocked(conn: &Connection, from_year: i32, to_year: i32) -> Result<(), String> {
+    if let Some(year) = db::find_locked_year(conn, from_year, to_year).map_err(|e| e.to_string())? {
+        return Err(format!("{}年は締め済みのため変更できません", year));
+    }
+    Ok(())
+}
+
+/// from_year〜to_year にロックされた年度・年度締め済みの年度があればエラーにする
+fn ensure_years_open(conn: &Connection, from_year: i32, to_year: i32) -> Result<(), String> {
+    ensure_years_unlocked(conn, from_year, to_year)?;
+    ensure_years_not_closed(conn, from_year, to_year)
+}
+
+/// 保存済みの日付が締め済みでないか確認する
+/// 形式が正しくない日付は締め済みの期間に含めない（get_invalid_dates で一覧した行を直せるようにするため）
+fn ensure_stored_date_open(conn: &Connection, date: &str) -> Result<(), String> {
+    match Date::parse(date) {
+        Ok(date) => ensure_date_open(conn, date),
+        Err(_) => Ok(()),
+    }
+}
+
+/// 保存済みの取得日以降に締め済みの年度がないか確認する（取得日の形式が正しくなければ確認しない）
+fn ensure_acquisition_years_open(conn: &Connection, acquisition_date: &str) -> Result<(), String> {
+    match Date::parse(acquisition_date) {
+        Ok(date) => ensure_years_open(conn, date.year(), i32::MAX),
+        Err(_) => Ok(()),
+    }
+}
+
+/// 既存仕訳の日付が締め済みでないか確認する
+fn ensure_entry_open(conn: &Connection, id: i64) -> Result<(), String> {
+    let date = db::fetch_entry_date(conn, id)
+        .map_err(|e| e.to_string())?
+        .ok_or("仕訳が見つかりません")?;
+    ensure_stored_date_open(conn, &date)
+}
+
 // ── 勘定科目 ──
 
+/// 集計・決算書の計算で科目コードを直接参照している科目
+/// （元入金・事業主貸・事業主借・売上高・仕入高・為替差益・為替差損。
+/// これらと消込の対象科目は、コード・区分の変更、非表示、削除、統合元への指定はできない）
+const SYSTEM_ACCOUNT_CODES: &[i32] = &[3100, 3200, 3300, 4100, 5100, 4300, 5960];
+
+fn ensure_not_system_account(account: &Account, action: &str) -> Result<(), String> {
+    if SYSTEM_ACCOUNT_CODES.contains(&account.code) || account.is_matching {
+        return Err(format!("「{}」は集計で使用する科目のため{}できません", account.name, action));
+    }
+    Ok(())
+}
+
+fn fetch_account(conn: &Connection, id: i64) -> Result<Account, String> {
+    db::fetch_account(conn, id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "勘定科目が見つかりません".to_string())
+}
+
+/// 科目を使用している仕訳の年月・期首残高の年度がすべて締めていないことを確認する
+/// （区分の変更や統合は過去の決算書の数字を変えるため）
+fn ensure_account_years_open(conn: &Connection, id: i64) -> Result<(), String> {
+    if let Some((from_year, to_year)) = db::fetch_account_year_range(conn, id).map_err(|e| e.to_string())? {
+        ensure_years_open(conn, from_year, to_year)?;
+    }
+    for (year, month) in db::fetch_account_entry_months(conn, id).map_err(|e| e.to_string())? {
+        if let Some((first, _)) = Date::period(year, Some(month)) {
+            ensure_date_open(conn, first)?;
+        }
+    }
+    Ok(())
+}
+
 #[tauri::command]
 pub fn get_accounts(state: State<DbState>) -> Result<Vec<Account>, String> {
     let conn = state.0.lock().map_err(|e| e.to_string())?;
@@ -19,12 +116,278 @@ pub fn get_accounts(state: State<DbState>) -> Result<Vec<Account>, String> {
 #[tauri::command]
 pub fn add_account(
     state: State<DbState>,
+    history: State<HistoryState>,
     code: i32,
     name: String,
     classification: String,
 ) -> Result<Account, String> {
     let conn = state.0.lock().map_err(|e| e.to_string())?;
-    db::insert_account(&conn, code, &name, &classification).map_err(|e| e.to_string())
+    let account = db::insert_account(&conn, code, &name, &classification).map_err(|e| e.to_string())?;
+    record(
+        &history,
+        format!("勘定科目の追加（{} {}）", account.code, account.name),
+        vec![Operation::AddAccount(account.clone())],
+    )?;
+    Ok(account)
+}
+
+#[tauri::command]
+pub fn update_account(
+    state: State<DbState>,
+    history: State<HistoryState>,
+    id: i64,
+    code: i32,
+    name: String,
+    classification: String,
+    is_active: bool,
+) -> Result<(), String> {
+    if code <= 0 {
+        return Err("科目コードは正の整数を入力してください".to_string());
+    }
+    if name.trim().is_empty() {
+        return Err("科目名を入力してください".to_string());
+    }
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let current = fetch_account(&conn, id)?;
+    if code != current.code || classification != current.classification {
+        ensure_not_system_account(&current, "科目コード・区分を変更")?;
+    }
+    if !is_active {
+        ensure_not_system_account(&current, "非表示に")?;
+    }
+    if code != current.code {
+        let accounts = db::fetch_accounts(&conn).map_err(|e| e.to_string())?;
+        if SYSTEM_ACCOUNT_CODES.contains(&code) || accounts.iter().any(|a| a.code == code) {
+            return Err(format!("科目コード {} は既に使われています", code));
+        }
+    }
+    if classification != current.classification {
+        ensure_account_years_open(&conn, id)?;
+    }
+    db::update_account(&conn, id, code, name.trim(), &classification, is_active).map_err(|e| e.to_string())?;
+    record(
+        &history,
+        format!("勘定科目の変更（{} {}）", current.code, current.name),
+        vec![Operation::UpdateAccount(current)],
+    )
+}
+
+#[tauri::command]
+pub fn delete_account(state: State<DbState>, history: State<HistoryState>, id: i64) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let account = fetch_account(&conn, id)?;
+    ensure_not_system_account(&account, "削除")?;
+    let references = db::count_account_references(&conn, id).map_err(|e| e.to_string())?;
+    if references > 0 {
+        return Err(format!(
+            "この勘定科目は{}件使用されているため削除できません（非表示にするか、他の科目に統合してください）",
+            references
+        ));
+    }
+    db::delete_account(&conn, id).map_err(|e| e.to_string())?;
+    record(
+        &history,
+        format!("勘定科目の削除（{} {}）", account.code, account.name),
+        vec![Operation::DeleteAccount(account)],
+    )
+}
+
+/// 勘定科目 from_id を to_id に統合する（付け替えた仕訳の件数を返す）
+/// 統合は元に戻せず、それより前の操作も統合後のデータとは食い違うため、元に戻す・やり直しの履歴を消す
+#[tauri::command]
+pub fn merge_accounts(
+    state: State<DbState>,
+    history: State<HistoryState>,
+    from_id: i64,
+    to_id: i64,
+) -> Result<usize, String> {
+    if from_id == to_id {
+        return Err("統合元と統合先に同じ科目は指定できません".to_string());
+    }
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let from = fetch_account(&conn, from_id)?;
+    let to = fetch_account(&conn, to_id)?;
+    ensure_not_system_account(&from, "統合元に")?;
+    if from.classification != to.classification {
+        return Err(format!(
+            "区分が異なる科目には統合できません（{}: {}、{}: {}）",
+            from.name, from.classification, to.name, to.classification
+        ));
+    }
+    ensure_account_years_open(&conn, from_id)?;
+    let changed = db::merge_accounts(&conn, from_id, to_id).map_err(|e| e.to_string())?;
+    history.0.lock().map_err(|e| e.to_string())?.clear();
+    Ok(changed)
+}
+
+// ── 補助科目 ──
+
+/// 補助科目が指定された勘定科目に属しているか確認する
+fn ensure_sub_account(conn: &Connection, account_id: i64, sub_account_id: Option<i64>) -> Result<(), String> {
+    let Some(sub_account_id) = sub_account_id else {
+        return Ok(());
+    };
+    let parent = db::fetch_sub_account_parent(conn, sub_account_id)
+        .map_err(|e| e.to_string())?
+        .ok_or("補助科目が見つかりません")?;
+    if parent != account_id {
+        return Err("補助科目が勘定科目に属していません".to_string());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_sub_accounts(state: State<DbState>, account_id: Option<i64>) -> Result<Vec<SubAccount>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::fetch_sub_accounts(&conn, account_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn add_sub_account(state: State<DbState>, account_id: i64, name: String) -> Result<i64, String> {
+    if name.trim().is_empty() {
+        return Err("補助科目名を入力してください".to_string());
+    }
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::insert_sub_account(&conn, account_id, name.trim()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn update_sub_account(state: State<DbState>, id: i64, name: String, is_active: bool) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("補助科目名を入力してください".to_string());
+    }
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::update_sub_account(&conn, id, name.trim(), is_active).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_sub_account(state: State<DbState>, id: i64) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    if db::count_sub_account_references(&conn, id).map_err(|e| e.to_string())? > 0 {
+        return Err("この補助科目は仕訳で使用されているため削除できません（無効にしてください）".to_string());
+    }
+    db::delete_sub_account(&conn, id).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// ── 取引先 ──
+
+/// 取引先の入力内容を検証する（インボイス登録番号は T + 13桁の数字）
+fn validate_partner(name: &str, invoice_number: &str, partner_type: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("取引先名を入力してください".to_string());
+    }
+    if !matches!(partner_type, "得意先" | "仕入先" | "両方") {
+        return Err(format!("不正な取引先区分です: {}", partner_type));
+    }
+    if !invoice_number.is_empty() {
+        let digits = invoice_number.strip_prefix('T').unwrap_or("");
+        if digits.len() != 13 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err("インボイス登録番号は T + 13桁の数字で入力してください".to_string());
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_partners(state: State<DbState>) -> Result<Vec<Partner>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::fetch_partners(&conn).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn add_partner(
+    state: State<DbState>,
+    name: String,
+    kana: String,
+    address: String,
+    invoice_number: String,
+    partner_type: String,
+    memo: String,
+) -> Result<i64, String> {
+    validate_partner(&name, &invoice_number, &partner_type)?;
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::insert_partner(&conn, name.trim(), &kana, &address, &invoice_number, &partner_type, &memo)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn update_partner(
+    state: State<DbState>,
+    id: i64,
+    name: String,
+    kana: String,
+    address: String,
+    invoice_number: String,
+    partner_type: String,
+    memo: String,
+) -> Result<(), String> {
+    validate_partner(&name, &invoice_number, &partner_type)?;
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::update_partner(&conn, id, name.trim(), &kana, &address, &invoice_number, &partner_type, &memo)
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_partner(state: State<DbState>, id: i64) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    if db::count_partner_references(&conn, id).map_err(|e| e.to_string())? > 0 {
+        return Err("この取引先は仕訳または地代家賃の内訳で使用されているため削除できません".to_string());
+    }
+    db::delete_partner(&conn, id).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// ── セグメント ──
+
+fn validate_segment(name: &str, segment_type: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("セグメント名を入力してください".to_string());
+    }
+    if !matches!(segment_type, "部門" | "プロジェクト" | "タグ") {
+        return Err(format!("不正なセグメント区分です: {}", segment_type));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_segments(state: State<DbState>) -> Result<Vec<Segment>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::fetch_segments(&conn).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn add_segment(state: State<DbState>, name: String, segment_type: String, memo: String) -> Result<i64, String> {
+    validate_segment(&name, &segment_type)?;
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::insert_segment(&conn, name.trim(), &segment_type, &memo).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn update_segment(
+    state: State<DbState>,
+    id: i64,
+    name: String,
+    segment_type: String,
+    memo: String,
+    is_active: bool,
+) -> Result<(), String> {
+    validate_segment(&name, &segment_type)?;
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::update_segment(&conn, id, name.trim(), &segment_type, &memo, is_active).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_segment(state: State<DbState>, id: i64) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    if db::count_segment_references(&conn, id).map_err(|e| e.to_string())? > 0 {
+        return Err("このセグメントは仕訳で使用されているため削除できません".to_string());
+    }
+    db::delete_segment(&conn, id).map_err(|e| e.to_string())?;
+    Ok(())
 }
 
 // ── 仕訳 ──
@@ -32,13 +395,18 @@ pub fn add_account(
 #[tauri::command]
 pub fn add_entry(
     state: State<DbState>,
-    date: String,
+    history: State<HistoryState>,
+    date: Date,
     debit_account_id: i64,
     debit_amount: i64,
     credit_account_id: i64,
     credit_amount: i64,
     description: String,
-) -> Result<i64, String> {
+    partner_id: Option<i64>,
+    debit_sub_account_id: Option<i64>,
+    credit_sub_account_id: Option<i64>,
+    segment_id: Option<i64>,
+) -> Result<EntryAddResult, String> {
     if debit_amount != credit_amount {
         return Err("借方金額と貸方金額が一致しません".to_string());
     }
@@ -46,8 +414,72 @@ pub fn add_entry(
         return Err("金額は1円以上を入力してください".to_string());
     }
     let conn = state.0.lock().map_err(|e| e.to_string())?;
-    db::insert_entry(&conn, &date, debit_account_id, debit_amount, credit_account_id, credit_amount, &description)
-        .map_err(|e| e.to_string())
+    ensure_date_open(&conn, date)?;
+    ensure_sub_account(&conn, debit_account_id, debit_sub_account_id)?;
+    ensure_sub_account(&conn, credit_account_id, credit_sub_account_id)?;
+    let id = db::insert_entry(
+        &conn, &date.to_string(), debit_account_id, debit_amount, credit_account_id, credit_amount, &description, partner_id,
+        debit_sub_account_id, credit_sub_account_id, segment_id,
+    ).map_err(|e| e.to_string())?;
+    record(&history, format!("仕訳の登録（{}）", entry_label(&date.to_string(), &description)), vec![Operation::AddEntry(id)])?;
+    let duplicates = find_duplicates_of(&conn, id, date);
+    Ok(EntryAddResult { id, duplicates })
+}
+
+/// 複合仕訳の明細を検証する（借方・貸方それぞれ1行以上、各行1円以上、貸借一致）
+fn validate_lines(lines: &[JournalLineInput]) -> Result<(), String> {
+    let mut debit_total: i64 = 0;
+    let mut credit_total: i64 = 0;
+    for line in lines {
+        if line.amount <= 0 {
+            return Err("金額は1円以上を入力してください".to_string());
+        }
+        match line.side.as_str() {
+            "借方" => debit_total += line.amount,
+            "貸方" => credit_total += line.amount,
+            _ => return Err(format!("不正な貸借区分です: {}", line.side)),
+        }
+    }
+    if debit_total == 0 || credit_total == 0 {
+        return Err("借方・貸方をそれぞれ1行以上入力してください".to_string());
+    }
+    if debit_total != credit_total {
+        return Err(format!(
+            "借方合計({})と貸方合計({})が一致しません",
+            debit_total, credit_total
+        ));
+    }
+    Ok(())
+}
+
+/// 複合仕訳の登録（重複の疑いがある仕訳も返す。登録自体は行う）
+#[tauri::command]
+pub fn add_compound_entry(
+    state: State<DbState>,
+    history: State<HistoryState>,
+    date: Date,
+    description: String,
+    lines: Vec<JournalLineInput>,
+    partner_id: Option<i64>,
+    segment_id: Option<i64>,
+) -> Result<EntryAddResult, String> {
+    validate_lines(&lines)?;
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    ensure_date_open(&conn, date)?;
+    for line in &lines {
+        ensure_sub_account(&conn, line.account_id, line.sub_account_id)?;
+    }
+    let id = db::insert_compound_entry(&conn, &date.to_string(), &description, &lines, partner_id, segment_id)
+        .map_err(|e| e.to_string())?;
+    record(&history, format!("仕訳の登録（{}）", entry_label(&date.to_string(), &description)), vec![Operation::AddEntry(id)])?;
+    let duplicates = find_duplicates_of(&conn, id, date);
+    Ok(EntryAddResult { id, duplicates })
+}
+
+#[tauri::command]
+pub fn get_entry_lines(state: State<DbState>, entry_id: i64) -> Result<Vec<JournalLine>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::fetch_entry_lines(&conn, entry_id).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -55,21 +487,147 @@ pub fn get_entries(
     state: State<DbState>,
     year: i32,
     month: Option<i32>,
+    partner_id: Option<i64>,
+    segment_id: Option<i64>,
 ) -> Result<Vec<JournalEntry>, String> {
     let conn = state.0.lock().map_err(|e| e.to_string())?;
-    db::fetch_entries(&conn, year, month).map_err(|e| e.to_string())
+    db::fetch_entries(&conn, year, month, partner_id, segment_id).map_err(|e| e.to_string())
+}
+
+/// 仕訳検索・ページ取得の条件を検証する
+fn validate_entry_query(query: &EntrySearchQuery) -> Result<(), String> {
+    if let (Some(from), Some(to)) = (query.date_from, query.date_to) {
+        if from > to {
+            return Err("検索期間の開始日が終了日より後になっています".to_string());
+        }
+    }
+    if let (Some(min), Some(max)) = (query.amount_min, query.amount_max) {
+        if min > max {
+            return Err("金額の下限が上限を超えています".to_string());
+        }
+    }
+    if let Some(sort) = &query.sort {
+        if !db::ENTRY_SORT_KEYS.contains(&sort.as_str()) {
+            return Err(format!("不正な並び替え項目です: {}", sort));
+        }
+    }
+    if query.limit.is_some_and(|l| !(1..=1000).contains(&l)) {
+        return Err("1ページの件数は1〜1000件で指定してください".to_string());
+    }
+    if query.offset.is_some_and(|o| o < 0) {
+        return Err("開始位置が不正です".to_string());
+    }
+    Ok(())
+}
+
+/// year 年 month 月（None なら年間）の初日と末日
+fn period_dates(year: i32, month: Option<i32>) -> Result<(Date, Date), String> {
+    Date::period(year, month).ok_or_else(|| match month {
+        Some(m) => format!("存在しない年月です: {}年{}月", year, m),
+        None => format!("存在しない年です: {}年", year),
+    })
+}
+
+/// 仕訳帳の期間（month 指定時はその月、未指定時は年間）を検索条件にする
+fn period_query(
+    year: i32,
+    month: Option<i32>,
+    partner_id: Option<i64>,
+    segment_id: Option<i64>,
+) -> Result<EntrySearchQuery, String> {
+    let (date_from, date_to) = period_dates(year, month)?;
+    Ok(EntrySearchQuery {
+        date_from: Some(date_from),
+        date_to: Some(date_to),
+        partner_id,
+        segment_id,
+        ..Default::default()
+    })
+}
+
+/// 条件を組み合わせた仕訳検索（電子帳簿保存法の検索要件: 取引年月日・取引金額・取引先）
+#[tauri::command]
+pub fn search_entries(state: State<DbState>, query: EntrySearchQuery) -> Result<EntrySearchResult, String> {
+    validate_entry_query(&query)?;
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::search_entries(&conn, &query).map_err(|e| e.to_string())
+}
+
+/// 仕訳帳の1ページ分（並び替え・件数指定つき）と期間内の全件数
+#[tauri::command]
+pub fn get_entries_page(
+    state: State<DbState>,
+    year: i32,
+    month: Option<i32>,
+    partner_id: Option<i64>,
+    segment_id: Option<i64>,
+    sort: Option<String>,
+    descending: Option<bool>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<EntrySearchResult, String> {
+    let query = EntrySearchQuery {
+        sort,
+        descending: descending.unwrap_or(false),
+        limit,
+        offset,
+        ..period_query(year, month, partner_id, segment_id)?
+    };
+    validate_entry_query(&query)?;
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::search_entries(&conn, &query).map_err(|e| e.to_string())
+}
+
+/// 期間内の仕訳の件数
+#[tauri::command]
+pub fn count_entries(
+    state: State<DbState>,
+    year: i32,
+    month: Option<i32>,
+    partner_id: Option<i64>,
+    segment_id: Option<i64>,
+) -> Result<i64, String> {
+    let query = period_query(year, month, partner_id, segment_id)?;
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::count_entries(&conn, &query).map_err(|e| e.to_string())
+}
+
+/// 摘要・金額から借方・貸方科目と取引先の候補を返す（limit 未指定時は5件）
+#[tauri::command]
+pub fn suggest_accounts(
+    state: State<DbState>,
+    description: String,
+    amount: Option<i64>,
+    limit: Option<usize>,
+) -> Result<Vec<AccountSuggestion>, String> {
+    let limit = limit.unwrap_or(5);
+    if !(1..=20).contains(&limit) {
+        return Err("候補の件数は1〜20件で指定してください".to_string());
+    }
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let today = chrono::Local::now().date_naive();
+    db::suggest_accounts(&conn, &description, amount, today, limit).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 pub fn update_entry(
     state: State<DbState>,
+    history: State<HistoryState>,
     id: i64,
-    date: String,
+    date: Date,
     debit_account_id: i64,
     debit_amount: i64,
     credit_account_id: i64,
     credit_amount: i64,
     description: String,
+    partner_id: Option<i64>,
+    debit_sub_account_id: Option<i64>,
+    credit_sub_account_id: Option<i64>,
+    segment_id: Option<i64>,
+    currency: Option<String>,
+    foreign_amount: Option<String>,
+    exchange_rate: Option<String>,
+    foreign_side: Option<String>,
 ) -> Result<(), String> {
     if debit_amount != credit_amount {
         return Err("借方金額と貸方金額が一致しません".to_string());
@@ -78,40 +636,804 @@ pub fn update_entry(
         return Err("金額は1円以上を入力してください".to_string());
     }
     let conn = state.0.lock().map_err(|e| e.to_string())?;
-    db::update_entry(&conn, id, &date, debit_account_id, debit_amount, credit_account_id, credit_amount, &description)
-        .map_err(|e| e.to_string())?;
-    Ok(())
+    ensure_entry_open(&conn, id)?;
+    ensure_date_open(&conn, date)?;
+    ensure_sub_account(&conn, debit_account_id, debit_sub_account_id)?;
+    ensure_sub_account(&conn, credit_account_id, credit_sub_account_id)?;
+    // 通貨を省略すると円建ての仕訳になる（外貨建ての仕訳は通貨・外貨額・外貨建ての科目の側を渡して訂正する）
+    let foreign = match (currency, foreign_amount, foreign_side) {
+        (Some(currency), Some(foreign_amount), Some(foreign_side)) => Some(resolve_foreign_amount(
+            &conn, date, &currency, &foreign_amount, exchange_rate.as_deref(), &foreign_side,
+        )?),
+        (None, None, None) => None,
+        _ => return Err("外貨建ての仕訳は通貨・外貨額・外貨建ての科目の側をすべて入力してください".to_string()),
+    };
+    let before = fetch_snapshot(&conn, id)?;
+    db::update_entry(
+        &conn, id, &date.to_string(), debit_account_id, debit_amount, credit_account_id, credit_amount, &description, partner_id,
+        debit_sub_account_id, credit_sub_account_id, segment_id, foreign.as_ref(),
+    ).map_err(|e| e.to_string())?;
+    record(
+        &history,
+        format!("仕訳の訂正（{}）", entry_label(&before.date, &before.description)),
+        vec![Operation::UpdateEntry { id, snapshot: before }],
+    )
 }
 
 #[tauri::command]
-pub fn delete_entry(state: State<DbState>, id: i64) -> Result<(), String> {
+pub fn update_compound_entry(
+    state: State<DbState>,
+    history: State<HistoryState>,
+    id: i64,
+    date: Date,
+    description: String,
+    lines: Vec<JournalLineInput>,
+    partner_id: Option<i64>,
+    segment_id: Option<i64>,
+) -> Result<(), String> {
+    validate_lines(&lines)?;
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    ensure_entry_open(&conn, id)?;
+    ensure_date_open(&conn, date)?;
+    for line in &lines {
+        ensure_sub_account(&conn, line.account_id, line.sub_account_id)?;
+    }
+    let before = fetch_snapshot(&conn, id)?;
+    db::update_compound_entry(&conn, id, &date.to_string(), &description, &lines, partner_id, segment_id).map_err(|e| e.to_string())?;
+    record(
+        &history,
+        format!("仕訳の訂正（{}）", entry_label(&before.date, &before.description)),
+        vec![Operation::UpdateEntry { id, snapshot: before }],
+    )
+}
+
+#[tauri::command]
+pub fn delete_entry(state: State<DbState>, history: State<HistoryState>, id: i64) -> Result<(), String> {
     let conn = state.0.lock().map_err(|e| e.to_string())?;
+    ensure_entry_open(&conn, id)?;
+    let before = fetch_snapshot(&conn, id)?;
     db::delete_entry(&conn, id).map_err(|e| e.to_string())?;
+    record(
+        &history,
+        format!("仕訳の削除（{}）", entry_label(&before.date, &before.description)),
+        vec![Operation::DeleteEntry(id)],
+    )
+}
+
+// ── 一括処理 ──
+
+/// 一括処理の1行を検証する（add_entry と同じ条件に加えて勘定科目の存在も確認する）
+fn validate_entry_row(conn: &Connection, row: &EntryBatchRow, account_ids: &HashSet<i64>) -> Result<(), String> {
+    if row.debit_amount != row.credit_amount {
+        return Err("借方金額と貸方金額が一致しません".to_string());
+    }
+    if row.debit_amount <= 0 {
+        return Err("金額は1円以上を入力してください".to_string());
+    }
+    for account_id in [row.debit_account_id, row.credit_account_id] {
+        if !account_ids.contains(&account_id) {
+            return Err(format!("勘定科目が見つかりません（ID: {}）", account_id));
+        }
+    }
+    ensure_date_open(conn, row.date)?;
+    ensure_sub_account(conn, row.debit_account_id, row.debit_sub_account_id)?;
+    ensure_sub_account(conn, row.credit_account_id, row.credit_sub_account_id)?;
+    Ok(())
+}
+
+/// 行ごとの検証結果からエラーの行だけを取り出す
+fn collect_row_errors(results: impl Iterator<Item = Result<(), String>>) -> Vec<EntryBatchError> {
+    results
+        .enumerate()
+        .filter_map(|(i, r)| r.err().map(|message| EntryBatchError { row: i + 1, message }))
+        .collect()
+}
+
+/// 同じ仕訳IDが2回目以降に現れたらエラーにする
+fn ensure_unique_id(seen: &mut HashSet<i64>, id: i64) -> Result<(), String> {
+    if !seen.insert(id) {
+        return Err(format!("同じ仕訳（ID: {}）が複数回指定されています", id));
+    }
+    Ok(())
+}
+
+fn fetch_account_ids(conn: &Connection) -> Result<HashSet<i64>, String> {
+    Ok(db::fetch_accounts(conn).map_err(|e| e.to_string())?.into_iter().map(|a| a.id).collect())
+}
+
+/// 単一行仕訳の一括登録（全行を検証してから1つのトランザクションで登録する）
+/// 登録した仕訳と重複の疑いがある組も返す（既存の仕訳との組、貼り付けた行どうしの組）
+#[tauri::command]
+pub fn add_entries_batch(
+    state: State<DbState>,
+    history: State<HistoryState>,
+    rows: Vec<EntryBatchRow>,
+) -> Result<EntryBatchResult, String> {
+    if rows.is_empty() {
+        return Err("登録する仕訳がありません".to_string());
+    }
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let account_ids = fetch_account_ids(&conn)?;
+    let errors = collect_row_errors(rows.iter().map(|r| validate_entry_row(&conn, r, &account_ids)));
+    if !errors.is_empty() {
+        return Ok(EntryBatchResult { ids: Vec::new(), errors, duplicates: Vec::new() });
+    }
+    let ids = db::insert_entries_batch(&conn, &rows).map_err(|e| e.to_string())?;
+    record(
+        &history,
+        format!("仕訳の一括登録（{}件）", ids.len()),
+        ids.iter().map(|&id| Operation::AddEntry(id)).collect(),
+    )?;
+    // 同じ組が両方の行から見つかるため、一度だけ返す
+    let mut seen_pairs = HashSet::new();
+    let duplicates = ids
+        .iter()
+        .zip(&rows)
+        .flat_map(|(&id, row)| find_duplicates_of(&conn, id, row.date))
+        .filter(|p| seen_pairs.insert((p.entry.id, p.duplicate.id)))
+        .collect();
+    Ok(EntryBatchResult { ids, errors, duplicates })
+}
+
+/// 仕訳の一括訂正（全行を検証してから1つのトランザクションで訂正する）
+#[tauri::command]
+pub fn update_entries_batch(
+    state: State<DbState>,
+    history: State<HistoryState>,
+    updates: Vec<EntryBatchUpdate>,
+) -> Result<EntryBatchResult, String> {
+    if updates.is_empty() {
+        return Err("訂正する仕訳がありません".to_string());
+    }
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let account_ids = fetch_account_ids(&conn)?;
+    let mut seen = HashSet::new();
+    let errors = collect_row_errors(updates.iter().map(|u| {
+        ensure_unique_id(&mut seen, u.id)?;
+        ensure_entry_open(&conn, u.id)?;
+        if fetch_snapshot(&conn, u.id)?.foreign.is_some() {
+            return Err("外貨建ての仕訳は一括訂正できません。1件ずつ訂正してください".to_string());
+        }
+        validate_entry_row(&conn, &u.row, &account_ids)
+    }));
+    if !errors.is_empty() {
+        return Ok(EntryBatchResult { ids: Vec::new(), errors, duplicates: Vec::new() });
+    }
+    let mut operations = Vec::with_capacity(updates.len());
+    for u in &updates {
+        operations.push(Operation::UpdateEntry { id: u.id, snapshot: fetch_snapshot(&conn, u.id)? });
+    }
+    db::update_entries_batch(&conn, &updates).map_err(|e| e.to_string())?;
+    record(&history, format!("仕訳の一括訂正（{}件）", updates.len()), operations)?;
+    Ok(EntryBatchResult { ids: updates.iter().map(|u| u.id).collect(), errors, duplicates: Vec::new() })
+}
+
+/// 仕訳の一括取消（全件を検証してから1つのトランザクションで取消す）
+#[tauri::command]
+pub fn delete_entries_batch(
+    state: State<DbState>,
+    history: State<HistoryState>,
+    ids: Vec<i64>,
+) -> Result<EntryBatchResult, String> {
+    if ids.is_empty() {
+        return Err("取消す仕訳がありません".to_string());
+    }
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let mut seen = HashSet::new();
+    let errors = collect_row_errors(ids.iter().map(|&id| {
+        ensure_unique_id(&mut seen, id)?;
+        ensure_entry_open(&conn, id)
+    }));
+    if !errors.is_empty() {
+        return Ok(EntryBatchResult { ids: Vec::new(), errors, duplicates: Vec::new() });
+    }
+    db::delete_entries_batch(&conn, &ids).map_err(|e| e.to_string())?;
+    record(
+        &history,
+        format!("仕訳の一括削除（{}件）", ids.len()),
+        ids.iter().map(|&id| Operation::DeleteEntry(id)).collect(),
+    )?;
+    Ok(EntryBatchResult { ids, errors, duplicates: Vec::new() })
+}
+
+// ── 重複チェック ──
+
+/// 同額・同摘要の仕訳を重複の疑いとみなす日数の既定値
+const DUPLICATE_WINDOW_DAYS: i64 = 7;
+
+/// 登録した仕訳と重複の疑いがある仕訳の組（確認済みの組は除く）
+/// 登録は済んでいて重複は警告にすぎないため、検索に失敗しても登録をエラーにせず空を返す
+fn find_duplicates_of(conn: &Connection, id: i64, Date(date): Date) -> Vec<DuplicatePair> {
+    let window = chrono::Duration::days(DUPLICATE_WINDOW_DAYS);
+    db::find_duplicate_pairs(conn, date - window, date + window, DUPLICATE_WINDOW_DAYS, Some(id))
+        .map(|pairs| pairs.into_iter().filter(|p| !p.dismissed).collect())
+        .unwrap_or_default()
+}
+
+/// 年度内に登録された仕訳のうち重複の疑いがある組（window_days 未指定時は7日。前年末の仕訳との組も含む）
+#[tauri::command]
+pub fn find_duplicate_entries(
+    state: State<DbState>,
+    year: i32,
+    window_days: Option<i64>,
+    include_dismissed: Option<bool>,
+) -> Result<Vec<DuplicatePair>, String> {
+    let window_days = window_days.unwrap_or(DUPLICATE_WINDOW_DAYS);
+    if !(0..=90).contains(&window_days) {
+        return Err("重複とみなす日数は0〜90日で指定してください".to_string());
+    }
+    let (Date(year_start), Date(year_end)) = period_dates(year, None)?;
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let pairs = db::find_duplicate_pairs(
+        &conn,
+        year_start - chrono::Duration::days(window_days),
+        year_end,
+        window_days,
+        None,
+    )
+    .map_err(|e| e.to_string())?;
+    let year_start = year_start.format("%Y-%m-%d").to_string();
+    Ok(pairs
+        .into_iter()
+        .filter(|p| p.entry.date >= year_start || p.duplicate.date >= year_start)
+        .filter(|p| include_dismissed.unwrap_or(false) || !p.dismissed)
+        .collect())
+}
+
+#[tauri::command]
+pub fn dismiss_duplicate(state: State<DbState>, entry_id: i64, duplicate_id: i64) -> Result<(), String> {
+    if entry_id == duplicate_id {
+        return Err("同じ仕訳は指定できません".to_string());
+    }
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::dismiss_duplicate(&conn, entry_id, duplicate_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn undismiss_duplicate(state: State<DbState>, entry_id: i64, duplicate_id: i64) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::undismiss_duplicate(&conn, entry_id, duplicate_id).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// ── 確認状況 ──
+
+/// 仕訳の確認状況（未確認・確認済・要質問）とメモを更新する
+/// 確認済みにするのは締め済みの期間でもできるが、確認待ちに戻すのは締め済みでない期間だけ
+#[tauri::command]
+pub fn update_entry_review(
+    state: State<DbState>,
+    history: State<HistoryState>,
+    id: i64,
+    review_status: String,
+    note: String,
+) -> Result<(), String> {
+    if !db::REVIEW_STATUSES.contains(&review_status.as_str()) {
+        return Err(format!("不正な確認状況です: {}", review_status));
+    }
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    if review_status != "確認済" {
+        ensure_entry_open(&conn, id)?;
+    }
+    let (before_status, before_note) = db::fetch_entry_review(&conn, id)
+        .map_err(|e| e.to_string())?
+        .ok_or("仕訳が見つかりません")?;
+    db::update_entry_review(&conn, id, &review_status, &note).map_err(|e| e.to_string())?;
+    let entry = fetch_snapshot(&conn, id)?;
+    record(
+        &history,
+        format!("確認状況の変更（{}）", entry_label(&entry.date, &entry.description)),
+        vec![Operation::UpdateEntryReview { id, review_status: before_status, note: before_note }],
+    )
+}
+
+/// 年度内の確認待ちの仕訳（月ごと）
+#[tauri::command]
+pub fn get_open_items(state: State<DbState>, year: i32) -> Result<Vec<OpenItemMonth>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::fetch_open_items(&conn, year).map_err(|e| e.to_string())
+}
+
+// ── 伝票番号 ──
+
+#[tauri::command]
+pub fn get_voucher_number_report(state: State<DbState>, year: i32) -> Result<VoucherNumberReport, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::check_voucher_numbers(&conn, year).map_err(|e| e.to_string())
+}
+
+// ── 赤黒訂正 ──
+
+/// 仕訳の貸借を入れ替えた取消仕訳を登録する（元の仕訳の期間が締め済みでも、訂正日が開いていればよい）
+#[tauri::command]
+pub fn reverse_entry(
+    state: State<DbState>,
+    history: State<HistoryState>,
+    id: i64,
+    date: Option<Date>,
+) -> Result<i64, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let original_date = db::fetch_entry_date(&conn, id)
+        .map_err(|e| e.to_string())?
+        .ok_or("仕訳が見つかりません")?;
+    let (reversal_of, reversed_by) = db::fetch_reversal_link(&conn, id).map_err(|e| e.to_string())?;
+    if reversal_of.is_some() {
+        return Err("赤黒訂正の取消仕訳はさらに取消できません".to_string());
+    }
+    if reversed_by.is_some() {
+        return Err("この仕訳は既に赤黒訂正されています".to_string());
+    }
+    let date = match date {
+        Some(date) => date,
+        None => Date::parse(&original_date)?,
+    };
+    ensure_date_open(&conn, date)?;
+    let reversal_id = db::reverse_entry(&conn, id, Some(&date.to_string()))
+        .map_err(|e| e.to_string())?
+        .ok_or("仕訳が見つかりません")?;
+    record(&history, format!("赤黒訂正（{}）", entry_label(&date.to_string(), "")), vec![Operation::AddEntry(reversal_id)])?;
+    Ok(reversal_id)
+}
+
+#[tauri::command]
+pub fn get_reversals(
+    state: State<DbState>,
+    year: i32,
+    month: Option<i32>,
+) -> Result<Vec<EntryReversal>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::fetch_reversals(&conn, year, month).map_err(|e| e.to_string())
+}
+
+// ── 訂正・削除履歴 ──
+
+#[tauri::command]
+pub fn get_entry_revisions(state: State<DbState>, entry_id: i64) -> Result<Vec<EntryRevision>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::fetch_entry_revisions(&conn, entry_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_revisions(
+    state: State<DbState>,
+    year: i32,
+    month: Option<i32>,
+) -> Result<Vec<EntryRevision>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::fetch_revisions(&conn, year, month).map_err(|e| e.to_string())
+}
+
+// ── 元に戻す・やり直し ──
+
+/// 元に戻す操作の説明に使う仕訳の表示（摘要は長ければ切り詰める）
+fn entry_label(date: &str, description: &str) -> String {
+    const MAX_CHARS: usize = 20;
+    if description.is_empty() {
+        return date.to_string();
+    }
+    let mut text: String = description.chars().take(MAX_CHARS).collect();
+    if description.chars().count() > MAX_CHARS {
+        text.push('…');
+    }
+    format!("{} {}", date, text)
+}
+
+fn fetch_snapshot(conn: &Connection, id: i64) -> Result<EntrySnapshot, String> {
+    db::fetch_entry_snapshot(conn, id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "仕訳が見つかりません".to_string())
+}
+
+fn record(history: &State<HistoryState>, description: String, operations: Vec<Operation>) -> Result<(), String> {
+    history.0.lock().map_err(|e| e.to_string())?.record(description, operations);
     Ok(())
 }
 
+/// 変更を1件取り消す（undo = false のときはやり直す）
+/// 反対側の履歴に積む変更を返す（Update* は適用前の内容を保持する）
+fn apply_operation(conn: &Connection, op: Operation, undo: bool) -> Result<Operation, String> {
+    let not_found = |changed: usize, what: &str| {
+        if changed == 0 {
+            Err(format!("{}が見つからないため元に戻せません", what))
+        } else {
+            Ok(())
+        }
+    };
+    match op {
+        Operation::AddEntry(id) | Operation::DeleteEntry(id) => {
+            let removing = matches!(op, Operation::AddEntry(_)) == undo;
+            // 取消済みの仕訳も対象にするため ensure_entry_open は使わない
+            ensure_stored_date_open(conn, &fetch_snapshot(conn, id)?.date)?;
+            let changed = if removing {
+                db::delete_entry(conn, id)
+            } else {
+                db::restore_entry(conn, id)
+            }
+            .map_err(|e| e.to_string())?;
+            not_found(changed, "仕訳")?;
+            Ok(op)
+        }
+        Operation::UpdateEntry { id, snapshot } => {
+            let current = fetch_snapshot(conn, id)?;
+            ensure_stored_date_open(conn, &current.date)?;
+            ensure_stored_date_open(conn, &snapshot.date)?;
+            let changed = db::apply_entry_snapshot(conn, id, &snapshot).map_err(|e| e.to_string())?;
+            not_found(changed, "仕訳")?;
+            Ok(Operation::UpdateEntry { id, snapshot: current })
+        }
+        Operation::AddAccount(ref account) | Operation::DeleteAccount(ref account) => {
+            let removing = matches!(op, Operation::AddAccount(_)) == undo;
+            if removing {
+                let references = db::count_account_references(conn, account.id).map_err(|e| e.to_string())?;
+                if references > 0 {
+                    return Err(format!("勘定科目「{}」は{}件使用されているため元に戻せません", account.name, references));
+                }
+                let changed = db::delete_account(conn, account.id).map_err(|e| e.to_string())?;
+                not_found(changed, "勘定科目")?;
+            } else {
+                db::restore_account(conn, account).map_err(|e| e.to_string())?;
+            }
+            Ok(op)
+        }
+        Operation::UpdateAccount(saved) => {
+            let current = db::fetch_account(conn, saved.id)
+                .map_err(|e| e.to_string())?
+                .ok_or("勘定科目が見つからないため元に戻せません")?;
+            if current.classification != saved.classification {
+                ensure_account_years_open(conn, saved.id)?;
+            }
+            db::update_account(conn, saved.id, saved.code, &saved.name, &saved.classification, saved.is_active)
+                .map_err(|e| e.to_string())?;
+            Ok(Operation::UpdateAccount(current))
+        }
+        Operation::AddFixedAsset(ref asset) | Operation::DeleteFixedAsset(ref asset) => {
+            let removing = matches!(op, Operation::AddFixedAsset(_)) == undo;
+            ensure_acquisition_years_open(conn, &asset.acquisition_date)?;
+            if removing {
+                let changed = db::delete_fixed_asset(conn, asset.id).map_err(|e| e.to_string())?;
+                not_found(changed, "固定資産")?;
+            } else {
+                db::restore_fixed_asset(conn, asset).map_err(|e| e.to_string())?;
+            }
+            Ok(op)
+        }
+        Operation::AddRentDetail(ref detail) | Operation::DeleteRentDetail(ref detail) => {
+            let removing = matches!(op, Operation::AddRentDetail(_)) == undo;
+            ensure_years_open(conn, detail.year, detail.year)?;
+            if removing {
+                let changed = db::delete_rent_detail(conn, detail.id).map_err(|e| e.to_string())?;
+                not_found(changed, "地代家賃の内訳")?;
+            } else {
+                db::restore_rent_detail(conn, detail).map_err(|e| e.to_string())?;
+            }
+            Ok(op)
+        }
+        Operation::AddLossCarryforward(ref loss) | Operation::DeleteLossCarryforward(ref loss) => {
+            let removing = matches!(op, Operation::AddLossCarryforward(_)) == undo;
+            ensure_years_open(conn, loss.loss_year, loss.loss_year + 3)?;
+            if removing {
+                let changed = db::delete_loss_carryforward(conn, loss.id).map_err(|e| e.to_string())?;
+                not_found(changed, "繰越損失")?;
+            } else {
+                db::restore_loss_carryforward(conn, loss).map_err(|e| e.to_string())?;
+            }
+            Ok(op)
+        }
+        Operation::UpdateLossCarryforward(saved) => {
+            let current = db::fetch_loss_carryforward(conn, saved.id)
+                .map_err(|e| e.to_string())?
+                .ok_or("繰越損失が見つからないため元に戻せません")?;
+            ensure_years_open(conn, saved.loss_year, saved.loss_year + 3)?;
+            db::update_loss_carryforward_usage(conn, saved.id, saved.used_year_1, saved.used_year_2, saved.used_year_3)
+                .map_err(|e| e.to_string())?;
+            Ok(Operation::UpdateLossCarryforward(current))
+        }
+        Operation::UpdateEntryReview { id, review_status, note } => {
+            let (current_status, current_note) = db::fetch_entry_review(conn, id)
+                .map_err(|e| e.to_string())?
+                .ok_or("仕訳が見つからないため元に戻せません")?;
+            // update_entry_review と同じく、確認待ちに戻すのは締め済みでない期間だけ
+            if review_status != "確認済" {
+                ensure_entry_open(conn, id)?;
+            }
+            db::update_entry_review(conn, id, &review_status, &note).map_err(|e| e.to_string())?;
+            Ok(Operation::UpdateEntryReview { id, review_status: current_status, note: current_note })
+        }
+        Operation::AddItemMatch(ref item_match) | Operation::DeleteItemMatch(ref item_match) => {
+            let removing = matches!(op, Operation::AddItemMatch(_)) == undo;
+            ensure_match_open(conn, item_match)?;
+            if removing {
+                let changed = db::delete_item_match(conn, item_match.id).map_err(|e| e.to_string())?;
+                not_found(changed, "消込")?;
+            } else {
+                db::restore_item_match(conn, item_match).map_err(|e| e.to_string())?;
+            }
+            Ok(op)
+        }
+    }
+}
+
+/// 1回分の操作をまとめて取り消す（途中で失敗したら何も変更しない）
+fn apply_step(conn: &Connection, step: &UndoStep, undo: bool) -> Result<UndoStep, String> {
+    conn.execute_batch("SAVEPOINT undo_step").map_err(|e| e.to_string())?;
+    let mut applied = Vec::with_capacity(step.operations.len());
+    let mut result = Ok(());
+    // 元に戻すときは記録と逆の順に適用する
+    let mut operations: Vec<&Operation> = step.operations.iter().collect();
+    if undo {
+        operations.reverse();
+    }
+    for op in operations {
+        match apply_operation(conn, op.clone(), undo) {
+            Ok(done) => applied.push(done),
+            Err(e) => {
+                result = Err(e);
+                break;
+            }
+        }
+    }
+    match result {
+        Ok(()) => {
+            conn.execute_batch("RELEASE undo_step").map_err(|e| e.to_string())?;
+            if undo {
+                applied.reverse();
+            }
+            Ok(UndoStep { description: step.description.clone(), operations: applied })
+        }
+        Err(e) => {
+            conn.execute_batch("ROLLBACK TO undo_step; RELEASE undo_step").map_err(|e| e.to_string())?;
+            Err(format!("「{}」を{}できません: {}", step.description, if undo { "元に戻" } else { "やり直" }, e))
+        }
+    }
+}
+
+/// count 回分（省略時は1回）の操作を元に戻すかやり直し、処理した操作の説明を返す
+fn undo_redo(
+    state: &State<DbState>,
+    history: &State<HistoryState>,
+    count: Option<usize>,
+    undo: bool,
+) -> Result<Vec<String>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let mut history = history.0.lock().map_err(|e| e.to_string())?;
+    let mut done = Vec::new();
+    for _ in 0..count.unwrap_or(1).max(1) {
+        let step = if undo { history.pop_undo() } else { history.pop_redo() };
+        let Some(step) = step else { break };
+        match apply_step(&conn, &step, undo) {
+            Ok(applied) => {
+                done.push(applied.description.clone());
+                if undo {
+                    history.push_redo(applied);
+                } else {
+                    history.push_undo(applied);
+                }
+            }
+            Err(e) => {
+                if undo {
+                    history.push_undo(step);
+                } else {
+                    history.push_redo(step);
+                }
+                if done.is_empty() {
+                    return Err(e);
+                }
+                break;
+            }
+        }
+    }
+    Ok(done)
+}
+
+#[tauri::command]
+pub fn undo(
+    state: State<DbState>,
+    history: State<HistoryState>,
+    count: Option<usize>,
+) -> Result<Vec<String>, String> {
+    undo_redo(&state, &history, count, true)
+}
+
+#[tauri::command]
+pub fn redo(
+    state: State<DbState>,
+    history: State<HistoryState>,
+    count: Option<usize>,
+) -> Result<Vec<String>, String> {
+    undo_redo(&state, &history, count, false)
+}
+
+#[tauri::command]
+pub fn get_undo_status(history: State<HistoryState>) -> Result<UndoStatus, String> {
+    Ok(history.0.lock().map_err(|e| e.to_string())?.status())
+}
+
 // ── 集計・レポート ──
 
 #[tauri::command]
-pub fn get_trial_balance(
-    state: State<DbState>,
-    year: i32,
-    month: Option<i32>,
-) -> Result<TrialBalance, String> {
+pub fn get_trial_balance(
+    state: State<DbState>,
+    year: i32,
+    month: Option<i32>,
+    by_sub_account: Option<bool>,
+) -> Result<TrialBalance, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::calc_trial_balance(&conn, year, month, by_sub_account.unwrap_or(false)).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_ledger(
+    state: State<DbState>,
+    account_id: i64,
+    year: i32,
+    month: Option<i32>,
+    partner_id: Option<i64>,
+    sub_account_id: Option<i64>,
+) -> Result<Ledger, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::calc_ledger(&conn, account_id, year, month, partner_id, sub_account_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_profit_loss(state: State<DbState>, year: i32) -> Result<ProfitLoss, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::calc_profit_loss(&conn, year).map_err(|e| e.to_string())
+}
+
+/// セグメント別の損益計算書（segment_id を指定するとそのセグメントだけ）
+#[tauri::command]
+pub fn get_segment_profit_loss(
+    state: State<DbState>,
+    year: i32,
+    segment_id: Option<i64>,
+) -> Result<Vec<SegmentProfitLoss>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::calc_segment_profit_loss(&conn, year, segment_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_balance_sheet(state: State<DbState>, year: i32) -> Result<BalanceSheet, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::calc_balance_sheet(&conn, year).map_err(|e| e.to_string())
+}
+
+// ── 期首残高 ──
+
+#[tauri::command]
+pub fn get_opening_balances(state: State<DbState>, year: i32) -> Result<Vec<OpeningBalance>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::fetch_opening_balances(&conn, year).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn set_opening_balances(
+    state: State<DbState>,
+    year: i32,
+    balances: Vec<OpeningBalanceInput>,
+) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    ensure_years_open(&conn, year, year)?;
+    if db::fetch_year_closing(&conn, year - 1).map_err(|e| e.to_string())?.is_some() {
+        return Err(format!(
+            "{}年は締め済みのため、{}年の期首残高は変更できません",
+            year - 1,
+            year
+        ));
+    }
+    let accounts = db::fetch_accounts(&conn).map_err(|e| e.to_string())?;
+
+    let mut debit_total: i64 = 0;
+    let mut credit_total: i64 = 0;
+    for b in &balances {
+        let account = accounts
+            .iter()
+            .find(|a| a.id == b.account_id)
+            .ok_or("存在しない勘定科目が指定されています")?;
+        match account.classification.as_str() {
+            "資産" => debit_total += b.amount,
+            "負債" | "純資産" => credit_total += b.amount,
+            _ => {
+                return Err(format!(
+                    "期首残高は資産・負債・純資産の科目のみ入力できます: {}",
+                    account.name
+                ))
+            }
+        }
+    }
+    if debit_total != credit_total {
+        return Err(format!(
+            "期首残高の貸借が一致しません（資産 {} / 負債・純資産 {}）",
+            debit_total, credit_total
+        ));
+    }
+
+    db::replace_opening_balances(&conn, year, &balances).map_err(|e| e.to_string())
+}
+
+// ── 年度締め ──
+
+#[tauri::command]
+pub fn get_year_closings(state: State<DbState>) -> Result<Vec<YearClosing>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::fetch_year_closings(&conn).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn close_year(state: State<DbState>, year: i32) -> Result<YearClosing, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    if db::fetch_year_closing(&conn, year).map_err(|e| e.to_string())?.is_some() {
+        return Err(format!("{}年は既に締め済みです", year));
+    }
+    ensure_years_open(&conn, year + 1, year + 1)?;
+    let open_items = db::count_open_items(&conn, year).map_err(|e| e.to_string())?;
+    if open_items > 0 {
+        return Err(format!("{}年に確認待ち（未確認・要質問）の仕訳が{}件あるため締められません", year, open_items));
+    }
+    if db::fetch_opening_balance_source(&conn, year + 1).map_err(|e| e.to_string())?.as_deref()
+        == Some("手入力")
+    {
+        return Err(format!(
+            "{}年に手入力の期首残高があるため締められません。先に期首残高を削除してください",
+            year + 1
+        ));
+    }
+    db::close_year(&conn, year).map_err(|e| e.to_string())
+}
+
+/// 締め済みの年度を再開する（翌年が締め済みの場合は先に翌年を再開する必要がある）
+#[tauri::command]
+pub fn reopen_year(state: State<DbState>, year: i32) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    if db::fetch_year_closing(&conn, year).map_err(|e| e.to_string())?.is_none() {
+        return Err(format!("{}年は締められていません", year));
+    }
+    ensure_years_unlocked(&conn, year, year + 1)?;
+    if db::fetch_year_closing(&conn, year + 1).map_err(|e| e.to_string())?.is_some() {
+        return Err(format!(
+            "{}年が締め済みのため再開できません。先に{}年を再開してください",
+            year + 1,
+            year + 1
+        ));
+    }
+    db::reopen_year(&conn, year).map_err(|e| e.to_string())
+}
+
+// ── 期間ロック ──
+
+#[tauri::command]
+pub fn get_period_locks(state: State<DbState>) -> Result<Vec<PeriodLock>, String> {
     let conn = state.0.lock().map_err(|e| e.to_string())?;
-    db::calc_trial_balance(&conn, year, month).map_err(|e| e.to_string())
+    db::fetch_period_locks(&conn).map_err(|e| e.to_string())
 }
 
+/// 月（month 指定時）または年度全体をロックする
 #[tauri::command]
-pub fn get_profit_loss(state: State<DbState>, year: i32) -> Result<ProfitLoss, String> {
+pub fn lock_period(state: State<DbState>, year: i32, month: Option<i32>) -> Result<i64, String> {
+    if let Some(m) = month {
+        if !(1..=12).contains(&m) {
+            return Err("月は1〜12で指定してください".to_string());
+        }
+    }
     let conn = state.0.lock().map_err(|e| e.to_string())?;
-    db::calc_profit_loss(&conn, year).map_err(|e| e.to_string())
+    let already_locked = match month {
+        Some(m) => db::is_period_locked(&conn, year, m),
+        None => db::find_locked_year(&conn, year, year).map(|y| y.is_some()),
+    }
+    .map_err(|e| e.to_string())?;
+    if already_locked {
+        return Err("指定の期間は既に締め済みです".to_string());
+    }
+    db::insert_period_lock(&conn, year, month).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub fn get_balance_sheet(state: State<DbState>, year: i32) -> Result<BalanceSheet, String> {
+pub fn unlock_period(state: State<DbState>, year: i32, month: Option<i32>) -> Result<(), String> {
     let conn = state.0.lock().map_err(|e| e.to_string())?;
-    db::calc_balance_sheet(&conn, year).map_err(|e| e.to_string())
+    let released = db::release_period_lock(&conn, year, month).map_err(|e| e.to_string())?;
+    if released == 0 {
+        return Err("指定の期間にロックはありません".to_string());
+    }
+    Ok(())
 }
 
 // ── 固定資産 ──
@@ -125,8 +1447,9 @@ pub fn get_fixed_assets(state: State<DbState>) -> Result<Vec<FixedAsset>, String
 #[tauri::command]
 pub fn add_fixed_asset(
     state: State<DbState>,
+    history: State<HistoryState>,
     name: String,
-    acquisition_date: String,
+    acquisition_date: Date,
     acquisition_cost: i64,
     useful_life: i32,
     depreciation_method: String,
@@ -137,31 +1460,45 @@ pub fn add_fixed_asset(
     if acquisition_cost <= 0 {
         return Err("取得価額は1円以上を入力してください".to_string());
     }
+    let acquisition_year = acquisition_date.year();
     let conn = state.0.lock().map_err(|e| e.to_string())?;
-    db::insert_fixed_asset(
-        &conn, &name, &acquisition_date, acquisition_cost, useful_life,
+    // 取得年以降の減価償却費に影響するため、取得年以降に締め済みの年度があれば登録できない
+    ensure_years_open(&conn, acquisition_year, i32::MAX)?;
+    let id = db::insert_fixed_asset(
+        &conn, &name, &acquisition_date.to_string(), acquisition_cost, useful_life,
         &depreciation_method, depreciation_rate, accumulated_dep, &memo,
-    ).map_err(|e| e.to_string())
+    ).map_err(|e| e.to_string())?;
+    if let Some(asset) = db::fetch_fixed_asset(&conn, id).map_err(|e| e.to_string())? {
+        record(&history, format!("固定資産の登録（{}）", asset.name), vec![Operation::AddFixedAsset(asset)])?;
+    }
+    Ok(id)
 }
 
 #[tauri::command]
-pub fn delete_fixed_asset(state: State<DbState>, id: i64) -> Result<(), String> {
+pub fn delete_fixed_asset(state: State<DbState>, history: State<HistoryState>, id: i64) -> Result<(), String> {
     let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let asset = db::fetch_fixed_asset(&conn, id)
+        .map_err(|e| e.to_string())?
+        .ok_or("固定資産が見つかりません")?;
+    ensure_acquisition_years_open(&conn, &asset.acquisition_date)?;
     db::delete_fixed_asset(&conn, id).map_err(|e| e.to_string())?;
-    Ok(())
+    record(&history, format!("固定資産の削除（{}）", asset.name), vec![Operation::DeleteFixedAsset(asset)])
 }
 
 // ── 地代家賃内訳 ──
 
 #[tauri::command]
-pub fn get_rent_details(state: State<DbState>) -> Result<Vec<RentDetail>, String> {
+pub fn get_rent_details(state: State<DbState>, year: i32) -> Result<Vec<RentDetail>, String> {
     let conn = state.0.lock().map_err(|e| e.to_string())?;
-    db::fetch_rent_details(&conn).map_err(|e| e.to_string())
+    db::fetch_rent_details(&conn, year).map_err(|e| e.to_string())
 }
 
+/// year は内訳を記載する決算書の年度
 #[tauri::command]
 pub fn add_rent_detail(
     state: State<DbState>,
+    history: State<HistoryState>,
+    year: i32,
     payee_address: String,
     payee_name: String,
     rent_type: String,
@@ -169,21 +1506,362 @@ pub fn add_rent_detail(
     annual_total: i64,
     business_ratio: i32,
     memo: String,
+    partner_id: Option<i64>,
 ) -> Result<i64, String> {
     let conn = state.0.lock().map_err(|e| e.to_string())?;
-    db::insert_rent_detail(
-        &conn, &payee_address, &payee_name, &rent_type,
-        monthly_rent, annual_total, business_ratio, &memo,
-    ).map_err(|e| e.to_string())
+    ensure_years_open(&conn, year, year)?;
+    let id = db::insert_rent_detail(
+        &conn, year, &payee_address, &payee_name, &rent_type,
+        monthly_rent, annual_total, business_ratio, &memo, partner_id,
+    ).map_err(|e| e.to_string())?;
+    if let Some(detail) = db::fetch_rent_detail(&conn, id).map_err(|e| e.to_string())? {
+        record(
+            &history,
+            format!("地代家賃内訳の登録（{}）", detail.payee_name),
+            vec![Operation::AddRentDetail(detail)],
+        )?;
+    }
+    Ok(id)
 }
 
 #[tauri::command]
-pub fn delete_rent_detail(state: State<DbState>, id: i64) -> Result<(), String> {
+pub fn delete_rent_detail(state: State<DbState>, history: State<HistoryState>, id: i64) -> Result<(), String> {
     let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let detail = db::fetch_rent_detail(&conn, id)
+        .map_err(|e| e.to_string())?
+        .ok_or("地代家賃の内訳が見つかりません")?;
+    ensure_years_open(&conn, detail.year, detail.year)?;
     db::delete_rent_detail(&conn, id).map_err(|e| e.to_string())?;
+    record(
+        &history,
+        format!("地代家賃内訳の削除（{}）", detail.payee_name),
+        vec![Operation::DeleteRentDetail(detail)],
+    )
+}
+
+// ── 家事按分 ──
+
+#[tauri::command]
+pub fn get_apportionment_rules(state: State<DbState>) -> Result<Vec<ApportionmentRule>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::fetch_apportionment_rules(&conn).map_err(|e| e.to_string())
+}
+
+/// 費用科目の家事按分ルールを登録・更新する（帳簿に反映するには post_apportionment で振替仕訳を作成する）
+#[tauri::command]
+pub fn save_apportionment_rule(
+    state: State<DbState>,
+    account_id: i64,
+    business_ratio: i32,
+    basis: String,
+) -> Result<i64, String> {
+    if !(0..=100).contains(&business_ratio) {
+        return Err("事業割合は0〜100%で入力してください".to_string());
+    }
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let account = fetch_account(&conn, account_id)?;
+    if account.classification != "費用" {
+        return Err(format!("「{}」は費用科目ではないため家事按分できません", account.name));
+    }
+    db::save_apportionment_rule(&conn, account_id, business_ratio, basis.trim()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_apportionment_rule(state: State<DbState>, id: i64) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::delete_apportionment_rule(&conn, id).map_err(|e| e.to_string())?;
     Ok(())
 }
 
+/// 年度の家事按分の計算結果（振替仕訳は作成しない）
+#[tauri::command]
+pub fn preview_apportionment(state: State<DbState>, year: i32) -> Result<Vec<ApportionmentLine>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::calc_apportionment(&conn, year).map_err(|e| e.to_string())
+}
+
+/// 年度の家事按分の振替仕訳を作り直す（作成済みの振替仕訳は取り消す）。作成した仕訳の ID を返す
+#[tauri::command]
+pub fn post_apportionment(state: State<DbState>, history: State<HistoryState>, year: i32) -> Result<Vec<i64>, String> {
+    let (_, year_end) = period_dates(year, None)?;
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    ensure_date_open(&conn, year_end)?;
+    let previous = db::fetch_apportionment_entry_ids(&conn, year).map_err(|e| e.to_string())?;
+    for &id in &previous {
+        ensure_entry_open(&conn, id)?;
+    }
+    let lines = db::calc_apportionment(&conn, year).map_err(|e| e.to_string())?;
+    let ids = db::post_apportionment(&conn, year, &lines).map_err(|e| e.to_string())?;
+    let operations = previous
+        .into_iter()
+        .map(Operation::DeleteEntry)
+        .chain(ids.iter().map(|&id| Operation::AddEntry(id)))
+        .collect();
+    record(&history, format!("家事按分の振替（{}年）", year), operations)?;
+    Ok(ids)
+}
+
+// ── 外貨建取引 ──
+
+/// 通貨コード（英字3文字、円以外）を大文字にそろえて返す
+fn normalize_currency(currency: &str) -> Result<String, String> {
+    let currency = currency.trim().to_ascii_uppercase();
+    if currency.len() != 3 || !currency.bytes().all(|b| b.is_ascii_uppercase()) {
+        return Err(format!("通貨コードは英字3文字で入力してください: {}", currency));
+    }
+    if currency == "JPY" {
+        return Err("円建ての取引は通常の仕訳として登録してください".to_string());
+    }
+    Ok(currency)
+}
+
+/// 小数の文字列を 10^scale 倍した整数にする（浮動小数点を経由しないので誤差が出ない）
+/// scale 桁より細かい小数や負の値はエラーにする
+fn parse_scaled(value: &str, scale: u32, label: &str) -> Result<i64, String> {
+    let value = value.trim();
+    let (int_part, frac_part) = value.split_once('.').unwrap_or((value, ""));
+    let is_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if (int_part.is_empty() && frac_part.is_empty())
+        || frac_part.len() > scale as usize
+        || !is_digits(int_part)
+        || !is_digits(frac_part)
+    {
+        return Err(format!("{}は小数点以下{}桁までの数値で入力してください: {}", label, scale, value));
+    }
+    format!("{}{:0<width$}", int_part, frac_part, width = scale as usize)
+        .parse::<i64>()
+        .map_err(|_| format!("{}が大きすぎます: {}", label, value))
+}
+
+/// 為替レート CSV の1行（日付,通貨,TTM）を読み取る
+fn parse_rate_row(fields: &[&str]) -> Result<ExchangeRate, String> {
+    let &[date, currency, ttm] = fields else {
+        return Err("日付,通貨,TTM の3列で入力してください".to_string());
+    };
+    let date = Date::parse(date)?;
+    let currency = normalize_currency(currency)?;
+    let ttm = parse_scaled(ttm, db::RATE_SCALE, "TTM")?;
+    if ttm <= 0 {
+        return Err("TTM は0より大きい値を入力してください".to_string());
+    }
+    Ok(ExchangeRate { currency, date: date.to_string(), ttm })
+}
+
+#[tauri::command]
+pub fn get_exchange_rates(
+    state: State<DbState>,
+    currency: Option<String>,
+    year: Option<i32>,
+) -> Result<Vec<ExchangeRate>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::fetch_exchange_rates(&conn, currency.as_deref(), year).map_err(|e| e.to_string())
+}
+
+/// date 以前で直近の TTM（7日以内に登録がなければ None）
+#[tauri::command]
+pub fn get_exchange_rate(state: State<DbState>, currency: String, date: Date) -> Result<Option<ExchangeRate>, String> {
+    let currency = normalize_currency(&currency)?;
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::find_exchange_rate(&conn, &currency, date.0).map_err(|e| e.to_string())
+}
+
+/// 為替レートを CSV（日付,通貨,TTM の3列。1行目が見出しなら読み飛ばす）から取り込む
+/// 1行でも読み取れない行があれば何も取り込まない
+#[tauri::command]
+pub fn import_exchange_rates(state: State<DbState>, csv: String) -> Result<ExchangeRateImportResult, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(csv.trim_start_matches('\u{feff}').as_bytes());
+    let mut rates = Vec::new();
+    let mut errors = Vec::new();
+    for (i, record) in reader.records().enumerate() {
+        // 行番号は CSV の行（空行も数える）で示す
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                let row = e.position().map_or(i + 1, |p| p.line() as usize);
+                errors.push(EntryBatchError { row, message: format!("CSV を読み取れません: {}", e) });
+                continue;
+            }
+        };
+        let row = record.position().map_or(i + 1, |p| p.line() as usize);
+        let fields: Vec<&str> = record.iter().collect();
+        if i == 0 && Date::parse(fields[0]).is_err() {
+            continue;
+        }
+        match parse_rate_row(&fields) {
+            Ok(rate) => rates.push(rate),
+            Err(message) => errors.push(EntryBatchError { row, message }),
+        }
+    }
+    if !errors.is_empty() {
+        return Ok(ExchangeRateImportResult { imported: 0, errors });
+    }
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let imported = db::import_exchange_rates(&conn, &rates).map_err(|e| e.to_string())?;
+    Ok(ExchangeRateImportResult { imported, errors })
+}
+
+/// 入力された通貨・外貨額・換算レートを検証する。レートを省略すると date 以前で直近の TTM を使う
+fn resolve_foreign_amount(
+    conn: &Connection,
+    date: Date,
+    currency: &str,
+    foreign_amount: &str,
+    exchange_rate: Option<&str>,
+    foreign_side: &str,
+) -> Result<ForeignAmount, String> {
+    if !["借方", "貸方", "両方"].contains(&foreign_side) {
+        return Err("外貨建ての科目の側は 借方・貸方・両方 のいずれかを指定してください".to_string());
+    }
+    let currency = normalize_currency(currency)?;
+    let foreign_amount = parse_scaled(foreign_amount, db::FOREIGN_AMOUNT_SCALE, "外貨額")?;
+    let exchange_rate = match exchange_rate {
+        Some(rate) => parse_scaled(rate, db::RATE_SCALE, "換算レート")?,
+        None => {
+            db::find_exchange_rate(conn, &currency, date.0)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| {
+                    format!("{}の{}以前{}日以内の TTM が登録されていません", currency, date, db::RATE_LOOKBACK_DAYS)
+                })?
+                .ttm
+        }
+    };
+    if exchange_rate == 0 {
+        return Err("換算レートは0より大きい値を入力してください".to_string());
+    }
+    Ok(ForeignAmount { currency, foreign_amount, exchange_rate, foreign_side: foreign_side.to_string() })
+}
+
+/// 外貨建ての単一行仕訳を登録する
+/// 外貨額（小数2桁まで）・換算レート（小数4桁まで）は誤差が出ないよう小数の文字列で受け取る
+/// exchange_rate を省略すると取引日以前で直近の TTM を使い、円換算額は外貨額 × レート（1円未満切り捨て）
+/// 銀行の受取額などで円換算額が決まっている場合は amount を指定する
+/// （外貨額0・amount 指定で、決済時の為替差額を外貨建ての債権などに計上できる）
+#[tauri::command]
+pub fn add_foreign_entry(
+    state: State<DbState>,
+    history: State<HistoryState>,
+    date: Date,
+    debit_account_id: i64,
+    credit_account_id: i64,
+    description: String,
+    partner_id: Option<i64>,
+    currency: String,
+    foreign_amount: String,
+    exchange_rate: Option<String>,
+    foreign_side: String,
+    amount: Option<i64>,
+) -> Result<EntryAddResult, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    ensure_date_open(&conn, date)?;
+    let foreign =
+        resolve_foreign_amount(&conn, date, &currency, &foreign_amount, exchange_rate.as_deref(), &foreign_side)?;
+    let amount = amount.unwrap_or_else(|| db::convert_to_yen(foreign.foreign_amount, foreign.exchange_rate));
+    if amount <= 0 {
+        return Err("円換算額が1円未満です".to_string());
+    }
+    let id = db::insert_foreign_entry(
+        &conn, &date.to_string(), debit_account_id, credit_account_id, amount, &description, partner_id, &foreign,
+    ).map_err(|e| e.to_string())?;
+    record(&history, format!("仕訳の登録（{}）", entry_label(&date.to_string(), &description)), vec![Operation::AddEntry(id)])?;
+    let duplicates = find_duplicates_of(&conn, id, date);
+    Ok(EntryAddResult { id, duplicates })
+}
+
+#[tauri::command]
+pub fn get_foreign_currency_report(state: State<DbState>, year: i32) -> Result<ForeignCurrencyReport, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::calc_foreign_currency_report(&conn, year).map_err(|e| e.to_string())
+}
+
+// ── 消込 ──
+
+/// 借方の計上（売掛金・立替金・仮払金）を貸方の入金・精算で消し込む
+/// amount を省略すると、双方の未消込額のうち小さい方を消し込む
+/// 締め済みの期間の残高の内訳が変わらないよう、双方の仕訳が締めていない期間にある場合だけ消し込める
+#[tauri::command]
+pub fn match_items(
+    state: State<DbState>,
+    history: State<HistoryState>,
+    account_id: i64,
+    debit_entry_id: i64,
+    credit_entry_id: i64,
+    amount: Option<i64>,
+) -> Result<i64, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let account = db::fetch_account(&conn, account_id)
+        .map_err(|e| e.to_string())?
+        .ok_or("科目が見つかりません")?;
+    if !account.is_matching {
+        return Err(format!("「{}」は消込の対象科目ではありません", account.name));
+    }
+    ensure_entry_open(&conn, debit_entry_id)?;
+    ensure_entry_open(&conn, credit_entry_id)?;
+    let debit_open = db::unmatched_amount(&conn, debit_entry_id, account_id, "借方").map_err(|e| e.to_string())?;
+    if debit_open <= 0 {
+        return Err(format!("仕訳 ID {} の借方に「{}」の未消込額がありません", debit_entry_id, account.name));
+    }
+    let credit_open = db::unmatched_amount(&conn, credit_entry_id, account_id, "貸方").map_err(|e| e.to_string())?;
+    if credit_open <= 0 {
+        return Err(format!("仕訳 ID {} の貸方に「{}」の未消込額がありません", credit_entry_id, account.name));
+    }
+    let limit = debit_open.min(credit_open);
+    let amount = amount.unwrap_or(limit);
+    if amount <= 0 || amount > limit {
+        return Err(format!("消込額は1円以上{}円以下で入力してください", limit));
+    }
+    let id = db::insert_item_match(&conn, account_id, debit_entry_id, credit_entry_id, amount).map_err(|e| e.to_string())?;
+    let item_match = fetch_item_match(&conn, id)?;
+    record(&history, match_label("消込", &item_match), vec![Operation::AddItemMatch(item_match)])?;
+    Ok(id)
+}
+
+#[tauri::command]
+pub fn unmatch_items(state: State<DbState>, history: State<HistoryState>, id: i64) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let item_match = fetch_item_match(&conn, id)?;
+    ensure_match_open(&conn, &item_match)?;
+    db::delete_item_match(&conn, id).map_err(|e| e.to_string())?;
+    record(&history, match_label("消込の解除", &item_match), vec![Operation::DeleteItemMatch(item_match)])
+}
+
+fn fetch_item_match(conn: &Connection, id: i64) -> Result<ItemMatch, String> {
+    db::fetch_item_match(conn, id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "消込が見つかりません".to_string())
+}
+
+/// 消込の双方の仕訳が締めていない期間にあることを確認する
+fn ensure_match_open(conn: &Connection, item_match: &ItemMatch) -> Result<(), String> {
+    ensure_stored_date_open(conn, &item_match.debit_date)?;
+    ensure_stored_date_open(conn, &item_match.credit_date)
+}
+
+fn match_label(action: &str, item_match: &ItemMatch) -> String {
+    format!(
+        "{}（{} ← {}）",
+        action,
+        entry_label(&item_match.debit_date, &item_match.debit_description),
+        entry_label(&item_match.credit_date, &item_match.credit_description)
+    )
+}
+
+#[tauri::command]
+pub fn get_item_matches(state: State<DbState>, entry_id: i64) -> Result<Vec<ItemMatch>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::fetch_item_matches(&conn, entry_id).map_err(|e| e.to_string())
+}
+
+/// 基準日時点の未消込の年齢表
+#[tauri::command]
+pub fn get_aging_report(state: State<DbState>, as_of: Date, account_id: Option<i64>) -> Result<AgingReport, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::calc_aging_report(&conn, as_of.0, account_id).map_err(|e| e.to_string())
+}
+
 // ── 純損失の繰越控除 ──
 
 #[tauri::command]
@@ -195,6 +1873,7 @@ pub fn get_loss_carryforwards(state: State<DbState>) -> Result<Vec<LossCarryforw
 #[tauri::command]
 pub fn add_loss_carryforward(
     state: State<DbState>,
+    history: State<HistoryState>,
     loss_year: i32,
     loss_amount: i64,
     memo: String,
@@ -203,29 +1882,52 @@ pub fn add_loss_carryforward(
         return Err("繰越損失額は1円以上を入力してください".to_string());
     }
     let conn = state.0.lock().map_err(|e| e.to_string())?;
-    db::insert_loss_carryforward(&conn, loss_year, loss_amount, &memo)
-        .map_err(|e| e.to_string())
+    // 損失年度と繰越控除を適用する3年間の申告に影響する
+    ensure_years_open(&conn, loss_year, loss_year + 3)?;
+    let id = db::insert_loss_carryforward(&conn, loss_year, loss_amount, &memo)
+        .map_err(|e| e.to_string())?;
+    if let Some(loss) = db::fetch_loss_carryforward(&conn, id).map_err(|e| e.to_string())? {
+        record(&history, format!("繰越損失の登録（{}年分）", loss_year), vec![Operation::AddLossCarryforward(loss)])?;
+    }
+    Ok(id)
 }
 
 #[tauri::command]
 pub fn update_loss_carryforward(
     state: State<DbState>,
+    history: State<HistoryState>,
     id: i64,
     used_year_1: i64,
     used_year_2: i64,
     used_year_3: i64,
 ) -> Result<(), String> {
     let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let loss = db::fetch_loss_carryforward(&conn, id)
+        .map_err(|e| e.to_string())?
+        .ok_or("繰越損失が見つかりません")?;
+    ensure_years_open(&conn, loss.loss_year, loss.loss_year + 3)?;
     db::update_loss_carryforward_usage(&conn, id, used_year_1, used_year_2, used_year_3)
         .map_err(|e| e.to_string())?;
-    Ok(())
+    record(
+        &history,
+        format!("繰越損失の控除額の変更（{}年分）", loss.loss_year),
+        vec![Operation::UpdateLossCarryforward(loss)],
+    )
 }
 
 #[tauri::command]
-pub fn delete_loss_carryforward(state: State<DbState>, id: i64) -> Result<(), String> {
+pub fn delete_loss_carryforward(state: State<DbState>, history: State<HistoryState>, id: i64) -> Result<(), String> {
     let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let loss = db::fetch_loss_carryforward(&conn, id)
+        .map_err(|e| e.to_string())?
+        .ok_or("繰越損失が見つかりません")?;
+    ensure_years_open(&conn, loss.loss_year, loss.loss_year + 3)?;
     db::delete_loss_carryforward(&conn, id).map_err(|e| e.to_string())?;
-    Ok(())
+    record(
+        &history,
+        format!("繰越損失の削除（{}年分）", loss.loss_year),
+        vec![Operation::DeleteLossCarryforward(loss)],
+    )
 }
 
 #[tauri::command]
@@ -254,20 +1956,46 @@ pub fn export_journal_csv(
     month: Option<i32>,
 ) -> Result<String, String> {
     let conn = state.0.lock().map_err(|e| e.to_string())?;
-    let entries = db::fetch_entries(&conn, year, month).map_err(|e| e.to_string())?;
+    let entries = db::fetch_entries(&conn, year, month, None, None).map_err(|e| e.to_string())?;
 
     let mut wtr = csv::Writer::from_writer(Vec::new());
-    wtr.write_record(["日付", "借方科目", "借方金額", "貸方科目", "貸方金額", "摘要"])
+    wtr.write_record(["伝票番号", "日付", "借方科目", "借方金額", "貸方科目", "貸方金額", "摘要", "メモ"])
         .map_err(|e| e.to_string())?;
 
     for entry in &entries {
+        let voucher_no = entry.voucher_no.map(|n| n.to_string()).unwrap_or_default();
+        if entry.is_compound {
+            // 複合仕訳は明細ごとに1行ずつ出力する
+            let lines = db::fetch_entry_lines(&conn, entry.id).map_err(|e| e.to_string())?;
+            for line in &lines {
+                let amount = line.amount.to_string();
+                let (debit_name, debit_amount, credit_name, credit_amount) = match line.side.as_str() {
+                    "借方" => (line.account_name.as_str(), amount.as_str(), "", ""),
+                    _ => ("", "", line.account_name.as_str(), amount.as_str()),
+                };
+                wtr.write_record([
+                    &voucher_no,
+                    &entry.date,
+                    debit_name,
+                    debit_amount,
+                    credit_name,
+                    credit_amount,
+                    &entry.description,
+                    &entry.note,
+                ])
+                .map_err(|e| e.to_string())?;
+            }
+            continue;
+        }
         wtr.write_record([
+            &voucher_no,
             &entry.date,
             entry.debit_account_name.as_deref().unwrap_or(""),
             &entry.debit_amount.to_string(),
             entry.credit_account_name.as_deref().unwrap_or(""),
             &entry.credit_amount.to_string(),
             &entry.description,
+            &entry.note,
         ])
         .map_err(|e| e.to_string())?;
     }
@@ -279,6 +2007,353 @@ pub fn export_journal_csv(
     Ok(csv_string)
 }
 
+// ── 仕訳テンプレート ──
+
+fn validate_template(template: &JournalTemplateInput) -> Result<(), String> {
+    if template.label.trim().is_empty() {
+        return Err("項目名を入力してください".to_string());
+    }
+    if template.default_amount.is_some_and(|a| a <= 0) {
+        return Err("金額は1円以上を入力してください".to_string());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_journal_templates(state: State<DbState>) -> Result<Vec<JournalTemplate>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::fetch_journal_templates(&conn).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn add_journal_template(state: State<DbState>, template: JournalTemplateInput) -> Result<i64, String> {
+    validate_template(&template)?;
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::insert_journal_template(&conn, &template).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn update_journal_template(
+    state: State<DbState>,
+    id: i64,
+    template: JournalTemplateInput,
+) -> Result<(), String> {
+    validate_template(&template)?;
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::update_journal_template(&conn, id, &template).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_journal_template(state: State<DbState>, id: i64) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::delete_journal_template(&conn, id).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// テンプレートの科目で仕訳を登録する（金額・摘要を省略するとテンプレートの既定値を使う）
+/// add_entry と同じく、重複の疑いがある仕訳を返す
+#[tauri::command]
+pub fn add_entry_from_template(
+    state: State<DbState>,
+    history: State<HistoryState>,
+    template_id: i64,
+    date: Date,
+    amount: Option<i64>,
+    description: Option<String>,
+    partner_id: Option<i64>,
+    segment_id: Option<i64>,
+) -> Result<EntryAddResult, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let template = db::fetch_journal_template(&conn, template_id)
+        .map_err(|e| e.to_string())?
+        .ok_or("テンプレートが見つかりません")?;
+    let amount = amount
+        .or(template.default_amount)
+        .ok_or("金額を入力してください")?;
+    if amount <= 0 {
+        return Err("金額は1円以上を入力してください".to_string());
+    }
+    let description = description
+        .filter(|d| !d.trim().is_empty())
+        .unwrap_or(template.default_description);
+    ensure_date_open(&conn, date)?;
+    let id = db::insert_entry(
+        &conn, &date.to_string(), template.debit_account_id, amount, template.credit_account_id, amount, &description, partner_id,
+        None, None, segment_id,
+    ).map_err(|e| e.to_string())?;
+    record(&history, format!("仕訳の登録（{}）", entry_label(&date.to_string(), &description)), vec![Operation::AddEntry(id)])?;
+    let duplicates = find_duplicates_of(&conn, id, date);
+    Ok(EntryAddResult { id, duplicates })
+}
+
+// ── 定期仕訳 ──
+
+/// 定期仕訳の定義を検証する
+fn validate_recurring(
+    amount: i64,
+    frequency: &str,
+    day_of_month: u32,
+    start_date: Date,
+    end_date: Option<Date>,
+) -> Result<(), String> {
+    if amount <= 0 {
+        return Err("金額は1円以上を入力してください".to_string());
+    }
+    if !matches!(frequency, "毎月" | "隔月" | "毎年" | "指定日" | "月末") {
+        return Err(format!("不正な頻度です: {}", frequency));
+    }
+    if !(1..=31).contains(&day_of_month) {
+        return Err("計上日は1〜31日で入力してください".to_string());
+    }
+    if let Some(end) = end_date {
+        if end < start_date {
+            return Err("終了日は開始日以降を入力してください".to_string());
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_recurring_entries(state: State<DbState>) -> Result<Vec<RecurringEntry>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::fetch_recurring_entries(&conn).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn add_recurring_entry(
+    state: State<DbState>,
+    name: String,
+    debit_account_id: i64,
+    credit_account_id: i64,
+    amount: i64,
+    description_template: String,
+    frequency: String,
+    day_of_month: u32,
+    start_date: Date,
+    end_date: Option<Date>,
+) -> Result<i64, String> {
+    validate_recurring(amount, &frequency, day_of_month, start_date, end_date)?;
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::insert_recurring_entry(
+        &conn, &name, debit_account_id, credit_account_id, amount,
+        &description_template, &frequency, day_of_month,
+        &start_date.to_string(), end_date.map(|d| d.to_string()).as_deref(),
+    ).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn update_recurring_entry(
+    state: State<DbState>,
+    id: i64,
+    name: String,
+    debit_account_id: i64,
+    credit_account_id: i64,
+    amount: i64,
+    description_template: String,
+    frequency: String,
+    day_of_month: u32,
+    start_date: Date,
+    end_date: Option<Date>,
+    is_active: bool,
+) -> Result<(), String> {
+    validate_recurring(amount, &frequency, day_of_month, start_date, end_date)?;
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::update_recurring_entry(
+        &conn, id, &name, debit_account_id, credit_account_id, amount,
+        &description_template, &frequency, day_of_month,
+        &start_date.to_string(), end_date.map(|d| d.to_string()).as_deref(), is_active,
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_recurring_entry(state: State<DbState>, id: i64) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::delete_recurring_entry(&conn, id).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// until までの未計上の定期仕訳を作成する（計上済みの日付は作成しないため、何度実行してもよい）
+/// 作成した仕訳は1回の操作として元に戻せる（元に戻しても計上済みの扱いは残り、再実行で作り直さない）
+#[tauri::command]
+pub fn generate_recurring_entries(
+    state: State<DbState>,
+    history: State<HistoryState>,
+    until: Date,
+) -> Result<RecurringRunResult, String> {
+    let until = until.0;
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let pending = db::fetch_pending_recurring(&conn, until).map_err(|e| e.to_string())?;
+
+    let mut result = RecurringRunResult { created: Vec::new(), skipped: Vec::new() };
+    let mut failure = None;
+    for (entry, due_date) in pending {
+        if let Err(reason) = ensure_date_open(&conn, due_date) {
+            result.skipped.push(RecurringSkip {
+                recurring_id: entry.id,
+                name: entry.name,
+                due_date,
+                reason,
+            });
+            continue;
+        }
+        let entry_id = match db::run_recurring_entry(&conn, &entry, due_date) {
+            Ok(entry_id) => entry_id,
+            Err(e) => {
+                failure = Some(e.to_string());
+                break;
+            }
+        };
+        result.created.push(RecurringRun {
+            recurring_id: entry.id,
+            name: entry.name,
+            due_date,
+            entry_id,
+        });
+    }
+    // 途中で失敗しても、それまでに作成した仕訳は元に戻せるよう記録する
+    record(
+        &history,
+        format!("定期仕訳の計上（{}件）", result.created.len()),
+        result.created.iter().map(|r| Operation::AddEntry(r.entry_id)).collect(),
+    )?;
+    match failure {
+        Some(e) => Err(e),
+        None => Ok(result),
+    }
+}
+
+// ── 証憑 ──
+
+fn ensure_entry_exists(conn: &Connection, entry_id: i64) -> Result<(), String> {
+    db::fetch_entry_date(conn, entry_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("仕訳が見つかりません（ID: {}）", entry_id))?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_attachments(state: State<DbState>, entry_id: Option<i64>) -> Result<Vec<Attachment>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::fetch_attachments(&conn, entry_id).map_err(|e| e.to_string())
+}
+
+/// ファイルを証憑として保存し、仕訳に紐付ける
+/// 同じ内容のファイルが登録済みなら、新たに登録せず既存の証憑を紐付ける（reused = true。
+/// その場合 received_date・memo は使わず、登録済みの受領日・メモのまま）
+/// ファイルは登録が成功してから保存し、保存できなければ登録も取り消す
+#[tauri::command]
+pub fn attach_file(
+    app_handle: tauri::AppHandle,
+    state: State<DbState>,
+    path: String,
+    entry_ids: Vec<i64>,
+    received_date: Date,
+    memo: String,
+) -> Result<AttachFileResult, String> {
+    let source = std::path::Path::new(&path);
+    if !source.is_file() {
+        return Err(format!("ファイルが見つかりません: {}", path));
+    }
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    for &entry_id in &entry_ids {
+        ensure_entry_exists(&conn, entry_id)?;
+    }
+    let dir = attachments::attachments_dir(&app_handle)?;
+    let file = attachments::inspect_file(source)?;
+    conn.execute_batch("SAVEPOINT attach_file").map_err(|e| e.to_string())?;
+    let result = register_attachment(&conn, &file, &entry_ids, received_date, &memo).and_then(|(id, reused)| {
+        let attachment = db::fetch_attachment(&conn, id)
+            .map_err(|e| e.to_string())?
+            .ok_or("証憑が見つかりません")?;
+        // 登録済みの証憑は拡張子が違っても既存の保存名で保存し、消えた・改変されたファイルを復元する
+        attachments::store_file(&dir, source, &attachment.stored_name, &attachment.sha256)?;
+        Ok(AttachFileResult { attachment, reused })
+    });
+    match result {
+        Ok(result) => {
+            conn.execute_batch("RELEASE attach_file").map_err(|e| e.to_string())?;
+            Ok(result)
+        }
+        Err(e) => {
+            conn.execute_batch("ROLLBACK TO attach_file; RELEASE attach_file").map_err(|e| e.to_string())?;
+            Err(e)
+        }
+    }
+}
+
+/// 証憑を登録（同じハッシュが登録済みならそれを使う）して仕訳に紐付け、(ID, 既存の証憑か) を返す
+fn register_attachment(
+    conn: &Connection,
+    file: &attachments::StoredFile,
+    entry_ids: &[i64],
+    received_date: Date,
+    memo: &str,
+) -> Result<(i64, bool), String> {
+    let existing = db::find_attachment_by_hash(conn, &file.sha256).map_err(|e| e.to_string())?;
+    let id = match existing {
+        Some(id) => id,
+        None => db::insert_attachment(
+            conn, &file.file_name, &file.stored_name, &file.mime_type, file.size,
+            &file.sha256, &received_date.to_string(), memo,
+        )
+        .map_err(|e| e.to_string())?,
+    };
+    for &entry_id in entry_ids {
+        db::link_attachment(conn, id, entry_id).map_err(|e| e.to_string())?;
+    }
+    Ok((id, existing.is_some()))
+}
+
+#[tauri::command]
+pub fn link_attachment(state: State<DbState>, attachment_id: i64, entry_id: i64) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    ensure_entry_exists(&conn, entry_id)?;
+    db::link_attachment(&conn, attachment_id, entry_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn unlink_attachment(state: State<DbState>, attachment_id: i64, entry_id: i64) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::unlink_attachment(&conn, attachment_id, entry_id).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 証憑ファイルを既定のアプリで開く（登録後に内容が変わっていれば開かない）
+#[tauri::command]
+pub fn open_attachment(app_handle: tauri::AppHandle, state: State<DbState>, id: i64) -> Result<(), String> {
+    let attachment = {
+        let conn = state.0.lock().map_err(|e| e.to_string())?;
+        db::fetch_attachment(&conn, id)
+            .map_err(|e| e.to_string())?
+            .ok_or("証憑が見つかりません")?
+    };
+    let dir = attachments::attachments_dir(&app_handle)?;
+    match attachments::verify(&dir, &attachment.stored_name, &attachment.sha256) {
+        "正常" => open::that(dir.join(&attachment.stored_name)).map_err(|e| e.to_string()),
+        status => Err(format!("証憑「{}」を開けません（{}）", attachment.file_name, status)),
+    }
+}
+
+/// 保存済みのすべての証憑ファイルが登録時から変更されていないか確認する
+#[tauri::command]
+pub fn verify_attachments(app_handle: tauri::AppHandle, state: State<DbState>) -> Result<Vec<AttachmentCheck>, String> {
+    let attachments = {
+        let conn = state.0.lock().map_err(|e| e.to_string())?;
+        db::fetch_attachments(&conn, None).map_err(|e| e.to_string())?
+    };
+    let dir = attachments::attachments_dir(&app_handle)?;
+    Ok(attachments
+        .into_iter()
+        .map(|a| AttachmentCheck {
+            status: attachments::verify(&dir, &a.stored_name, &a.sha256).to_string(),
+            id: a.id,
+            file_name: a.file_name,
+        })
+        .collect())
+}
+
 // ── バックアップ ──
 
 #[tauri::command]
@@ -297,3 +2372,43 @@ pub fn backup_database(app_handle: tauri::AppHandle) -> Result<String, String> {
 
     Ok(backup_path.to_string_lossy().to_string())
 }
+
+// ── 日付チェック ──
+
+/// YYYY-MM-DD として読み取れない保存済みの日付（旧バージョンで登録され、自動で直せなかったもの）
+#[tauri::command]
+pub fn get_invalid_dates(state: State<DbState>) -> Result<Vec<InvalidDate>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    db::fetch_invalid_dates(&conn).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(side: &str, amount: i64) -> JournalLineInput {
+        JournalLineInput { side: side.to_string(), account_id: 1, sub_account_id: None, amount }
+    }
+
+    #[test]
+    fn validate_lines_requires_balanced_sides() {
+        assert!(validate_lines(&[line("借方", 300_000), line("貸方", 40_000), line("貸方", 260_000)]).is_ok());
+        assert_eq!(
+            validate_lines(&[line("借方", 1000), line("貸方", 900)]),
+            Err("借方合計(1000)と貸方合計(900)が一致しません".to_string())
+        );
+        assert!(validate_lines(&[line("借方", 1000)]).is_err());
+        assert!(validate_lines(&[line("借方", 0), line("貸方", 0)]).is_err());
+        assert!(validate_lines(&[line("借方", 1000), line("相殺", 1000)]).is_err());
+    }
+
+    #[test]
+    fn validate_entry_query_rejects_unknown_sort() {
+        for key in db::ENTRY_SORT_KEYS {
+            let query = EntrySearchQuery { sort: Some(key.to_string()), ..Default::default() };
+            assert!(validate_entry_query(&query).is_ok());
+        }
+        let query = EntrySearchQuery { sort: Some("ammount".to_string()), ..Default::default() };
+        assert_eq!(validate_entry_query(&query), Err("不正な並び替え項目です: ammount".to_string()));
+    }
+}
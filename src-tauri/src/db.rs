@@ -1,4 +1,6 @@
-use rusqlite::{params, Connection, Result as SqlResult};
+use chrono::{Datelike, NaiveDate};
+use rusqlite::{params, Connection, OptionalExtension, Result as SqlResult};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::PathBuf;
 
 use crate::models::*;
@@ -6,6 +8,58 @@ use crate::models::*;
 const MIGRATION_001: &str = include_str!("../migrations/001_init.sql");
 const MIGRATION_002: &str = include_str!("../migrations/002_financial_statements.sql");
 const MIGRATION_003: &str = include_str!("../migrations/003_loss_carryforward.sql");
+const MIGRATION_004: &str = include_str!("../migrations/004_journal_lines.sql");
+const MIGRATION_005: &str = include_str!("../migrations/005_opening_balances.sql");
+const MIGRATION_006: &str = include_str!("../migrations/006_year_closings.sql");
+const MIGRATION_007: &str = include_str!("../migrations/007_period_locks.sql");
+const MIGRATION_008: &str = include_str!("../migrations/008_entry_revisions.sql");
+const MIGRATION_009: &str = include_str!("../migrations/009_entry_reversals.sql");
+const MIGRATION_010: &str = include_str!("../migrations/010_voucher_numbers.sql");
+const MIGRATION_011: &str = include_str!("../migrations/011_recurring_entries.sql");
+const MIGRATION_012: &str = include_str!("../migrations/012_journal_templates.sql");
+const MIGRATION_013: &str = include_str!("../migrations/013_partners.sql");
+const MIGRATION_014: &str = include_str!("../migrations/014_sub_accounts.sql");
+const MIGRATION_015: &str = include_str!("../migrations/015_segments.sql");
+const MIGRATION_016: &str = include_str!("../migrations/016_entry_search.sql");
+const MIGRATION_017: &str = include_str!("../migrations/017_entry_paging.sql");
+const MIGRATION_018: &str = include_str!("../migrations/018_revision_restore.sql");
+const MIGRATION_019: &str = include_str!("../migrations/019_account_status.sql");
+const MIGRATION_020: &str = include_str!("../migrations/020_duplicate_dismissals.sql");
+const MIGRATION_021: &str = include_str!("../migrations/021_attachments.sql");
+const MIGRATION_022: &str = include_str!("../migrations/022_date_normalization.sql");
+const MIGRATION_023: &str = include_str!("../migrations/023_entry_review.sql");
+const MIGRATION_024: &str = include_str!("../migrations/024_apportionment.sql");
+const MIGRATION_025: &str = include_str!("../migrations/025_foreign_currency.sql");
+const MIGRATION_026: &str = include_str!("../migrations/026_item_matching.sql");
+const MIGRATION_027: &str = include_str!("../migrations/027_rent_detail_year.sql");
+
+/// 004 以降のマイグレーション（PRAGMA user_version で適用済みバージョンを管理）
+const VERSIONED_MIGRATIONS: &[(i32, &str)] = &[
+    (4, MIGRATION_004),
+    (5, MIGRATION_005),
+    (6, MIGRATION_006),
+    (7, MIGRATION_007),
+    (8, MIGRATION_008),
+    (9, MIGRATION_009),
+    (10, MIGRATION_010),
+    (11, MIGRATION_011),
+    (12, MIGRATION_012),
+    (13, MIGRATION_013),
+    (14, MIGRATION_014),
+    (15, MIGRATION_015),
+    (16, MIGRATION_016),
+    (17, MIGRATION_017),
+    (18, MIGRATION_018),
+    (19, MIGRATION_019),
+    (20, MIGRATION_020),
+    (21, MIGRATION_021),
+    (22, MIGRATION_022),
+    (23, MIGRATION_023),
+    (24, MIGRATION_024),
+    (25, MIGRATION_025),
+    (26, MIGRATION_026),
+    (27, MIGRATION_027),
+];
 
 pub fn get_db_path(app_handle: &tauri::AppHandle) -> PathBuf {
     let app_dir = app_handle
@@ -18,18 +72,65 @@ pub fn get_db_path(app_handle: &tauri::AppHandle) -> PathBuf {
 
 pub fn init_db(path: &PathBuf) -> SqlResult<Connection> {
     let conn = Connection::open(path)?;
-    conn.execute_batch("PRAGMA journal_mode=WAL; PRAGMA foreign_keys=ON;")?;
+    conn.execute_batch("PRAGMA journal_mode=WAL;")?;
+    migrate(&conn)?;
+    Ok(conn)
+}
+
+/// 外部キー制約を有効にし、スキーマを最新の状態にする
+fn migrate(conn: &Connection) -> SqlResult<()> {
+    conn.execute_batch("PRAGMA foreign_keys=ON;")?;
     conn.execute_batch(MIGRATION_001)?;
     conn.execute_batch(MIGRATION_002)?;
     conn.execute_batch(MIGRATION_003)?;
-    Ok(conn)
+    apply_versioned_migrations(conn)
+}
+
+/// ALTER TABLE を含むマイグレーションは再実行できないため、未適用のものだけを順に適用する
+fn apply_versioned_migrations(conn: &Connection) -> SqlResult<()> {
+    let current: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    for &(version, sql) in VERSIONED_MIGRATIONS {
+        if version <= current {
+            continue;
+        }
+        in_savepoint(conn, || {
+            conn.execute_batch(sql)?;
+            conn.pragma_update(None, "user_version", version)
+        })?;
+    }
+    Ok(())
+}
+
+/// year 年 month 月（None なら年間）の初日と末日
+/// 存在しない月を指定された場合は、どの仕訳にも一致しない空の期間を返す
+fn period_bounds(year: i32, month: Option<i32>) -> (String, String) {
+    match Date::period(year, month) {
+        Some((first, last)) => (first.to_string(), last.to_string()),
+        None => (String::new(), String::new()),
+    }
+}
+
+/// 複数の更新をセーブポイント内で実行し、失敗時はまとめて取り消す
+/// （トランザクション内から呼ばれてもネストできるよう SAVEPOINT を使う）
+pub fn in_savepoint<T>(conn: &Connection, f: impl FnOnce() -> SqlResult<T>) -> SqlResult<T> {
+    conn.execute_batch("SAVEPOINT sp")?;
+    match f() {
+        Ok(value) => {
+            conn.execute_batch("RELEASE sp")?;
+            Ok(value)
+        }
+        Err(e) => {
+            conn.execute_batch("ROLLBACK TO sp; RELEASE sp")?;
+            Err(e)
+        }
+    }
 }
 
 // ── 勘定科目 ──
 
 pub fn fetch_accounts(conn: &Connection) -> SqlResult<Vec<Account>> {
     let mut stmt = conn.prepare(
-        "SELECT id, code, name, classification FROM accounts ORDER BY code",
+        "SELECT id, code, name, classification, is_active, is_matching FROM accounts ORDER BY code",
     )?;
     let rows = stmt.query_map([], |row| {
         Ok(Account {
@@ -37,11 +138,17 @@ pub fn fetch_accounts(conn: &Connection) -> SqlResult<Vec<Account>> {
             code: row.get(1)?,
             name: row.get(2)?,
             classification: row.get(3)?,
+            is_active: row.get(4)?,
+            is_matching: row.get(5)?,
         })
     })?;
     rows.collect()
 }
 
+pub fn fetch_account(conn: &Connection, id: i64) -> SqlResult<Option<Account>> {
+    Ok(fetch_accounts(conn)?.into_iter().find(|a| a.id == id))
+}
+
 pub fn insert_account(
     conn: &Connection,
     code: i32,
@@ -58,9 +165,345 @@ pub fn insert_account(
         code,
         name: name.to_string(),
         classification: classification.to_string(),
+        is_active: true,
+        is_matching: false,
     })
 }
 
+pub fn update_account(
+    conn: &Connection,
+    id: i64,
+    code: i32,
+    name: &str,
+    classification: &str,
+    is_active: bool,
+) -> SqlResult<usize> {
+    conn.execute(
+        "UPDATE accounts SET code = ?1, name = ?2, classification = ?3, is_active = ?4 WHERE id = ?5",
+        params![code, name, classification, is_active, id],
+    )
+}
+
+/// 勘定科目を使用している仕訳の年（取消済みを含む）と期首残高の年の範囲
+pub fn fetch_account_year_range(conn: &Connection, id: i64) -> SqlResult<Option<(i32, i32)>> {
+    conn.query_row(
+        "SELECT MIN(year), MAX(year) FROM (
+             SELECT CAST(substr(j.date, 1, 4) AS INTEGER) AS year
+             FROM journal_entries j
+             WHERE j.debit_account_id = ?1 OR j.credit_account_id = ?1
+                OR EXISTS (SELECT 1 FROM journal_lines l WHERE l.entry_id = j.id AND l.account_id = ?1)
+             UNION ALL
+             SELECT year FROM opening_balances WHERE account_id = ?1
+         )",
+        params![id],
+        |row| Ok(row.get::<_, Option<i32>>(0)?.zip(row.get::<_, Option<i32>>(1)?)),
+    )
+}
+
+/// 勘定科目を使用している仕訳の年月（取消済みを含む。形式が正しくない日付は除く）
+pub fn fetch_account_entry_months(conn: &Connection, id: i64) -> SqlResult<Vec<(i32, i32)>> {
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT CAST(substr(j.date, 1, 4) AS INTEGER), CAST(substr(j.date, 6, 2) AS INTEGER)
+         FROM journal_entries j
+         WHERE (j.debit_account_id = ?1 OR j.credit_account_id = ?1
+                OR EXISTS (SELECT 1 FROM journal_lines l WHERE l.entry_id = j.id AND l.account_id = ?1))
+           AND date(j.date) IS j.date
+         ORDER BY 1, 2",
+    )?;
+    let rows = stmt.query_map(params![id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    rows.collect()
+}
+
+/// 仕訳・期首残高・補助科目・テンプレート・定期仕訳から参照されている件数（取消済みの仕訳を含む）
+pub fn count_account_references(conn: &Connection, id: i64) -> SqlResult<i64> {
+    conn.query_row(
+        "SELECT (SELECT COUNT(*) FROM journal_lines WHERE account_id = ?1)
+              + (SELECT COUNT(*) FROM journal_entries WHERE debit_account_id = ?1 OR credit_account_id = ?1)
+              + (SELECT COUNT(*) FROM opening_balances WHERE account_id = ?1)
+              + (SELECT COUNT(*) FROM sub_accounts WHERE account_id = ?1)
+              + (SELECT COUNT(*) FROM journal_templates WHERE debit_account_id = ?1 OR credit_account_id = ?1)
+              + (SELECT COUNT(*) FROM recurring_entries WHERE debit_account_id = ?1 OR credit_account_id = ?1)",
+        params![id],
+        |row| row.get(0),
+    )
+}
+
+pub fn delete_account(conn: &Connection, id: i64) -> SqlResult<usize> {
+    conn.execute("DELETE FROM accounts WHERE id = ?1", params![id])
+}
+
+/// 削除した勘定科目を同じ ID で登録し直す（元に戻す操作用）
+pub fn restore_account(conn: &Connection, account: &Account) -> SqlResult<()> {
+    conn.execute(
+        "INSERT INTO accounts (id, code, name, classification, is_active, is_matching) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![account.id, account.code, account.name, account.classification, account.is_active, account.is_matching],
+    )?;
+    Ok(())
+}
+
+/// 勘定科目 from_id の仕訳・期首残高・補助科目・テンプレート・定期仕訳をすべて to_id に付け替えて
+/// from_id を削除する（区分が同じ科目同士で呼ぶこと）。付け替えた仕訳の件数を返す
+pub fn merge_accounts(conn: &Connection, from_id: i64, to_id: i64) -> SqlResult<usize> {
+    in_savepoint(conn, || {
+        let entry_ids: Vec<i64> = {
+            let mut stmt = conn.prepare(
+                "SELECT j.id FROM journal_entries j
+                 WHERE j.debit_account_id = ?1 OR j.credit_account_id = ?1
+                    OR EXISTS (SELECT 1 FROM journal_lines l WHERE l.entry_id = j.id AND l.account_id = ?1)
+                 ORDER BY j.id",
+            )?;
+            let rows = stmt.query_map(params![from_id], |row| row.get(0))?;
+            rows.collect::<SqlResult<_>>()?
+        };
+        let snapshots = entry_ids
+            .iter()
+            .map(|&id| fetch_entry_snapshot(conn, id))
+            .collect::<SqlResult<Vec<_>>>()?;
+
+        // 統合先に同名の補助科目があればそちらに寄せ、なければ補助科目ごと移す
+        conn.execute(
+            "UPDATE journal_lines SET sub_account_id = (
+                 SELECT t.id FROM sub_accounts s JOIN sub_accounts t ON t.name = s.name AND t.account_id = ?2
+                 WHERE s.id = journal_lines.sub_account_id
+             )
+             WHERE sub_account_id IN (
+                 SELECT s.id FROM sub_accounts s JOIN sub_accounts t ON t.name = s.name AND t.account_id = ?2
+                 WHERE s.account_id = ?1
+             )",
+            params![from_id, to_id],
+        )?;
+        conn.execute(
+            "DELETE FROM sub_accounts
+             WHERE account_id = ?1 AND name IN (SELECT name FROM sub_accounts WHERE account_id = ?2)",
+            params![from_id, to_id],
+        )?;
+        conn.execute("UPDATE sub_accounts SET account_id = ?2 WHERE account_id = ?1", params![from_id, to_id])?;
+
+        conn.execute("UPDATE journal_lines SET account_id = ?2 WHERE account_id = ?1", params![from_id, to_id])?;
+        conn.execute(
+            "UPDATE journal_entries SET debit_account_id = ?2 WHERE debit_account_id = ?1",
+            params![from_id, to_id],
+        )?;
+        conn.execute(
+            "UPDATE journal_entries SET credit_account_id = ?2 WHERE credit_account_id = ?1",
+            params![from_id, to_id],
+        )?;
+        conn.execute(
+            "INSERT INTO opening_balances (year, account_id, amount)
+             SELECT year, ?2, amount FROM opening_balances WHERE account_id = ?1
+             ON CONFLICT (year, account_id) DO UPDATE SET amount = amount + excluded.amount",
+            params![from_id, to_id],
+        )?;
+        conn.execute("DELETE FROM opening_balances WHERE account_id = ?1", params![from_id])?;
+        for table in ["journal_templates", "recurring_entries"] {
+            conn.execute(
+                &format!("UPDATE {table} SET debit_account_id = ?2 WHERE debit_account_id = ?1"),
+                params![from_id, to_id],
+            )?;
+            conn.execute(
+                &format!("UPDATE {table} SET credit_account_id = ?2 WHERE credit_account_id = ?1"),
+                params![from_id, to_id],
+            )?;
+        }
+        conn.execute("UPDATE item_matches SET account_id = ?2 WHERE account_id = ?1", params![from_id, to_id])?;
+        // 統合先に家事按分のルールがなければ引き継ぐ（あれば統合元のルールは科目とともに削除される）
+        conn.execute(
+            "UPDATE OR IGNORE apportionment_rules SET account_id = ?2 WHERE account_id = ?1",
+            params![from_id, to_id],
+        )?;
+        delete_account(conn, from_id)?;
+
+        for (&id, before) in entry_ids.iter().zip(&snapshots) {
+            record_revision(conn, id, "訂正", before.as_ref())?;
+        }
+        Ok(entry_ids.len())
+    })
+}
+
+// ── 補助科目 ──
+
+/// 補助科目の一覧（account_id を指定するとその科目の補助科目だけ）
+pub fn fetch_sub_accounts(conn: &Connection, account_id: Option<i64>) -> SqlResult<Vec<SubAccount>> {
+    let mut stmt = conn.prepare(
+        "SELECT s.id, s.account_id, a.code, a.name, s.name, s.is_active
+         FROM sub_accounts s
+         JOIN accounts a ON a.id = s.account_id
+         WHERE ?1 IS NULL OR s.account_id = ?1
+         ORDER BY a.code, s.id",
+    )?;
+    let rows = stmt.query_map(params![account_id], |row| {
+        Ok(SubAccount {
+            id: row.get(0)?,
+            account_id: row.get(1)?,
+            account_code: row.get(2)?,
+            account_name: row.get(3)?,
+            name: row.get(4)?,
+            is_active: row.get::<_, i32>(5)? != 0,
+        })
+    })?;
+    rows.collect()
+}
+
+/// 補助科目の親の勘定科目
+pub fn fetch_sub_account_parent(conn: &Connection, id: i64) -> SqlResult<Option<i64>> {
+    conn.query_row("SELECT account_id FROM sub_accounts WHERE id = ?1", params![id], |row| row.get(0))
+        .optional()
+}
+
+pub fn insert_sub_account(conn: &Connection, account_id: i64, name: &str) -> SqlResult<i64> {
+    conn.execute(
+        "INSERT INTO sub_accounts (account_id, name) VALUES (?1, ?2)",
+        params![account_id, name],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn update_sub_account(conn: &Connection, id: i64, name: &str, is_active: bool) -> SqlResult<usize> {
+    conn.execute(
+        "UPDATE sub_accounts SET name = ?1, is_active = ?2 WHERE id = ?3",
+        params![name, is_active, id],
+    )
+}
+
+/// 仕訳明細（取消済みの仕訳を含む）から参照されている件数
+pub fn count_sub_account_references(conn: &Connection, id: i64) -> SqlResult<i64> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM journal_lines WHERE sub_account_id = ?1",
+        params![id],
+        |row| row.get(0),
+    )
+}
+
+pub fn delete_sub_account(conn: &Connection, id: i64) -> SqlResult<usize> {
+    conn.execute("DELETE FROM sub_accounts WHERE id = ?1", params![id])
+}
+
+// ── 取引先 ──
+
+pub fn fetch_partners(conn: &Connection) -> SqlResult<Vec<Partner>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, kana, address, invoice_number, partner_type, memo
+         FROM partners
+         ORDER BY CASE WHEN kana = '' THEN name ELSE kana END, id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(Partner {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            kana: row.get(2)?,
+            address: row.get(3)?,
+            invoice_number: row.get(4)?,
+            partner_type: row.get(5)?,
+            memo: row.get(6)?,
+        })
+    })?;
+    rows.collect()
+}
+
+pub fn insert_partner(
+    conn: &Connection,
+    name: &str,
+    kana: &str,
+    address: &str,
+    invoice_number: &str,
+    partner_type: &str,
+    memo: &str,
+) -> SqlResult<i64> {
+    conn.execute(
+        "INSERT INTO partners (name, kana, address, invoice_number, partner_type, memo)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![name, kana, address, invoice_number, partner_type, memo],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn update_partner(
+    conn: &Connection,
+    id: i64,
+    name: &str,
+    kana: &str,
+    address: &str,
+    invoice_number: &str,
+    partner_type: &str,
+    memo: &str,
+) -> SqlResult<usize> {
+    conn.execute(
+        "UPDATE partners
+         SET name = ?1, kana = ?2, address = ?3, invoice_number = ?4, partner_type = ?5, memo = ?6
+         WHERE id = ?7",
+        params![name, kana, address, invoice_number, partner_type, memo, id],
+    )
+}
+
+/// 仕訳（取消済みを含む）・地代家賃の内訳から参照されている件数
+pub fn count_partner_references(conn: &Connection, id: i64) -> SqlResult<i64> {
+    conn.query_row(
+        "SELECT (SELECT COUNT(*) FROM journal_entries WHERE partner_id = ?1)
+              + (SELECT COUNT(*) FROM rent_details WHERE partner_id = ?1)",
+        params![id],
+        |row| row.get(0),
+    )
+}
+
+pub fn delete_partner(conn: &Connection, id: i64) -> SqlResult<usize> {
+    conn.execute("DELETE FROM partners WHERE id = ?1", params![id])
+}
+
+// ── セグメント ──
+
+pub fn fetch_segments(conn: &Connection) -> SqlResult<Vec<Segment>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, segment_type, memo, is_active
+         FROM segments
+         ORDER BY CASE segment_type WHEN '部門' THEN 0 WHEN 'プロジェクト' THEN 1 ELSE 2 END, name, id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(Segment {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            segment_type: row.get(2)?,
+            memo: row.get(3)?,
+            is_active: row.get::<_, i32>(4)? != 0,
+        })
+    })?;
+    rows.collect()
+}
+
+pub fn insert_segment(conn: &Connection, name: &str, segment_type: &str, memo: &str) -> SqlResult<i64> {
+    conn.execute(
+        "INSERT INTO segments (name, segment_type, memo) VALUES (?1, ?2, ?3)",
+        params![name, segment_type, memo],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn update_segment(
+    conn: &Connection,
+    id: i64,
+    name: &str,
+    segment_type: &str,
+    memo: &str,
+    is_active: bool,
+) -> SqlResult<usize> {
+    conn.execute(
+        "UPDATE segments SET name = ?1, segment_type = ?2, memo = ?3, is_active = ?4 WHERE id = ?5",
+        params![name, segment_type, memo, is_active, id],
+    )
+}
+
+/// 仕訳（取消済みを含む）から参照されている件数
+pub fn count_segment_references(conn: &Connection, id: i64) -> SqlResult<i64> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM journal_entries WHERE segment_id = ?1",
+        params![id],
+        |row| row.get(0),
+    )
+}
+
+pub fn delete_segment(conn: &Connection, id: i64) -> SqlResult<usize> {
+    conn.execute("DELETE FROM segments WHERE id = ?1", params![id])
+}
+
 // ── 仕訳 ──
 
 pub fn insert_entry(
@@ -71,63 +514,329 @@ pub fn insert_entry(
     credit_account_id: i64,
     credit_amount: i64,
     description: &str,
+    partner_id: Option<i64>,
+    debit_sub_account_id: Option<i64>,
+    credit_sub_account_id: Option<i64>,
+    segment_id: Option<i64>,
 ) -> SqlResult<i64> {
-    conn.execute(
-        "INSERT INTO journal_entries (date, debit_account_id, debit_amount, credit_account_id, credit_amount, description)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-        params![date, debit_account_id, debit_amount, credit_account_id, credit_amount, description],
-    )?;
-    Ok(conn.last_insert_rowid())
+    in_savepoint(conn, || {
+        conn.execute(
+            "INSERT INTO journal_entries (date, debit_account_id, debit_amount, credit_account_id, credit_amount,
+                    description, voucher_no, partner_id, segment_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                date,
+                debit_account_id,
+                debit_amount,
+                credit_account_id,
+                credit_amount,
+                description,
+                next_voucher_no(conn, date)?,
+                partner_id,
+                segment_id
+            ],
+        )?;
+        let id = conn.last_insert_rowid();
+        let lines = simple_lines(
+            debit_account_id, debit_amount, credit_account_id, credit_amount, debit_sub_account_id, credit_sub_account_id,
+        );
+        insert_lines(conn, id, &lines)?;
+        record_revision(conn, id, "登録", None)?;
+        Ok(id)
+    })
 }
 
-pub fn fetch_entries(conn: &Connection, year: i32, month: Option<i32>) -> SqlResult<Vec<JournalEntry>> {
-    let (sql, params_vec): (&str, Vec<Box<dyn rusqlite::types::ToSql>>) = match month {
-        Some(m) => (
-            "SELECT j.id, j.date, j.debit_account_id, da.name, j.debit_amount,
-                    j.credit_account_id, ca.name, j.credit_amount, j.description, j.created_at
-             FROM journal_entries j
-             JOIN accounts da ON da.id = j.debit_account_id
-             JOIN accounts ca ON ca.id = j.credit_account_id
-             WHERE j.date >= ?1 AND j.date <= ?2
-             ORDER BY j.date, j.id",
-            vec![
-                Box::new(format!("{:04}-{:02}-01", year, m)),
-                Box::new(format!("{:04}-{:02}-31", year, m)),
-            ],
-        ),
-        None => (
-            "SELECT j.id, j.date, j.debit_account_id, da.name, j.debit_amount,
-                    j.credit_account_id, ca.name, j.credit_amount, j.description, j.created_at
-             FROM journal_entries j
-             JOIN accounts da ON da.id = j.debit_account_id
-             JOIN accounts ca ON ca.id = j.credit_account_id
-             WHERE j.date >= ?1 AND j.date <= ?2
-             ORDER BY j.date, j.id",
-            vec![
-                Box::new(format!("{:04}-01-01", year)),
-                Box::new(format!("{:04}-12-31", year)),
+/// 複合仕訳を登録する（貸借一致のチェックは呼び出し側で行う）
+pub fn insert_compound_entry(
+    conn: &Connection,
+    date: &str,
+    description: &str,
+    lines: &[JournalLineInput],
+    partner_id: Option<i64>,
+    segment_id: Option<i64>,
+) -> SqlResult<i64> {
+    let (debit_account_id, credit_account_id, total) = compound_header(lines);
+    in_savepoint(conn, || {
+        conn.execute(
+            "INSERT INTO journal_entries (date, debit_account_id, debit_amount, credit_account_id, credit_amount,
+                    description, is_compound, voucher_no, partner_id, segment_id)
+             VALUES (?1, ?2, ?3, ?4, ?3, ?5, 1, ?6, ?7, ?8)",
+            params![
+                date,
+                debit_account_id,
+                total,
+                credit_account_id,
+                description,
+                next_voucher_no(conn, date)?,
+                partner_id,
+                segment_id
             ],
-        ),
+        )?;
+        let id = conn.last_insert_rowid();
+        insert_lines(conn, id, lines)?;
+        record_revision(conn, id, "登録", None)?;
+        Ok(id)
+    })
+}
+
+/// date の年度で次に使う伝票番号（取消済みの仕訳の番号も再利用しない）
+fn next_voucher_no(conn: &Connection, date: &str) -> SqlResult<i64> {
+    conn.query_row(
+        "SELECT COALESCE(MAX(voucher_no), 0) + 1 FROM journal_entries WHERE substr(date, 1, 4) = substr(?1, 1, 4)",
+        params![date],
+        |row| row.get(0),
+    )
+}
+
+/// 訂正で年度が変わる場合だけ、新しい年度の伝票番号を採番し直す
+fn renumbered_voucher_no(conn: &Connection, before: Option<&EntrySnapshot>, date: &str) -> SqlResult<Option<i64>> {
+    match before {
+        Some(b) if b.date.get(..4) != date.get(..4) => next_voucher_no(conn, date).map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// 単一行仕訳を借方1行・貸方1行の明細に展開する
+fn simple_lines(
+    debit_account_id: i64,
+    debit_amount: i64,
+    credit_account_id: i64,
+    credit_amount: i64,
+    debit_sub_account_id: Option<i64>,
+    credit_sub_account_id: Option<i64>,
+) -> [JournalLineInput; 2] {
+    [
+        JournalLineInput {
+            side: "借方".to_string(),
+            account_id: debit_account_id,
+            sub_account_id: debit_sub_account_id,
+            amount: debit_amount,
+        },
+        JournalLineInput {
+            side: "貸方".to_string(),
+            account_id: credit_account_id,
+            sub_account_id: credit_sub_account_id,
+            amount: credit_amount,
+        },
+    ]
+}
+
+/// 複合仕訳のヘッダー列（先頭の借方科目・先頭の貸方科目・借方合計）を求める
+fn compound_header(lines: &[JournalLineInput]) -> (i64, i64, i64) {
+    let first_of = |side: &str| {
+        lines.iter().find(|l| l.side == side).map(|l| l.account_id).unwrap_or(0)
     };
+    let total = lines.iter().filter(|l| l.side == "借方").map(|l| l.amount).sum();
+    (first_of("借方"), first_of("貸方"), total)
+}
 
-    let mut stmt = conn.prepare(sql)?;
-    let rows = stmt.query_map(rusqlite::params_from_iter(params_vec.iter()), |row| {
-        Ok(JournalEntry {
+fn insert_lines(conn: &Connection, entry_id: i64, lines: &[JournalLineInput]) -> SqlResult<()> {
+    let mut stmt = conn.prepare(
+        "INSERT INTO journal_lines (entry_id, line_no, side, account_id, amount, sub_account_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    )?;
+    for (i, line) in lines.iter().enumerate() {
+        stmt.execute(params![entry_id, i as i64 + 1, line.side, line.account_id, line.amount, line.sub_account_id])?;
+    }
+    Ok(())
+}
+
+fn replace_lines(conn: &Connection, entry_id: i64, lines: &[JournalLineInput]) -> SqlResult<()> {
+    conn.execute("DELETE FROM journal_lines WHERE entry_id = ?1", params![entry_id])?;
+    insert_lines(conn, entry_id, lines)
+}
+
+pub fn fetch_entry_lines(conn: &Connection, entry_id: i64) -> SqlResult<Vec<JournalLine>> {
+    let mut stmt = conn.prepare(
+        "SELECT l.id, l.entry_id, l.line_no, l.side, l.account_id, a.code, a.name, l.amount,
+                l.sub_account_id, s.name
+         FROM journal_lines l
+         JOIN accounts a ON a.id = l.account_id
+         LEFT JOIN sub_accounts s ON s.id = l.sub_account_id
+         WHERE l.entry_id = ?1
+         ORDER BY l.line_no",
+    )?;
+    let rows = stmt.query_map(params![entry_id], |row| {
+        Ok(JournalLine {
             id: row.get(0)?,
-            date: row.get(1)?,
-            debit_account_id: row.get(2)?,
-            debit_account_name: row.get(3)?,
-            debit_amount: row.get(4)?,
-            credit_account_id: row.get(5)?,
-            credit_account_name: row.get(6)?,
-            credit_amount: row.get(7)?,
-            description: row.get(8)?,
-            created_at: row.get(9)?,
+            entry_id: row.get(1)?,
+            line_no: row.get(2)?,
+            side: row.get(3)?,
+            account_id: row.get(4)?,
+            account_code: row.get(5)?,
+            account_name: row.get(6)?,
+            amount: row.get(7)?,
+            sub_account_id: row.get(8)?,
+            sub_account_name: row.get(9)?,
         })
     })?;
     rows.collect()
 }
 
+/// 仕訳一覧の SELECT 句と結合（列の並びは map_journal_entry に対応する）
+const ENTRY_SELECT: &str = "
+    SELECT j.id, j.date, j.debit_account_id, da.name, j.debit_amount,
+           j.credit_account_id, ca.name, j.credit_amount, j.description, j.created_at,
+           j.is_compound, j.reversal_of,
+           (SELECT r.id FROM journal_entries r WHERE r.reversal_of = j.id AND r.voided_at IS NULL),
+           j.voucher_no, j.partner_id, p.name, j.segment_id, g.name, j.review_status, j.note,
+           j.currency, j.foreign_amount, j.exchange_rate, j.foreign_side
+    FROM journal_entries j
+    JOIN accounts da ON da.id = j.debit_account_id
+    JOIN accounts ca ON ca.id = j.credit_account_id
+    LEFT JOIN partners p ON p.id = j.partner_id
+    LEFT JOIN segments g ON g.id = j.segment_id";
+
+fn map_journal_entry(row: &rusqlite::Row) -> SqlResult<JournalEntry> {
+    Ok(JournalEntry {
+        id: row.get(0)?,
+        date: row.get(1)?,
+        debit_account_id: row.get(2)?,
+        debit_account_name: row.get(3)?,
+        debit_amount: row.get(4)?,
+        credit_account_id: row.get(5)?,
+        credit_account_name: row.get(6)?,
+        credit_amount: row.get(7)?,
+        description: row.get(8)?,
+        created_at: row.get(9)?,
+        is_compound: row.get::<_, i32>(10)? != 0,
+        reversal_of: row.get(11)?,
+        reversed_by: row.get(12)?,
+        voucher_no: row.get(13)?,
+        partner_id: row.get(14)?,
+        partner_name: row.get(15)?,
+        segment_id: row.get(16)?,
+        segment_name: row.get(17)?,
+        review_status: row.get(18)?,
+        note: row.get(19)?,
+        currency: row.get(20)?,
+        foreign_amount: row.get(21)?,
+        exchange_rate: row.get(22)?,
+        foreign_side: row.get(23)?,
+    })
+}
+
+/// 期間内の仕訳（partner_id / segment_id を指定するとその取引先・セグメントの仕訳だけ）
+pub fn fetch_entries(
+    conn: &Connection,
+    year: i32,
+    month: Option<i32>,
+    partner_id: Option<i64>,
+    segment_id: Option<i64>,
+) -> SqlResult<Vec<JournalEntry>> {
+    let (date_from, date_to) = period_bounds(year, month);
+    let sql = format!(
+        "{ENTRY_SELECT}
+         WHERE j.date >= ?1 AND j.date <= ?2 AND j.voided_at IS NULL
+           AND (?3 IS NULL OR j.partner_id = ?3)
+           AND (?4 IS NULL OR j.segment_id = ?4)
+         ORDER BY j.date, j.id"
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params![date_from, date_to, partner_id, segment_id], map_journal_entry)?;
+    rows.collect()
+}
+
+/// LIKE の特殊文字（% _ \）をエスケープする
+fn escape_like(term: &str) -> String {
+    term.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+/// 仕訳検索の1ページあたりの件数（limit 未指定時）
+pub const SEARCH_PAGE_SIZE: i64 = 100;
+
+/// 仕訳一覧の並び替えに使える項目
+pub const ENTRY_SORT_KEYS: &[&str] = &["date", "amount", "account_code", "created_at", "voucher_no"];
+
+/// 並び替えの ORDER BY 句（同順位は登録順。伝票番号は年度ごとの連番なので年度を先に並べる）
+/// ENTRY_SORT_KEYS にない項目は日付順にせずエラーにする
+fn entry_order_by(sort: Option<&str>, descending: bool) -> SqlResult<String> {
+    let dir = if descending { "DESC" } else { "ASC" };
+    Ok(match sort.unwrap_or("date") {
+        "date" => format!("j.date {dir}, j.id {dir}"),
+        "amount" => format!("j.debit_amount {dir}, j.date {dir}, j.id {dir}"),
+        "account_code" => format!("da.code {dir}, j.date {dir}, j.id {dir}"),
+        "created_at" => format!("j.created_at {dir}, j.id {dir}"),
+        "voucher_no" => format!("substr(j.date, 1, 4) {dir}, j.voucher_no {dir}, j.id {dir}"),
+        other => return Err(rusqlite::Error::InvalidParameterName(format!("sort: {other}"))),
+    })
+}
+
+/// 検索条件を WHERE 句とパラメータに変換する（取消済みの仕訳は対象外）
+/// キーワードは空白区切りの AND 検索。3文字以上の語は全文検索インデックスで、
+/// trigram で引けない2文字以下の語は摘要の部分一致で絞り込む
+fn entry_conditions(query: &EntrySearchQuery) -> (String, Vec<Box<dyn rusqlite::types::ToSql>>) {
+    let mut conditions = vec!["j.voided_at IS NULL"];
+    let mut values: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+
+    for term in query.keyword.split_whitespace() {
+        if term.chars().count() >= 3 {
+            conditions.push("j.id IN (SELECT rowid FROM journal_entries_fts WHERE journal_entries_fts MATCH ?)");
+            values.push(Box::new(format!("\"{}\"", term.replace('"', "\"\""))));
+        } else {
+            conditions.push("j.description LIKE ? ESCAPE '\\'");
+            values.push(Box::new(format!("%{}%", escape_like(term))));
+        }
+    }
+    if let Some(date_from) = &query.date_from {
+        conditions.push("j.date >= ?");
+        values.push(Box::new(*date_from));
+    }
+    if let Some(date_to) = &query.date_to {
+        conditions.push("j.date <= ?");
+        values.push(Box::new(*date_to));
+    }
+    if let Some(amount_min) = query.amount_min {
+        conditions.push("j.debit_amount >= ?");
+        values.push(Box::new(amount_min));
+    }
+    if let Some(amount_max) = query.amount_max {
+        conditions.push("j.debit_amount <= ?");
+        values.push(Box::new(amount_max));
+    }
+    if let Some(account_id) = query.account_id {
+        conditions.push("EXISTS (SELECT 1 FROM journal_lines l WHERE l.entry_id = j.id AND l.account_id = ?)");
+        values.push(Box::new(account_id));
+    }
+    if let Some(partner_id) = query.partner_id {
+        conditions.push("j.partner_id = ?");
+        values.push(Box::new(partner_id));
+    }
+    if let Some(segment_id) = query.segment_id {
+        conditions.push("j.segment_id = ?");
+        values.push(Box::new(segment_id));
+    }
+    (conditions.join(" AND "), values)
+}
+
+/// 条件に一致する仕訳の件数（結合や並び替えをしない軽量な集計）
+pub fn count_entries(conn: &Connection, query: &EntrySearchQuery) -> SqlResult<i64> {
+    let (where_clause, values) = entry_conditions(query);
+    conn.query_row(
+        &format!("SELECT COUNT(*) FROM journal_entries j WHERE {where_clause}"),
+        rusqlite::params_from_iter(values.iter()),
+        |row| row.get(0),
+    )
+}
+
+/// 条件を組み合わせて仕訳を検索し、指定ページ分と全件数を返す
+pub fn search_entries(conn: &Connection, query: &EntrySearchQuery) -> SqlResult<EntrySearchResult> {
+    let total_count = count_entries(conn, query)?;
+
+    let (where_clause, mut values) = entry_conditions(query);
+    values.push(Box::new(query.limit.unwrap_or(SEARCH_PAGE_SIZE)));
+    values.push(Box::new(query.offset.unwrap_or(0)));
+    let order_by = entry_order_by(query.sort.as_deref(), query.descending)?;
+    let mut stmt = conn.prepare(&format!(
+        "{ENTRY_SELECT} WHERE {where_clause} ORDER BY {order_by} LIMIT ? OFFSET ?"
+    ))?;
+    let entries = stmt
+        .query_map(rusqlite::params_from_iter(values.iter()), map_journal_entry)?
+        .collect::<SqlResult<Vec<_>>>()?;
+
+    Ok(EntrySearchResult { entries, total_count })
+}
+
 pub fn update_entry(
     conn: &Connection,
     id: i64,
@@ -137,42 +846,814 @@ pub fn update_entry(
     credit_account_id: i64,
     credit_amount: i64,
     description: &str,
+    partner_id: Option<i64>,
+    debit_sub_account_id: Option<i64>,
+    credit_sub_account_id: Option<i64>,
+    segment_id: Option<i64>,
+    foreign: Option<&ForeignAmount>,
 ) -> SqlResult<usize> {
-    conn.execute(
-        "UPDATE journal_entries
-         SET date = ?1, debit_account_id = ?2, debit_amount = ?3,
-             credit_account_id = ?4, credit_amount = ?5, description = ?6
-         WHERE id = ?7",
-        params![date, debit_account_id, debit_amount, credit_account_id, credit_amount, description, id],
+    in_savepoint(conn, || {
+        let before = fetch_entry_snapshot(conn, id)?;
+        let voucher_no = renumbered_voucher_no(conn, before.as_ref(), date)?;
+        let changed = conn.execute(
+            "UPDATE journal_entries
+             SET date = ?1, debit_account_id = ?2, debit_amount = ?3,
+                 credit_account_id = ?4, credit_amount = ?5, description = ?6, is_compound = 0,
+                 voucher_no = COALESCE(?8, voucher_no), partner_id = ?9, segment_id = ?10,
+                 currency = ?11, foreign_amount = ?12, exchange_rate = ?13, foreign_side = ?14
+             WHERE id = ?7 AND voided_at IS NULL",
+            params![
+                date,
+                debit_account_id,
+                debit_amount,
+                credit_account_id,
+                credit_amount,
+                description,
+                id,
+                voucher_no,
+                partner_id,
+                segment_id,
+                foreign.map(|f| &f.currency),
+                foreign.map(|f| f.foreign_amount),
+                foreign.map(|f| f.exchange_rate),
+                foreign.map(|f| &f.foreign_side)
+            ],
+        )?;
+        if changed > 0 {
+            let lines = simple_lines(
+                debit_account_id, debit_amount, credit_account_id, credit_amount, debit_sub_account_id, credit_sub_account_id,
+            );
+            replace_lines(conn, id, &lines)?;
+            record_revision(conn, id, "訂正", before.as_ref())?;
+        }
+        Ok(changed)
+    })
+}
+
+/// 複合仕訳は外貨建てにできないため、外貨の情報は消す
+pub fn update_compound_entry(
+    conn: &Connection,
+    id: i64,
+    date: &str,
+    description: &str,
+    lines: &[JournalLineInput],
+    partner_id: Option<i64>,
+    segment_id: Option<i64>,
+) -> SqlResult<usize> {
+    let (debit_account_id, credit_account_id, total) = compound_header(lines);
+    in_savepoint(conn, || {
+        let before = fetch_entry_snapshot(conn, id)?;
+        let voucher_no = renumbered_voucher_no(conn, before.as_ref(), date)?;
+        let changed = conn.execute(
+            "UPDATE journal_entries
+             SET date = ?1, debit_account_id = ?2, debit_amount = ?3,
+                 credit_account_id = ?4, credit_amount = ?3, description = ?5, is_compound = 1,
+                 voucher_no = COALESCE(?7, voucher_no), partner_id = ?8, segment_id = ?9,
+                 currency = NULL, foreign_amount = NULL, exchange_rate = NULL, foreign_side = NULL
+             WHERE id = ?6 AND voided_at IS NULL",
+            params![
+                date,
+                debit_account_id,
+                total,
+                credit_account_id,
+                description,
+                id,
+                voucher_no,
+                partner_id,
+                segment_id
+            ],
+        )?;
+        if changed > 0 {
+            replace_lines(conn, id, lines)?;
+            record_revision(conn, id, "訂正", before.as_ref())?;
+        }
+        Ok(changed)
+    })
+}
+
+/// 有効な（取消されていない）仕訳の日付
+pub fn fetch_entry_date(conn: &Connection, id: i64) -> SqlResult<Option<String>> {
+    conn.query_row(
+        "SELECT date FROM journal_entries WHERE id = ?1 AND voided_at IS NULL",
+        params![id],
+        |row| row.get(0),
     )
+    .optional()
 }
 
+/// 仕訳を取消す（行は残し、取消日時を記録する論理削除）
 pub fn delete_entry(conn: &Connection, id: i64) -> SqlResult<usize> {
-    conn.execute("DELETE FROM journal_entries WHERE id = ?1", params![id])
+    in_savepoint(conn, || {
+        let before = fetch_entry_snapshot(conn, id)?;
+        let changed = conn.execute(
+            "UPDATE journal_entries SET voided_at = datetime('now', 'localtime')
+             WHERE id = ?1 AND voided_at IS NULL",
+            params![id],
+        )?;
+        if changed > 0 {
+            record_revision(conn, id, "取消", before.as_ref())?;
+        }
+        Ok(changed)
+    })
+}
+
+/// 単一行仕訳をまとめて登録する（1件でも失敗したらすべて取り消す）
+pub fn insert_entries_batch(conn: &Connection, rows: &[EntryBatchRow]) -> SqlResult<Vec<i64>> {
+    in_savepoint(conn, || {
+        rows.iter()
+            .map(|r| {
+                insert_entry(
+                    conn,
+                    &r.date.to_string(),
+                    r.debit_account_id,
+                    r.debit_amount,
+                    r.credit_account_id,
+                    r.credit_amount,
+                    &r.description,
+                    r.partner_id,
+                    r.debit_sub_account_id,
+                    r.credit_sub_account_id,
+                    r.segment_id,
+                )
+            })
+            .collect()
+    })
+}
+
+/// 仕訳をまとめて訂正する（1件でも失敗したらすべて取り消す。外貨建ての仕訳は対象外）
+pub fn update_entries_batch(conn: &Connection, updates: &[EntryBatchUpdate]) -> SqlResult<usize> {
+    in_savepoint(conn, || {
+        let mut changed = 0;
+        for EntryBatchUpdate { id, row: r } in updates {
+            changed += update_entry(
+                conn,
+                *id,
+                &r.date.to_string(),
+                r.debit_account_id,
+                r.debit_amount,
+                r.credit_account_id,
+                r.credit_amount,
+                &r.description,
+                r.partner_id,
+                r.debit_sub_account_id,
+                r.credit_sub_account_id,
+                r.segment_id,
+                None,
+            )?;
+        }
+        Ok(changed)
+    })
+}
+
+/// 仕訳をまとめて取消す（1件でも失敗したらすべて取り消す）
+pub fn delete_entries_batch(conn: &Connection, ids: &[i64]) -> SqlResult<usize> {
+    in_savepoint(conn, || {
+        let mut changed = 0;
+        for &id in ids {
+            changed += delete_entry(conn, id)?;
+        }
+        Ok(changed)
+    })
+}
+
+/// 取消済みの仕訳を有効に戻す（元に戻す操作用。伝票番号はそのまま使う）
+pub fn restore_entry(conn: &Connection, id: i64) -> SqlResult<usize> {
+    in_savepoint(conn, || {
+        let changed = conn.execute(
+            "UPDATE journal_entries SET voided_at = NULL WHERE id = ?1 AND voided_at IS NOT NULL",
+            params![id],
+        )?;
+        if changed > 0 {
+            record_revision(conn, id, "復元", None)?;
+        }
+        Ok(changed)
+    })
+}
+
+/// 履歴に記録した内容で仕訳を訂正する（元に戻す操作用）
+pub fn apply_entry_snapshot(conn: &Connection, id: i64, snapshot: &EntrySnapshot) -> SqlResult<usize> {
+    let debit = snapshot.lines.iter().find(|l| l.side == "借方");
+    let credit = snapshot.lines.iter().find(|l| l.side == "貸方");
+    match (snapshot.is_compound, debit, credit) {
+        (false, Some(d), Some(c)) if snapshot.lines.len() == 2 => update_entry(
+            conn,
+            id,
+            &snapshot.date,
+            d.account_id,
+            d.amount,
+            c.account_id,
+            c.amount,
+            &snapshot.description,
+            snapshot.partner_id,
+            d.sub_account_id,
+            c.sub_account_id,
+            snapshot.segment_id,
+            snapshot.foreign.as_ref(),
+        ),
+        _ => update_compound_entry(
+            conn,
+            id,
+            &snapshot.date,
+            &snapshot.description,
+            &snapshot.lines,
+            snapshot.partner_id,
+            snapshot.segment_id,
+        ),
+    }
+}
+
+// ── 重複チェック ──
+
+/// 重複チェックで比較する仕訳（明細は「借貸:科目:金額」を並べた文字列で比較する）
+struct DuplicateCandidate {
+    id: i64,
+    date: NaiveDate,
+    amount: i64,
+    description: String,
+    lines: String,
+}
+
+fn fetch_duplicate_candidates(conn: &Connection, date_from: NaiveDate, date_to: NaiveDate) -> SqlResult<Vec<DuplicateCandidate>> {
+    // 赤黒訂正した仕訳とその訂正仕訳は打ち消し合っているので対象外
+    let mut stmt = conn.prepare(
+        "SELECT j.id, j.date, j.debit_amount, j.description,
+                (SELECT GROUP_CONCAT(x, '|') FROM (
+                     SELECT l.side || ':' || l.account_id || ':' || l.amount AS x
+                     FROM journal_lines l WHERE l.entry_id = j.id
+                     ORDER BY l.side, l.account_id, l.amount))
+         FROM journal_entries j
+         WHERE j.date >= ?1 AND j.date <= ?2 AND j.voided_at IS NULL AND j.reversal_of IS NULL
+           AND NOT EXISTS (SELECT 1 FROM journal_entries r WHERE r.reversal_of = j.id AND r.voided_at IS NULL)
+         ORDER BY j.debit_amount, j.date, j.id",
+    )?;
+    let rows = stmt.query_map(
+        params![date_from.format("%Y-%m-%d").to_string(), date_to.format("%Y-%m-%d").to_string()],
+        |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, Option<String>>(4)?,
+            ))
+        },
+    )?;
+    let mut candidates = Vec::new();
+    for row in rows {
+        let (id, date, amount, description, lines) = row?;
+        // 日付の形式が不正な仕訳は比較できないので除く
+        let Ok(Date(date)) = Date::parse(&date) else { continue };
+        candidates.push(DuplicateCandidate {
+            id,
+            date,
+            amount,
+            description: description.trim().to_string(),
+            lines: lines.unwrap_or_default(),
+        });
+    }
+    Ok(candidates)
+}
+
+/// 期間内で重複の疑いがある仕訳の組を返す（target_id を指定するとその仕訳を含む組だけ）
+/// 同日・同額・同じ明細の組と、window_days 日以内の同額・同摘要の組を重複の疑いとする
+pub fn find_duplicate_pairs(
+    conn: &Connection,
+    date_from: NaiveDate,
+    date_to: NaiveDate,
+    window_days: i64,
+    target_id: Option<i64>,
+) -> SqlResult<Vec<DuplicatePair>> {
+    let candidates = fetch_duplicate_candidates(conn, date_from, date_to)?;
+    let mut found = Vec::new();
+    for (i, a) in candidates.iter().enumerate() {
+        for b in candidates[i + 1..].iter().take_while(|b| b.amount == a.amount) {
+            if target_id.is_some_and(|t| a.id != t && b.id != t) {
+                continue;
+            }
+            let days_apart = (b.date - a.date).num_days().abs();
+            let reason = if days_apart == 0 && a.lines == b.lines {
+                "同日・同額・同科目"
+            } else if days_apart <= window_days && !a.description.is_empty() && a.description == b.description {
+                "同額・同摘要"
+            } else {
+                continue;
+            };
+            found.push((a.id.min(b.id), a.id.max(b.id), reason, days_apart));
+        }
+    }
+    if found.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let dismissed: HashSet<(i64, i64)> = {
+        let mut stmt = conn.prepare("SELECT entry_id, duplicate_id FROM duplicate_dismissals")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect::<SqlResult<_>>()?
+    };
+    let ids: Vec<String> = found.iter().flat_map(|(a, b, ..)| [a.to_string(), b.to_string()]).collect();
+    let entries: HashMap<i64, JournalEntry> = {
+        let sql = format!("{ENTRY_SELECT} WHERE j.id IN ({})", ids.join(", "));
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map([], map_journal_entry)?;
+        rows.map(|r| r.map(|e| (e.id, e))).collect::<SqlResult<_>>()?
+    };
+
+    let mut pairs: Vec<DuplicatePair> = found
+        .into_iter()
+        .filter_map(|(a, b, reason, days_apart)| {
+            Some(DuplicatePair {
+                entry: entries.get(&a)?.clone(),
+                duplicate: entries.get(&b)?.clone(),
+                reason: reason.to_string(),
+                days_apart,
+                dismissed: dismissed.contains(&(a, b)),
+            })
+        })
+        .collect();
+    pairs.sort_by(|x, y| (&x.duplicate.date, x.duplicate.id).cmp(&(&y.duplicate.date, y.duplicate.id)));
+    Ok(pairs)
+}
+
+/// 仕訳の組を「重複ではない」として記録する
+pub fn dismiss_duplicate(conn: &Connection, entry_id: i64, duplicate_id: i64) -> SqlResult<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO duplicate_dismissals (entry_id, duplicate_id) VALUES (?1, ?2)",
+        params![entry_id.min(duplicate_id), entry_id.max(duplicate_id)],
+    )?;
+    Ok(())
+}
+
+pub fn undismiss_duplicate(conn: &Connection, entry_id: i64, duplicate_id: i64) -> SqlResult<usize> {
+    conn.execute(
+        "DELETE FROM duplicate_dismissals WHERE entry_id = ?1 AND duplicate_id = ?2",
+        params![entry_id.min(duplicate_id), entry_id.max(duplicate_id)],
+    )
+}
+
+// ── 科目の候補 ──
+
+/// 候補の計算に使う過去の仕訳の件数（条件に一致するものを新しい順に）
+const SUGGESTION_HISTORY_LIMIT: i64 = 2000;
+/// 経過日数による重みが半分になる日数
+const SUGGESTION_HALF_LIFE_DAYS: f64 = 180.0;
+
+/// 摘要・金額の似ている過去の単一行仕訳から、借方・貸方科目と取引先の組み合わせを候補として返す
+/// 仕訳ごとの一致度（摘要の語・取引先名・金額）に経過日数による減衰を掛け、組み合わせごとに合計する
+pub fn suggest_accounts(
+    conn: &Connection,
+    description: &str,
+    amount: Option<i64>,
+    today: NaiveDate,
+    limit: usize,
+) -> SqlResult<Vec<AccountSuggestion>> {
+    let description = description.trim().to_lowercase();
+    let terms: Vec<&str> = description.split_whitespace().collect();
+    // 摘要に名前が含まれている取引先の仕訳も対象にする
+    let partner_ids: Vec<i64> = fetch_partners(conn)?
+        .into_iter()
+        .filter(|p| !p.name.is_empty() && description.contains(&p.name.to_lowercase()))
+        .map(|p| p.id)
+        .collect();
+
+    let mut conditions = Vec::new();
+    let mut values: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+    for term in &terms {
+        conditions.push("j.description LIKE ? ESCAPE '\\'".to_string());
+        values.push(Box::new(format!("%{}%", escape_like(term))));
+    }
+    if !partner_ids.is_empty() {
+        let ids: Vec<String> = partner_ids.iter().map(|id| id.to_string()).collect();
+        conditions.push(format!("j.partner_id IN ({})", ids.join(", ")));
+    }
+    if let Some(amount) = amount {
+        conditions.push("j.debit_amount = ?".to_string());
+        values.push(Box::new(amount));
+    }
+    if conditions.is_empty() {
+        return Ok(Vec::new());
+    }
+    values.push(Box::new(SUGGESTION_HISTORY_LIMIT));
+
+    let sql = format!(
+        "SELECT j.date, j.description, j.debit_account_id, da.name, j.credit_account_id, ca.name,
+                j.partner_id, p.name, j.debit_amount
+         FROM journal_entries j
+         JOIN accounts da ON da.id = j.debit_account_id
+         JOIN accounts ca ON ca.id = j.credit_account_id
+         LEFT JOIN partners p ON p.id = j.partner_id
+         WHERE j.voided_at IS NULL AND j.is_compound = 0 AND da.is_active = 1 AND ca.is_active = 1
+           AND ({})
+         ORDER BY j.date DESC, j.id DESC
+         LIMIT ?",
+        conditions.join(" OR ")
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(rusqlite::params_from_iter(values.iter()), |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            AccountSuggestion {
+                debit_account_id: row.get(2)?,
+                debit_account_name: row.get(3)?,
+                credit_account_id: row.get(4)?,
+                credit_account_name: row.get(5)?,
+                partner_id: row.get(6)?,
+                partner_name: row.get(7)?,
+                score: 0.0,
+                match_count: 0,
+                last_used: String::new(),
+            },
+            row.get::<_, i64>(8)?,
+        ))
+    })?;
+
+    let mut suggestions: Vec<AccountSuggestion> = Vec::new();
+    for row in rows {
+        let (date, entry_description, candidate, entry_amount) = row?;
+        let entry_description = entry_description.trim().to_lowercase();
+        let mut weight = if terms.is_empty() {
+            0.0
+        } else if entry_description == description {
+            2.0
+        } else {
+            terms.iter().filter(|t| entry_description.contains(*t)).count() as f64 / terms.len() as f64
+        };
+        if candidate.partner_id.is_some_and(|id| partner_ids.contains(&id)) {
+            weight += 1.0;
+        }
+        if let Some(amount) = amount {
+            if entry_amount == amount {
+                weight += 1.0;
+            } else if (entry_amount - amount).abs() * 10 <= amount.abs() {
+                weight += 0.5;
+            }
+        }
+        let days = Date::parse(&date)
+            .map(|Date(d)| (today - d).num_days().max(0))
+            .unwrap_or(0);
+        let score = weight * 0.5_f64.powf(days as f64 / SUGGESTION_HALF_LIFE_DAYS);
+
+        let existing = suggestions.iter_mut().find(|s| {
+            s.debit_account_id == candidate.debit_account_id
+                && s.credit_account_id == candidate.credit_account_id
+                && s.partner_id == candidate.partner_id
+        });
+        match existing {
+            Some(s) => {
+                s.score += score;
+                s.match_count += 1;
+            }
+            // 新しい順に読むので、最初に見つかった仕訳の日付が最終使用日
+            None => suggestions.push(AccountSuggestion { score, match_count: 1, last_used: date, ..candidate }),
+        }
+    }
+    suggestions.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| b.last_used.cmp(&a.last_used)));
+    suggestions.truncate(limit);
+    Ok(suggestions)
+}
+
+// ── 確認状況 ──
+
+pub const REVIEW_STATUSES: &[&str] = &["未確認", "確認済", "要質問"];
+
+/// 有効な仕訳の確認状況とメモ
+pub fn fetch_entry_review(conn: &Connection, id: i64) -> SqlResult<Option<(String, String)>> {
+    conn.query_row(
+        "SELECT review_status, note FROM journal_entries WHERE id = ?1 AND voided_at IS NULL",
+        params![id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+    .optional()
+}
+
+/// 仕訳の確認状況とメモを更新する（仕訳の内容ではないため訂正履歴には残さない）
+pub fn update_entry_review(conn: &Connection, id: i64, review_status: &str, note: &str) -> SqlResult<usize> {
+    conn.execute(
+        "UPDATE journal_entries SET review_status = ?2, note = ?3 WHERE id = ?1 AND voided_at IS NULL",
+        params![id, review_status, note],
+    )
+}
+
+/// 年度内の確認待ち（未確認・要質問）の仕訳を月ごとにまとめる（確認待ちのない月は含めない）
+pub fn fetch_open_items(conn: &Connection, year: i32) -> SqlResult<Vec<OpenItemMonth>> {
+    let (date_from, date_to) = period_bounds(year, None);
+    let sql = format!(
+        "{ENTRY_SELECT}
+         WHERE j.date >= ?1 AND j.date <= ?2 AND j.voided_at IS NULL AND j.review_status <> '確認済'
+         ORDER BY j.date, j.id"
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params![date_from, date_to], map_journal_entry)?;
+
+    let mut months: Vec<OpenItemMonth> = Vec::new();
+    for entry in rows {
+        let entry = entry?;
+        let month = entry.date.get(5..7).and_then(|m| m.parse().ok()).unwrap_or(0);
+        if months.last().map(|m| m.month) != Some(month) {
+            months.push(OpenItemMonth { month, unconfirmed_count: 0, question_count: 0, entries: Vec::new() });
+        }
+        let current = months.last_mut().expect("pushed above");
+        if entry.review_status == "要質問" {
+            current.question_count += 1;
+        } else {
+            current.unconfirmed_count += 1;
+        }
+        current.entries.push(entry);
+    }
+    Ok(months)
+}
+
+/// 年度内の確認待ち（未確認・要質問）の仕訳の件数
+pub fn count_open_items(conn: &Connection, year: i32) -> SqlResult<i64> {
+    let (date_from, date_to) = period_bounds(year, None);
+    conn.query_row(
+        "SELECT COUNT(*) FROM journal_entries
+         WHERE date >= ?1 AND date <= ?2 AND voided_at IS NULL AND review_status <> '確認済'",
+        params![date_from, date_to],
+        |row| row.get(0),
+    )
+}
+
+// ── 伝票番号 ──
+
+/// 年度内の伝票番号の欠番・重複を調べる（取消済みの仕訳も番号は使用済みとして数える）
+pub fn check_voucher_numbers(conn: &Connection, year: i32) -> SqlResult<VoucherNumberReport> {
+    let mut stmt = conn.prepare(
+        "SELECT voucher_no, id, voided_at IS NOT NULL
+         FROM journal_entries
+         WHERE substr(date, 1, 4) = ?1
+         ORDER BY voucher_no, id",
+    )?;
+    let rows = stmt
+        .query_map(params![format!("{:04}", year)], |row| {
+            Ok((row.get::<_, Option<i64>>(0)?, row.get::<_, i64>(1)?, row.get::<_, bool>(2)?))
+        })?
+        .collect::<SqlResult<Vec<_>>>()?;
+
+    let mut by_no: BTreeMap<i64, Vec<i64>> = BTreeMap::new();
+    let mut unnumbered = Vec::new();
+    let mut voided_count = 0;
+    for (voucher_no, id, voided) in &rows {
+        if *voided {
+            voided_count += 1;
+        }
+        match voucher_no {
+            Some(no) => by_no.entry(*no).or_default().push(*id),
+            None => unnumbered.push(*id),
+        }
+    }
+
+    let max_voucher_no = by_no.keys().next_back().copied().unwrap_or(0);
+    let gaps = (1..=max_voucher_no).filter(|no| !by_no.contains_key(no)).collect();
+    let duplicates = by_no
+        .into_iter()
+        .filter(|(_, ids)| ids.len() > 1)
+        .map(|(voucher_no, entry_ids)| VoucherDuplicate { voucher_no, entry_ids })
+        .collect();
+
+    Ok(VoucherNumberReport {
+        year,
+        entry_count: rows.len() as i64,
+        voided_count,
+        max_voucher_no,
+        gaps,
+        duplicates,
+        unnumbered,
+    })
+}
+
+// ── 赤黒訂正 ──
+
+/// 仕訳の貸借を入れ替えた取消仕訳を登録する（元の仕訳が見つからなければ None）
+pub fn reverse_entry(conn: &Connection, id: i64, date: Option<&str>) -> SqlResult<Option<i64>> {
+    let Some(original) = fetch_entry_snapshot(conn, id)? else {
+        return Ok(None);
+    };
+    let lines: Vec<JournalLineInput> = original
+        .lines
+        .iter()
+        .map(|l| JournalLineInput {
+            side: if l.side == "借方" { "貸方" } else { "借方" }.to_string(),
+            account_id: l.account_id,
+            sub_account_id: l.sub_account_id,
+            amount: l.amount,
+        })
+        .collect();
+    let (debit_account_id, credit_account_id, total) = compound_header(&lines);
+    let date = date.unwrap_or(&original.date);
+    let description = format!("赤黒訂正（取消）: {}", original.description);
+    in_savepoint(conn, || {
+        conn.execute(
+            "INSERT INTO journal_entries (date, debit_account_id, debit_amount, credit_account_id, credit_amount,
+                    description, is_compound, reversal_of, voucher_no, partner_id, segment_id)
+             VALUES (?1, ?2, ?3, ?4, ?3, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                date,
+                debit_account_id,
+                total,
+                credit_account_id,
+                description,
+                original.is_compound,
+                id,
+                next_voucher_no(conn, date)?,
+                original.partner_id,
+                original.segment_id
+            ],
+        )?;
+        let reversal_id = conn.last_insert_rowid();
+        insert_lines(conn, reversal_id, &lines)?;
+        record_revision(conn, reversal_id, "登録", None)?;
+        Ok(Some(reversal_id))
+    })
+}
+
+/// 仕訳の赤黒訂正の状態（reversal_of: 自身が取消す仕訳, reversed_by: 自身を取消した仕訳）
+pub fn fetch_reversal_link(conn: &Connection, id: i64) -> SqlResult<(Option<i64>, Option<i64>)> {
+    conn.query_row(
+        "SELECT j.reversal_of,
+                (SELECT r.id FROM journal_entries r WHERE r.reversal_of = j.id AND r.voided_at IS NULL)
+         FROM journal_entries j
+         WHERE j.id = ?1",
+        params![id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+}
+
+/// 期間内に赤黒訂正された仕訳の一覧（元の仕訳の日付で判定する）
+pub fn fetch_reversals(conn: &Connection, year: i32, month: Option<i32>) -> SqlResult<Vec<EntryReversal>> {
+    let (date_from, date_to) = period_bounds(year, month);
+    let mut stmt = conn.prepare(
+        "SELECT o.id, o.date, o.description, o.debit_amount, r.id, r.date
+         FROM journal_entries r
+         JOIN journal_entries o ON o.id = r.reversal_of
+         WHERE o.date >= ?1 AND o.date <= ?2 AND o.voided_at IS NULL AND r.voided_at IS NULL
+         ORDER BY o.date, o.id",
+    )?;
+    let rows = stmt.query_map(params![date_from, date_to], |row| {
+        Ok(EntryReversal {
+            entry_id: row.get(0)?,
+            entry_date: row.get(1)?,
+            description: row.get(2)?,
+            amount: row.get(3)?,
+            reversal_id: row.get(4)?,
+            reversal_date: row.get(5)?,
+        })
+    })?;
+    rows.collect()
+}
+
+// ── 訂正・削除履歴 ──
+
+/// 履歴に記録する時点の仕訳の内容
+pub fn fetch_entry_snapshot(conn: &Connection, id: i64) -> SqlResult<Option<EntrySnapshot>> {
+    let header = conn
+        .query_row(
+            "SELECT date, description, is_compound, partner_id, segment_id,
+                    currency, foreign_amount, exchange_rate, foreign_side
+             FROM journal_entries WHERE id = ?1",
+            params![id],
+            |row| {
+                let foreign = match (row.get(5)?, row.get(6)?, row.get(7)?, row.get(8)?) {
+                    (Some(currency), Some(foreign_amount), Some(exchange_rate), Some(foreign_side)) => {
+                        Some(ForeignAmount { currency, foreign_amount, exchange_rate, foreign_side })
+                    }
+                    _ => None,
+                };
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i32>(2)? != 0,
+                    row.get::<_, Option<i64>>(3)?,
+                    row.get::<_, Option<i64>>(4)?,
+                    foreign,
+                ))
+            },
+        )
+        .optional()?;
+    let Some((date, description, is_compound, partner_id, segment_id, foreign)) = header else {
+        return Ok(None);
+    };
+    let lines = fetch_entry_lines(conn, id)?
+        .into_iter()
+        .map(|l| JournalLineInput {
+            side: l.side,
+            account_id: l.account_id,
+            sub_account_id: l.sub_account_id,
+            amount: l.amount,
+        })
+        .collect();
+    Ok(Some(EntrySnapshot { date, description, is_compound, partner_id, segment_id, lines, foreign }))
+}
+
+fn to_json(snapshot: Option<&EntrySnapshot>) -> SqlResult<Option<String>> {
+    snapshot
+        .map(serde_json::to_string)
+        .transpose()
+        .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))
+}
+
+/// 変更操作のユーザー名（OS のログインユーザー）
+fn current_user() -> String {
+    std::env::var("USERNAME")
+        .or_else(|_| std::env::var("USER"))
+        .unwrap_or_default()
+}
+
+/// 仕訳の変更を履歴に追記する。変更後の内容は現在の状態から取得する（取消時は NULL）
+fn record_revision(
+    conn: &Connection,
+    entry_id: i64,
+    action: &str,
+    before: Option<&EntrySnapshot>,
+) -> SqlResult<()> {
+    let after = if action == "取消" { None } else { fetch_entry_snapshot(conn, entry_id)? };
+    conn.execute(
+        "INSERT INTO journal_entry_revisions (entry_id, revision_no, action, before_json, after_json, changed_by)
+         VALUES (
+             ?1,
+             (SELECT COALESCE(MAX(revision_no), 0) + 1 FROM journal_entry_revisions WHERE entry_id = ?1),
+             ?2, ?3, ?4, ?5
+         )",
+        params![entry_id, action, to_json(before)?, to_json(after.as_ref())?, current_user()],
+    )?;
+    Ok(())
+}
+
+fn map_entry_revision(row: &rusqlite::Row) -> SqlResult<EntryRevision> {
+    let parse = |idx: usize| -> SqlResult<Option<EntrySnapshot>> {
+        let json: Option<String> = row.get(idx)?;
+        json.map(|s| serde_json::from_str(&s))
+            .transpose()
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, Box::new(e)))
+    };
+    Ok(EntryRevision {
+        id: row.get(0)?,
+        entry_id: row.get(1)?,
+        revision_no: row.get(2)?,
+        action: row.get(3)?,
+        before: parse(4)?,
+        after: parse(5)?,
+        changed_by: row.get(6)?,
+        changed_at: row.get(7)?,
+    })
+}
+
+pub fn fetch_entry_revisions(conn: &Connection, entry_id: i64) -> SqlResult<Vec<EntryRevision>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, entry_id, revision_no, action, before_json, after_json, changed_by, changed_at
+         FROM journal_entry_revisions
+         WHERE entry_id = ?1
+         ORDER BY revision_no",
+    )?;
+    let rows = stmt.query_map(params![entry_id], map_entry_revision)?;
+    rows.collect()
+}
+
+/// 期間内の仕訳（取消済みを含む）に対する履歴。期間は仕訳の現在の日付で判定する
+pub fn fetch_revisions(conn: &Connection, year: i32, month: Option<i32>) -> SqlResult<Vec<EntryRevision>> {
+    let (date_from, date_to) = period_bounds(year, month);
+    let mut stmt = conn.prepare(
+        "SELECT r.id, r.entry_id, r.revision_no, r.action, r.before_json, r.after_json, r.changed_by, r.changed_at
+         FROM journal_entry_revisions r
+         JOIN journal_entries j ON j.id = r.entry_id
+         WHERE j.date >= ?1 AND j.date <= ?2
+         ORDER BY r.changed_at, r.id",
+    )?;
+    let rows = stmt.query_map(params![date_from, date_to], map_entry_revision)?;
+    rows.collect()
 }
 
 // ── 集計 ──
 
-pub fn calc_trial_balance(conn: &Connection, year: i32, month: Option<i32>) -> SqlResult<TrialBalance> {
-    let (date_from, date_to) = match month {
-        Some(m) => (format!("{:04}-{:02}-01", year, m), format!("{:04}-{:02}-31", year, m)),
-        None => (format!("{:04}-01-01", year), format!("{:04}-12-31", year)),
-    };
+/// 試算表（by_sub_account が true なら補助科目ごとの行に分け、補助科目なしの明細は科目のみの行にまとめる）
+pub fn calc_trial_balance(
+    conn: &Connection,
+    year: i32,
+    month: Option<i32>,
+    by_sub_account: bool,
+) -> SqlResult<TrialBalance> {
+    let (date_from, date_to) = period_bounds(year, month);
 
     let sql = "
         SELECT a.id, a.code, a.name, a.classification,
-               COALESCE(SUM(CASE WHEN j.debit_account_id = a.id THEN j.debit_amount ELSE 0 END), 0) AS debit_total,
-               COALESCE(SUM(CASE WHEN j.credit_account_id = a.id THEN j.credit_amount ELSE 0 END), 0) AS credit_total
+               COALESCE(SUM(CASE WHEN l.side = '借方' THEN l.amount ELSE 0 END), 0) AS debit_total,
+               COALESCE(SUM(CASE WHEN l.side = '貸方' THEN l.amount ELSE 0 END), 0) AS credit_total,
+               CASE WHEN ?3 THEN l.sub_account_id END AS sub_id,
+               (SELECT s.name FROM sub_accounts s WHERE s.id = CASE WHEN ?3 THEN l.sub_account_id END)
         FROM accounts a
-        LEFT JOIN journal_entries j ON (j.debit_account_id = a.id OR j.credit_account_id = a.id)
-            AND j.date >= ?1 AND j.date <= ?2
-        GROUP BY a.id
+        LEFT JOIN (
+            SELECT jl.account_id, jl.side, jl.amount, jl.sub_account_id
+            FROM journal_lines jl
+            JOIN journal_entries j ON j.id = jl.entry_id
+            WHERE j.date >= ?1 AND j.date <= ?2 AND j.voided_at IS NULL
+        ) l ON l.account_id = a.id
+        GROUP BY a.id, sub_id
         HAVING debit_total > 0 OR credit_total > 0
-        ORDER BY a.code";
+        ORDER BY a.code, sub_id";
 
     let mut stmt = conn.prepare(sql)?;
     let rows: Vec<TrialBalanceRow> = stmt
-        .query_map(params![date_from, date_to], |row| {
+        .query_map(params![date_from, date_to, by_sub_account], |row| {
             let debit_total: i64 = row.get(4)?;
             let credit_total: i64 = row.get(5)?;
             let classification: String = row.get(3)?;
@@ -188,6 +1669,8 @@ pub fn calc_trial_balance(conn: &Connection, year: i32, month: Option<i32>) -> S
                 debit_total,
                 credit_total,
                 balance,
+                sub_account_id: row.get(6)?,
+                sub_account_name: row.get(7)?,
             })
         })?
         .collect::<SqlResult<Vec<_>>>()?;
@@ -203,35 +1686,46 @@ pub fn calc_trial_balance(conn: &Connection, year: i32, month: Option<i32>) -> S
 }
 
 pub fn calc_profit_loss(conn: &Connection, year: i32) -> SqlResult<ProfitLoss> {
-    let date_from = format!("{:04}-01-01", year);
-    let date_to = format!("{:04}-12-31", year);
+    Ok(build_profit_loss(fetch_pl_totals(conn, year, year)?))
+}
+
+/// from_year 期首〜to_year 期末の収益・費用科目の借方・貸方合計（金額のある科目だけ）
+fn fetch_pl_totals(conn: &Connection, from_year: i32, to_year: i32) -> SqlResult<Vec<AccountTotals>> {
+    let date_from = format!("{:04}-01-01", from_year);
+    let date_to = format!("{:04}-12-31", to_year);
 
     let sql = "
         SELECT a.id, a.code, a.name, a.classification,
-               COALESCE(SUM(CASE WHEN j.debit_account_id = a.id THEN j.debit_amount ELSE 0 END), 0) AS debit_total,
-               COALESCE(SUM(CASE WHEN j.credit_account_id = a.id THEN j.credit_amount ELSE 0 END), 0) AS credit_total
+               COALESCE(SUM(CASE WHEN l.side = '借方' THEN l.amount ELSE 0 END), 0) AS debit_total,
+               COALESCE(SUM(CASE WHEN l.side = '貸方' THEN l.amount ELSE 0 END), 0) AS credit_total
         FROM accounts a
-        LEFT JOIN journal_entries j ON (j.debit_account_id = a.id OR j.credit_account_id = a.id)
-            AND j.date >= ?1 AND j.date <= ?2
+        LEFT JOIN (
+            SELECT jl.account_id, jl.side, jl.amount
+            FROM journal_lines jl
+            JOIN journal_entries j ON j.id = jl.entry_id
+            WHERE j.date >= ?1 AND j.date <= ?2 AND j.voided_at IS NULL
+        ) l ON l.account_id = a.id
         WHERE a.classification IN ('収益', '費用')
         GROUP BY a.id
         HAVING debit_total > 0 OR credit_total > 0
         ORDER BY a.code";
 
     let mut stmt = conn.prepare(sql)?;
-    let all_rows: Vec<(i64, i32, String, String, i64, i64)> = stmt
-        .query_map(params![date_from, date_to], |row| {
-            Ok((
-                row.get(0)?,
-                row.get(1)?,
-                row.get(2)?,
-                row.get(3)?,
-                row.get(4)?,
-                row.get(5)?,
-            ))
-        })?
-        .collect::<SqlResult<Vec<_>>>()?;
+    let rows = stmt.query_map(params![date_from, date_to], |row| {
+        Ok((
+            row.get(0)?,
+            row.get(1)?,
+            row.get(2)?,
+            row.get(3)?,
+            row.get(4)?,
+            row.get(5)?,
+        ))
+    })?;
+    rows.collect()
+}
 
+/// 収益・費用科目の借方・貸方合計から損益計算書を組み立てる
+fn build_profit_loss(all_rows: Vec<AccountTotals>) -> ProfitLoss {
     let mut revenue_rows = Vec::new();
     let mut expense_rows = Vec::new();
 
@@ -257,59 +1751,95 @@ pub fn calc_profit_loss(conn: &Connection, year: i32) -> SqlResult<ProfitLoss> {
     let total_revenue: i64 = revenue_rows.iter().map(|r| r.amount).sum();
     let total_expense: i64 = expense_rows.iter().map(|r| r.amount).sum();
 
-    Ok(ProfitLoss {
+    ProfitLoss {
         revenue_rows,
         expense_rows,
         total_revenue,
         total_expense,
         net_income: total_revenue - total_expense,
-    })
+    }
 }
 
-pub fn calc_balance_sheet(conn: &Connection, year: i32) -> SqlResult<BalanceSheet> {
-    let date_from = format!("{:04}-01-01", year);
-    let date_to = format!("{:04}-12-31", year);
-
-    let sql = "
-        SELECT a.id, a.code, a.name, a.classification,
-               COALESCE(SUM(CASE WHEN j.debit_account_id = a.id THEN j.debit_amount ELSE 0 END), 0) AS debit_total,
-               COALESCE(SUM(CASE WHEN j.credit_account_id = a.id THEN j.credit_amount ELSE 0 END), 0) AS credit_total
-        FROM accounts a
-        LEFT JOIN journal_entries j ON (j.debit_account_id = a.id OR j.credit_account_id = a.id)
-            AND j.date >= ?1 AND j.date <= ?2
-        WHERE a.classification IN ('資産', '負債', '純資産')
-        GROUP BY a.id
-        HAVING debit_total > 0 OR credit_total > 0
-        ORDER BY a.code";
-
-    let mut stmt = conn.prepare(sql)?;
-    let all_rows: Vec<(i64, i32, String, String, i64, i64)> = stmt
-        .query_map(params![date_from, date_to], |row| {
+/// セグメント別の損益計算書（セグメントのない仕訳は「未割当」にまとめる）
+/// segment_id を指定した場合はそのセグメントだけを返す。
+/// 未割当を含めた全セグメントの合計は calc_profit_loss と一致する
+pub fn calc_segment_profit_loss(
+    conn: &Connection,
+    year: i32,
+    segment_id: Option<i64>,
+) -> SqlResult<Vec<SegmentProfitLoss>> {
+    let mut stmt = conn.prepare(
+        "SELECT j.segment_id, a.id, a.code, a.name, a.classification,
+                SUM(CASE WHEN jl.side = '借方' THEN jl.amount ELSE 0 END),
+                SUM(CASE WHEN jl.side = '貸方' THEN jl.amount ELSE 0 END)
+         FROM journal_lines jl
+         JOIN journal_entries j ON j.id = jl.entry_id
+         JOIN accounts a ON a.id = jl.account_id
+         WHERE j.date >= ?1 AND j.date <= ?2 AND j.voided_at IS NULL
+           AND a.classification IN ('収益', '費用')
+         GROUP BY j.segment_id, a.id
+         ORDER BY a.code",
+    )?;
+    let mut by_segment: HashMap<Option<i64>, Vec<AccountTotals>> = HashMap::new();
+    let rows = stmt.query_map(
+        params![format!("{:04}-01-01", year), format!("{:04}-12-31", year)],
+        |row| {
             Ok((
-                row.get(0)?,
-                row.get(1)?,
-                row.get(2)?,
-                row.get(3)?,
-                row.get(4)?,
-                row.get(5)?,
+                row.get::<_, Option<i64>>(0)?,
+                (row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?, row.get(6)?),
             ))
-        })?
-        .collect::<SqlResult<Vec<_>>>()?;
+        },
+    )?;
+    for row in rows {
+        let (segment, totals) = row?;
+        by_segment.entry(segment).or_default().push(totals);
+    }
+
+    let mut result = Vec::new();
+    for segment in fetch_segments(conn)? {
+        if segment_id.is_some_and(|id| id != segment.id) {
+            continue;
+        }
+        let totals = by_segment.remove(&Some(segment.id));
+        if totals.is_none() && !segment.is_active && segment_id.is_none() {
+            continue;
+        }
+        result.push(SegmentProfitLoss {
+            segment_id: Some(segment.id),
+            segment_name: segment.name,
+            segment_type: Some(segment.segment_type),
+            profit_loss: build_profit_loss(totals.unwrap_or_default()),
+        });
+    }
+    if segment_id.is_none() {
+        result.push(SegmentProfitLoss {
+            segment_id: None,
+            segment_name: "未割当".to_string(),
+            segment_type: None,
+            profit_loss: build_profit_loss(by_segment.remove(&None).unwrap_or_default()),
+        });
+    }
+    Ok(result)
+}
+
+pub fn calc_balance_sheet(conn: &Connection, year: i32) -> SqlResult<BalanceSheet> {
+    let opening = calc_opening_balances(conn, year)?;
 
     let mut asset_rows = Vec::new();
     let mut liability_rows = Vec::new();
     let mut equity_rows = Vec::new();
 
-    for (id, code, name, classification, debit_total, credit_total) in all_rows {
-        let amount = match classification.as_str() {
-            "資産" => debit_total - credit_total,
-            _ => credit_total - debit_total,
-        };
+    for (id, code, name, classification, debit_total, credit_total) in fetch_bs_movements(conn, year, year)? {
+        let opening_amount = opening.get(&id).copied().unwrap_or(0);
+        if opening_amount == 0 && debit_total == 0 && credit_total == 0 {
+            continue;
+        }
         let row = BalanceSheetRow {
             account_id: id,
             account_code: code,
             account_name: name,
-            amount,
+            opening_amount,
+            amount: opening_amount + bs_balance(&classification, debit_total, credit_total),
         };
         match classification.as_str() {
             "資産" => asset_rows.push(row),
@@ -322,6 +1852,9 @@ pub fn calc_balance_sheet(conn: &Connection, year: i32) -> SqlResult<BalanceShee
     let total_assets: i64 = asset_rows.iter().map(|r| r.amount).sum();
     let total_liabilities: i64 = liability_rows.iter().map(|r| r.amount).sum();
     let total_equity: i64 = equity_rows.iter().map(|r| r.amount).sum();
+    let total_assets_opening: i64 = asset_rows.iter().map(|r| r.opening_amount).sum();
+    let total_liabilities_opening: i64 = liability_rows.iter().map(|r| r.opening_amount).sum();
+    let total_equity_opening: i64 = equity_rows.iter().map(|r| r.opening_amount).sum();
 
     let pl = calc_profit_loss(conn, year)?;
 
@@ -332,10 +1865,477 @@ pub fn calc_balance_sheet(conn: &Connection, year: i32) -> SqlResult<BalanceShee
         total_assets,
         total_liabilities,
         total_equity,
+        total_assets_opening,
+        total_liabilities_opening,
+        total_equity_opening,
         net_income: pl.net_income,
     })
 }
 
+/// 科目ごとの借方・貸方合計 (id, code, name, classification, debit_total, credit_total)
+type AccountTotals = (i64, i32, String, String, i64, i64);
+
+/// 貸借対照表科目ごとの from_year 期首〜to_year 期末の借方・貸方合計（取引のない科目も含む）
+fn fetch_bs_movements(conn: &Connection, from_year: i32, to_year: i32) -> SqlResult<Vec<AccountTotals>> {
+    let date_from = format!("{:04}-01-01", from_year);
+    let date_to = format!("{:04}-12-31", to_year);
+
+    let sql = "
+        SELECT a.id, a.code, a.name, a.classification,
+               COALESCE(SUM(CASE WHEN l.side = '借方' THEN l.amount ELSE 0 END), 0) AS debit_total,
+               COALESCE(SUM(CASE WHEN l.side = '貸方' THEN l.amount ELSE 0 END), 0) AS credit_total
+        FROM accounts a
+        LEFT JOIN (
+            SELECT jl.account_id, jl.side, jl.amount
+            FROM journal_lines jl
+            JOIN journal_entries j ON j.id = jl.entry_id
+            WHERE j.date >= ?1 AND j.date <= ?2 AND j.voided_at IS NULL
+        ) l ON l.account_id = a.id
+        WHERE a.classification IN ('資産', '負債', '純資産')
+        GROUP BY a.id
+        ORDER BY a.code";
+
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map(params![date_from, date_to], |row| {
+        Ok((
+            row.get(0)?,
+            row.get(1)?,
+            row.get(2)?,
+            row.get(3)?,
+            row.get(4)?,
+            row.get(5)?,
+        ))
+    })?;
+    rows.collect()
+}
+
+/// 貸借対照表科目の残高（資産は借方残、負債・純資産は貸方残を正とする）
+fn bs_balance(classification: &str, debit_total: i64, credit_total: i64) -> i64 {
+    match classification {
+        "資産" => debit_total - credit_total,
+        _ => credit_total - debit_total,
+    }
+}
+
+// ── 総勘定元帳 ──
+
+/// 勘定科目ごとの元帳（期首残高からの差引残高つき）
+/// month を指定した場合は年初から前月末までの増減を前月繰越として繰り込む。
+/// partner_id・sub_account_id を指定した場合はその取引先・補助科目の明細だけを集計し（補助元帳）、
+/// 繰越額もその明細から求める（期首残高は取引先・補助科目別に持たないため、貸借対照表科目は帳簿開始からの累計とする）
+pub fn calc_ledger(
+    conn: &Connection,
+    account_id: i64,
+    year: i32,
+    month: Option<i32>,
+    partner_id: Option<i64>,
+    sub_account_id: Option<i64>,
+) -> SqlResult<Ledger> {
+    let (account_code, account_name, classification): (i32, String, String) = conn.query_row(
+        "SELECT code, name, classification FROM accounts WHERE id = ?1",
+        params![account_id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )?;
+    let debit_normal = matches!(classification.as_str(), "資産" | "費用");
+    let signed = |side: &str, amount: i64| if (side == "借方") == debit_normal { amount } else { -amount };
+
+    let year_start = format!("{:04}-01-01", year);
+    let (date_from, date_to) = period_bounds(year, month);
+
+    let is_bs = matches!(classification.as_str(), "資産" | "負債" | "純資産");
+    let filtered = partner_id.is_some() || sub_account_id.is_some();
+    let (mut opening_balance, carry_from) = match (filtered, is_bs) {
+        (true, true) => (0, "0000-01-01".to_string()),
+        (true, false) => (0, year_start.clone()),
+        (false, _) => (calc_opening_balances(conn, year)?.get(&account_id).copied().unwrap_or(0), year_start.clone()),
+    };
+    if date_from > carry_from {
+        let (debit, credit): (i64, i64) = conn.query_row(
+            "SELECT COALESCE(SUM(CASE WHEN l.side = '借方' THEN l.amount ELSE 0 END), 0),
+                    COALESCE(SUM(CASE WHEN l.side = '貸方' THEN l.amount ELSE 0 END), 0)
+             FROM journal_lines l
+             JOIN journal_entries j ON j.id = l.entry_id
+             WHERE l.account_id = ?1 AND j.date >= ?2 AND j.date < ?3 AND j.voided_at IS NULL
+               AND (?4 IS NULL OR j.partner_id = ?4)
+               AND (?5 IS NULL OR l.sub_account_id = ?5)",
+            params![account_id, carry_from, date_from, partner_id, sub_account_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        opening_balance += signed("借方", debit) + signed("貸方", credit);
+    }
+
+    // 相手科目は反対側の明細が1科目ならその科目名、複数なら「諸口」
+    let mut stmt = conn.prepare(
+        "SELECT j.id, j.date, j.description, l.side, l.amount, j.reversal_of,
+                (SELECT CASE WHEN COUNT(DISTINCT o.account_id) = 1 THEN MAX(a.name) ELSE '諸口' END
+                 FROM journal_lines o
+                 JOIN accounts a ON a.id = o.account_id
+                 WHERE o.entry_id = j.id AND o.side <> l.side),
+                p.name
+         FROM journal_lines l
+         JOIN journal_entries j ON j.id = l.entry_id
+         LEFT JOIN partners p ON p.id = j.partner_id
+         WHERE l.account_id = ?1 AND j.date >= ?2 AND j.date <= ?3 AND j.voided_at IS NULL
+           AND (?4 IS NULL OR j.partner_id = ?4)
+           AND (?5 IS NULL OR l.sub_account_id = ?5)
+         ORDER BY j.date, j.id, l.line_no",
+    )?;
+    let lines = stmt
+        .query_map(params![account_id, date_from, date_to, partner_id, sub_account_id], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, i64>(4)?,
+                row.get::<_, Option<i64>>(5)?,
+                row.get::<_, Option<String>>(6)?,
+                row.get::<_, Option<String>>(7)?,
+            ))
+        })?
+        .collect::<SqlResult<Vec<_>>>()?;
+
+    let mut balance = opening_balance;
+    let mut rows = Vec::with_capacity(lines.len());
+    for (entry_id, date, description, side, amount, reversal_of, counter, partner_name) in lines {
+        balance += signed(&side, amount);
+        let (debit_amount, credit_amount) = if side == "借方" { (amount, 0) } else { (0, amount) };
+        rows.push(LedgerRow {
+            entry_id,
+            date,
+            description,
+            counter_account_name: counter.unwrap_or_default(),
+            debit_amount,
+            credit_amount,
+            balance,
+            reversal_of,
+            partner_name,
+        });
+    }
+
+    let debit_total = rows.iter().map(|r| r.debit_amount).sum();
+    let credit_total = rows.iter().map(|r| r.credit_amount).sum();
+
+    Ok(Ledger {
+        account_id,
+        partner_id,
+        sub_account_id,
+        account_code,
+        account_name,
+        classification,
+        opening_balance,
+        rows,
+        debit_total,
+        credit_total,
+        closing_balance: balance,
+    })
+}
+
+// ── 期首残高 ──
+
+/// 指定年度の期首残高（account_id → 残高）を求める
+/// 期首残高（手入力または年度締めの繰越）があればそれを優先し、なければ前年の期末残高を繰り越す
+/// 前年以前の年度を1年ずつ締め直すのではなく、直近の期首残高の年度から前年末までをまとめて集計する
+pub fn calc_opening_balances(conn: &Connection, year: i32) -> SqlResult<HashMap<i64, i64>> {
+    let explicit = fetch_opening_balance_rows(conn, year)?;
+    if !explicit.is_empty() {
+        return Ok(explicit.into_iter().collect());
+    }
+    let base_year: Option<i32> = conn.query_row(
+        "SELECT MAX(year) FROM opening_balances WHERE year < ?1",
+        params![year],
+        |row| row.get(0),
+    )?;
+    let (from_year, opening) = match base_year {
+        Some(base) => (base, fetch_opening_balance_rows(conn, base)?.into_iter().collect()),
+        None => match first_book_year(conn)? {
+            Some(first) if first < year => (first, HashMap::new()),
+            _ => return Ok(HashMap::new()),
+        },
+    };
+    Ok(calc_closing_range(conn, opening, from_year, year - 1)?.balances)
+}
+
+/// 年度末の締め計算の結果（翌期首残高と元入金の内訳）
+struct ClosingCalc {
+    balances: HashMap<i64, i64>,
+    prior_capital: i64,
+    income: i64,
+    owner_borrowings: i64,
+    owner_drawings: i64,
+    next_capital: i64,
+}
+
+/// year の期末残高から翌期首残高を求める
+fn calc_closing(conn: &Connection, year: i32) -> SqlResult<ClosingCalc> {
+    calc_closing_range(conn, calc_opening_balances(conn, year)?, year, year)
+}
+
+/// from_year の期首残高 opening に to_year 末までの増減を加えて、to_year の翌期首残高を求める
+/// 元入金 = 元入金 + 青色申告特別控除前の所得 + 事業主借 − 事業主貸 とし、
+/// 事業主貸・事業主借は0に戻す（その他の純資産科目は元入金に含める）
+/// 毎年の締めは純資産の合計と所得を元入金にまとめるだけなので、複数年度をまとめて集計しても結果は同じ
+/// （複数年度のときの内訳は期間全体の合計になる）
+fn calc_closing_range(
+    conn: &Connection,
+    opening: HashMap<i64, i64>,
+    from_year: i32,
+    to_year: i32,
+) -> SqlResult<ClosingCalc> {
+    let mut balances = opening;
+    let movements = fetch_bs_movements(conn, from_year, to_year)?;
+    for (id, _, _, classification, debit_total, credit_total) in &movements {
+        *balances.entry(*id).or_insert(0) += bs_balance(classification, *debit_total, *credit_total);
+    }
+
+    let income = build_profit_loss(fetch_pl_totals(conn, from_year, to_year)?).net_income;
+    let mut prior_capital = 0;
+    let mut owner_borrowings = 0;
+    let mut owner_drawings = 0;
+    for (id, code, _, classification, _, _) in &movements {
+        if classification != "純資産" {
+            continue;
+        }
+        let balance = balances.get(id).copied().unwrap_or(0);
+        match code {
+            // 事業主貸は貸方残を正とした残高なので符号を反転する
+            3200 => owner_drawings = -balance,
+            3300 => owner_borrowings = balance,
+            _ => prior_capital += balance,
+        }
+    }
+    let next_capital = prior_capital + income + owner_borrowings - owner_drawings;
+
+    if let Some((capital_id, ..)) = movements.iter().find(|m| m.1 == 3100) {
+        for (id, _, _, classification, _, _) in &movements {
+            if classification == "純資産" {
+                balances.remove(id);
+            }
+        }
+        balances.insert(*capital_id, next_capital);
+    }
+
+    balances.retain(|_, amount| *amount != 0);
+    Ok(ClosingCalc {
+        balances,
+        prior_capital,
+        income,
+        owner_borrowings,
+        owner_drawings,
+        next_capital,
+    })
+}
+
+/// 帳簿の開始年度（期首残高または仕訳が存在する最も古い年度）
+fn first_book_year(conn: &Connection) -> SqlResult<Option<i32>> {
+    conn.query_row(
+        "SELECT MIN(y) FROM (
+             SELECT MIN(year) AS y FROM opening_balances
+             UNION ALL
+             SELECT MIN(CAST(substr(date, 1, 4) AS INTEGER)) FROM journal_entries WHERE voided_at IS NULL
+         )",
+        [],
+        |row| row.get(0),
+    )
+}
+
+fn fetch_opening_balance_rows(conn: &Connection, year: i32) -> SqlResult<Vec<(i64, i64)>> {
+    let mut stmt = conn.prepare("SELECT account_id, amount FROM opening_balances WHERE year = ?1")?;
+    let rows = stmt.query_map(params![year], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    rows.collect()
+}
+
+/// 登録済み期首残高の出所（"手入力" / "繰越"）。未登録なら None
+pub fn fetch_opening_balance_source(conn: &Connection, year: i32) -> SqlResult<Option<String>> {
+    conn.query_row(
+        "SELECT source FROM opening_balances WHERE year = ?1 LIMIT 1",
+        params![year],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+/// 期首残高の一覧（登録がなければ前年からの繰越額を返す）
+pub fn fetch_opening_balances(conn: &Connection, year: i32) -> SqlResult<Vec<OpeningBalance>> {
+    let source = fetch_opening_balance_source(conn, year)?.unwrap_or_else(|| "繰越".to_string());
+    let balances = calc_opening_balances(conn, year)?;
+    let rows = fetch_accounts(conn)?
+        .into_iter()
+        .filter_map(|a| {
+            let amount = *balances.get(&a.id)?;
+            Some(OpeningBalance {
+                account_id: a.id,
+                account_code: a.code,
+                account_name: a.name,
+                classification: a.classification,
+                amount,
+                source: source.clone(),
+            })
+        })
+        .collect();
+    Ok(rows)
+}
+
+/// 指定年度の期首残高を入力内容で置き換える（空なら繰越に戻る）
+pub fn replace_opening_balances(
+    conn: &Connection,
+    year: i32,
+    balances: &[OpeningBalanceInput],
+) -> SqlResult<()> {
+    in_savepoint(conn, || {
+        conn.execute("DELETE FROM opening_balances WHERE year = ?1", params![year])?;
+        let mut stmt = conn.prepare(
+            "INSERT INTO opening_balances (year, account_id, amount) VALUES (?1, ?2, ?3)",
+        )?;
+        for b in balances.iter().filter(|b| b.amount != 0) {
+            stmt.execute(params![year, b.account_id, b.amount])?;
+        }
+        Ok(())
+    })
+}
+
+// ── 年度締め ──
+
+pub fn fetch_year_closings(conn: &Connection) -> SqlResult<Vec<YearClosing>> {
+    let mut stmt = conn.prepare(
+        "SELECT year, prior_capital, income, owner_borrowings, owner_drawings, next_capital, closed_at
+         FROM year_closings ORDER BY year",
+    )?;
+    let rows = stmt.query_map([], map_year_closing)?;
+    rows.collect()
+}
+
+pub fn fetch_year_closing(conn: &Connection, year: i32) -> SqlResult<Option<YearClosing>> {
+    conn.query_row(
+        "SELECT year, prior_capital, income, owner_borrowings, owner_drawings, next_capital, closed_at
+         FROM year_closings WHERE year = ?1",
+        params![year],
+        map_year_closing,
+    )
+    .optional()
+}
+
+fn map_year_closing(row: &rusqlite::Row) -> SqlResult<YearClosing> {
+    Ok(YearClosing {
+        year: row.get(0)?,
+        prior_capital: row.get(1)?,
+        income: row.get(2)?,
+        owner_borrowings: row.get(3)?,
+        owner_drawings: row.get(4)?,
+        next_capital: row.get(5)?,
+        closed_at: row.get(6)?,
+    })
+}
+
+/// 年度を締め、期末残高を翌年の期首残高（繰越）として確定する
+pub fn close_year(conn: &Connection, year: i32) -> SqlResult<YearClosing> {
+    let calc = calc_closing(conn, year)?;
+    in_savepoint(conn, || {
+        conn.execute("DELETE FROM opening_balances WHERE year = ?1", params![year + 1])?;
+        let mut stmt = conn.prepare(
+            "INSERT INTO opening_balances (year, account_id, amount, source) VALUES (?1, ?2, ?3, '繰越')",
+        )?;
+        for (account_id, amount) in &calc.balances {
+            stmt.execute(params![year + 1, account_id, amount])?;
+        }
+        conn.execute(
+            "INSERT INTO year_closings (year, prior_capital, income, owner_borrowings, owner_drawings, next_capital)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![year, calc.prior_capital, calc.income, calc.owner_borrowings,
+                    calc.owner_drawings, calc.next_capital],
+        )?;
+        Ok(())
+    })?;
+    fetch_year_closing(conn, year)?.ok_or(rusqlite::Error::QueryReturnedNoRows)
+}
+
+/// from_year〜to_year のうち年度締めが済んでいる最初の年度
+pub fn find_closed_year(conn: &Connection, from_year: i32, to_year: i32) -> SqlResult<Option<i32>> {
+    conn.query_row(
+        "SELECT MIN(year) FROM year_closings WHERE year >= ?1 AND year <= ?2",
+        params![from_year, to_year],
+        |row| row.get(0),
+    )
+}
+
+/// 年度締めを取り消し、翌年の繰越期首残高を削除する
+pub fn reopen_year(conn: &Connection, year: i32) -> SqlResult<()> {
+    in_savepoint(conn, || {
+        conn.execute(
+            "DELETE FROM opening_balances WHERE year = ?1 AND source = '繰越'",
+            params![year + 1],
+        )?;
+        conn.execute("DELETE FROM year_closings WHERE year = ?1", params![year])?;
+        Ok(())
+    })
+}
+
+// ── 期間ロック ──
+
+pub fn fetch_period_locks(conn: &Connection) -> SqlResult<Vec<PeriodLock>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, year, month, locked_at, unlocked_at
+         FROM period_locks ORDER BY year, IFNULL(month, 0), id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(PeriodLock {
+            id: row.get(0)?,
+            year: row.get(1)?,
+            month: row.get(2)?,
+            locked_at: row.get(3)?,
+            unlocked_at: row.get(4)?,
+        })
+    })?;
+    rows.collect()
+}
+
+pub fn insert_period_lock(conn: &Connection, year: i32, month: Option<i32>) -> SqlResult<i64> {
+    conn.execute(
+        "INSERT INTO period_locks (year, month) VALUES (?1, ?2)",
+        params![year, month],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// 有効なロックを解除する（履歴として解除日時を記録）
+pub fn release_period_lock(conn: &Connection, year: i32, month: Option<i32>) -> SqlResult<usize> {
+    conn.execute(
+        "UPDATE period_locks SET unlocked_at = datetime('now', 'localtime')
+         WHERE year = ?1 AND IFNULL(month, 0) = IFNULL(?2, 0) AND unlocked_at IS NULL",
+        params![year, month],
+    )
+}
+
+/// 指定の年月が締め済みか（月のロックまたは年度全体のロック）
+pub fn is_period_locked(conn: &Connection, year: i32, month: i32) -> SqlResult<bool> {
+    conn.query_row(
+        "SELECT EXISTS (
+             SELECT 1 FROM period_locks
+             WHERE year = ?1 AND (month IS NULL OR month = ?2) AND unlocked_at IS NULL
+         )",
+        params![year, month],
+        |row| row.get(0),
+    )
+}
+
+/// from_year〜to_year のうち年度全体がロックされている最初の年度
+pub fn find_locked_year(conn: &Connection, from_year: i32, to_year: i32) -> SqlResult<Option<i32>> {
+    conn.query_row(
+        "SELECT MIN(year) FROM period_locks
+         WHERE year >= ?1 AND year <= ?2 AND month IS NULL AND unlocked_at IS NULL",
+        params![from_year, to_year],
+        |row| row.get(0),
+    )
+}
+
+pub fn fetch_fixed_asset(conn: &Connection, id: i64) -> SqlResult<Option<FixedAsset>> {
+    Ok(fetch_fixed_assets(conn)?.into_iter().find(|a| a.id == id))
+}
+
+pub fn fetch_loss_carryforward(conn: &Connection, id: i64) -> SqlResult<Option<LossCarryforward>> {
+    Ok(fetch_loss_carryforwards(conn)?.into_iter().find(|l| l.id == id))
+}
+
 // ── 固定資産 ──
 
 pub fn fetch_fixed_assets(conn: &Connection) -> SqlResult<Vec<FixedAsset>> {
@@ -383,6 +2383,28 @@ pub fn insert_fixed_asset(
     Ok(conn.last_insert_rowid())
 }
 
+/// 削除した固定資産を同じ ID で登録し直す（元に戻す操作用）
+pub fn restore_fixed_asset(conn: &Connection, asset: &FixedAsset) -> SqlResult<()> {
+    conn.execute(
+        "INSERT INTO fixed_assets (id, name, acquisition_date, acquisition_cost, useful_life,
+                depreciation_method, depreciation_rate, accumulated_dep, memo, is_active)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            asset.id,
+            asset.name,
+            asset.acquisition_date,
+            asset.acquisition_cost,
+            asset.useful_life,
+            asset.depreciation_method,
+            asset.depreciation_rate,
+            asset.accumulated_dep,
+            asset.memo,
+            asset.is_active
+        ],
+    )?;
+    Ok(())
+}
+
 pub fn delete_fixed_asset(conn: &Connection, id: i64) -> SqlResult<usize> {
     conn.execute("DELETE FROM fixed_assets WHERE id = ?1", params![id])
 }
@@ -397,8 +2419,11 @@ pub fn calc_depreciation(conn: &Connection, year: i32) -> SqlResult<Vec<Deprecia
             continue;
         }
         // 取得年を判定し、取得初年度の月割り計算
-        let acq_year: i32 = asset.acquisition_date[..4].parse().unwrap_or(year);
-        let acq_month: i32 = asset.acquisition_date[5..7].parse().unwrap_or(1);
+        // 取得日を読み取れない資産は償却期間を判定できないため計上しない（get_invalid_dates で確認できる）
+        let Ok(acquisition_date) = Date::parse(&asset.acquisition_date) else {
+            continue;
+        };
+        let (acq_year, acq_month) = (acquisition_date.year(), acquisition_date.month());
 
         // 取得年より前の年度は対象外
         if year < acq_year {
@@ -434,7 +2459,7 @@ pub fn calc_depreciation(conn: &Connection, year: i32) -> SqlResult<Vec<Deprecia
         rows.push(DepreciationRow {
             asset_id: asset.id,
             asset_name: asset.name,
-            acquisition_date: asset.acquisition_date,
+            acquisition_date,
             acquisition_cost: asset.acquisition_cost,
             depreciation_method: asset.depreciation_method,
             useful_life: asset.useful_life,
@@ -450,13 +2475,20 @@ pub fn calc_depreciation(conn: &Connection, year: i32) -> SqlResult<Vec<Deprecia
 
 // ── 地代家賃内訳 ──
 
-pub fn fetch_rent_details(conn: &Connection) -> SqlResult<Vec<RentDetail>> {
+/// 年度の決算書に記載する内訳
+/// 取引先を参照している内訳は、支払先の氏名・住所に取引先マスタの内容を使う
+pub fn fetch_rent_details(conn: &Connection, year: i32) -> SqlResult<Vec<RentDetail>> {
     let mut stmt = conn.prepare(
-        "SELECT id, payee_address, payee_name, rent_type, monthly_rent,
-                annual_total, business_ratio, memo
-         FROM rent_details ORDER BY id",
+        "SELECT r.id,
+                CASE WHEN p.id IS NULL OR p.address = '' THEN r.payee_address ELSE p.address END,
+                COALESCE(p.name, r.payee_name),
+                r.rent_type, r.monthly_rent, r.annual_total, r.business_ratio, r.memo, r.partner_id, r.year
+         FROM rent_details r
+         LEFT JOIN partners p ON p.id = r.partner_id
+         WHERE r.year = ?1
+         ORDER BY r.id",
     )?;
-    let rows = stmt.query_map([], |row| {
+    let rows = stmt.query_map(params![year], |row| {
         Ok(RentDetail {
             id: row.get(0)?,
             payee_address: row.get(1)?,
@@ -466,48 +2498,391 @@ pub fn fetch_rent_details(conn: &Connection) -> SqlResult<Vec<RentDetail>> {
             annual_total: row.get(5)?,
             business_ratio: row.get(6)?,
             memo: row.get(7)?,
+            partner_id: row.get(8)?,
+            year: row.get(9)?,
+        })
+    })?;
+    rows.collect()
+}
+
+pub fn insert_rent_detail(
+    conn: &Connection,
+    year: i32,
+    payee_address: &str,
+    payee_name: &str,
+    rent_type: &str,
+    monthly_rent: i64,
+    annual_total: i64,
+    business_ratio: i32,
+    memo: &str,
+    partner_id: Option<i64>,
+) -> SqlResult<i64> {
+    conn.execute(
+        "INSERT INTO rent_details (payee_address, payee_name, rent_type, monthly_rent,
+                annual_total, business_ratio, memo, partner_id, year)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![payee_address, payee_name, rent_type, monthly_rent, annual_total, business_ratio, memo, partner_id, year],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// 登録されたままの内訳（取引先マスタの内容で置き換えない）
+pub fn fetch_rent_detail(conn: &Connection, id: i64) -> SqlResult<Option<RentDetail>> {
+    conn.query_row(
+        "SELECT id, payee_address, payee_name, rent_type, monthly_rent,
+                annual_total, business_ratio, memo, partner_id, year
+         FROM rent_details WHERE id = ?1",
+        params![id],
+        |row| {
+            Ok(RentDetail {
+                id: row.get(0)?,
+                payee_address: row.get(1)?,
+                payee_name: row.get(2)?,
+                rent_type: row.get(3)?,
+                monthly_rent: row.get(4)?,
+                annual_total: row.get(5)?,
+                business_ratio: row.get(6)?,
+                memo: row.get(7)?,
+                partner_id: row.get(8)?,
+                year: row.get(9)?,
+            })
+        },
+    )
+    .optional()
+}
+
+/// 削除した内訳を同じ ID で登録し直す（元に戻す操作用）
+pub fn restore_rent_detail(conn: &Connection, detail: &RentDetail) -> SqlResult<()> {
+    conn.execute(
+        "INSERT INTO rent_details (id, payee_address, payee_name, rent_type, monthly_rent,
+                annual_total, business_ratio, memo, partner_id, year)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            detail.id,
+            detail.payee_address,
+            detail.payee_name,
+            detail.rent_type,
+            detail.monthly_rent,
+            detail.annual_total,
+            detail.business_ratio,
+            detail.memo,
+            detail.partner_id,
+            detail.year
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn delete_rent_detail(conn: &Connection, id: i64) -> SqlResult<usize> {
+    conn.execute("DELETE FROM rent_details WHERE id = ?1", params![id])
+}
+
+// ── 仕訳テンプレート ──
+
+fn map_journal_template(row: &rusqlite::Row) -> SqlResult<JournalTemplate> {
+    Ok(JournalTemplate {
+        id: row.get(0)?,
+        label: row.get(1)?,
+        category: row.get(2)?,
+        help_text: row.get(3)?,
+        debit_account_id: row.get(4)?,
+        debit_account_name: row.get(5)?,
+        credit_account_id: row.get(6)?,
+        credit_account_name: row.get(7)?,
+        default_description: row.get(8)?,
+        description_placeholder: row.get(9)?,
+        default_amount: row.get(10)?,
+        tax_category: row.get(11)?,
+        sort_order: row.get(12)?,
+    })
+}
+
+const JOURNAL_TEMPLATE_COLUMNS: &str =
+    "t.id, t.label, t.category, t.help_text, t.debit_account_id, da.name, t.credit_account_id, ca.name,
+     t.default_description, t.description_placeholder, t.default_amount, t.tax_category, t.sort_order";
+
+pub fn fetch_journal_templates(conn: &Connection) -> SqlResult<Vec<JournalTemplate>> {
+    let sql = format!(
+        "SELECT {}
+         FROM journal_templates t
+         JOIN accounts da ON da.id = t.debit_account_id
+         JOIN accounts ca ON ca.id = t.credit_account_id
+         ORDER BY t.sort_order, t.id",
+        JOURNAL_TEMPLATE_COLUMNS
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map([], map_journal_template)?;
+    rows.collect()
+}
+
+pub fn fetch_journal_template(conn: &Connection, id: i64) -> SqlResult<Option<JournalTemplate>> {
+    let sql = format!(
+        "SELECT {}
+         FROM journal_templates t
+         JOIN accounts da ON da.id = t.debit_account_id
+         JOIN accounts ca ON ca.id = t.credit_account_id
+         WHERE t.id = ?1",
+        JOURNAL_TEMPLATE_COLUMNS
+    );
+    conn.query_row(&sql, params![id], map_journal_template).optional()
+}
+
+/// テンプレートを追加する（並び順は末尾）
+pub fn insert_journal_template(conn: &Connection, template: &JournalTemplateInput) -> SqlResult<i64> {
+    conn.execute(
+        "INSERT INTO journal_templates (label, category, help_text, debit_account_id, credit_account_id,
+                default_description, description_placeholder, default_amount, tax_category, sort_order)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9,
+                 (SELECT COALESCE(MAX(sort_order), 0) + 1 FROM journal_templates))",
+        params![
+            template.label,
+            template.category,
+            template.help_text,
+            template.debit_account_id,
+            template.credit_account_id,
+            template.default_description,
+            template.description_placeholder,
+            template.default_amount,
+            template.tax_category
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn update_journal_template(conn: &Connection, id: i64, template: &JournalTemplateInput) -> SqlResult<usize> {
+    conn.execute(
+        "UPDATE journal_templates
+         SET label = ?1, category = ?2, help_text = ?3, debit_account_id = ?4, credit_account_id = ?5,
+             default_description = ?6, description_placeholder = ?7, default_amount = ?8, tax_category = ?9
+         WHERE id = ?10",
+        params![
+            template.label,
+            template.category,
+            template.help_text,
+            template.debit_account_id,
+            template.credit_account_id,
+            template.default_description,
+            template.description_placeholder,
+            template.default_amount,
+            template.tax_category,
+            id
+        ],
+    )
+}
+
+pub fn delete_journal_template(conn: &Connection, id: i64) -> SqlResult<usize> {
+    conn.execute("DELETE FROM journal_templates WHERE id = ?1", params![id])
+}
+
+// ── 定期仕訳 ──
+
+pub fn fetch_recurring_entries(conn: &Connection) -> SqlResult<Vec<RecurringEntry>> {
+    let mut stmt = conn.prepare(
+        "SELECT r.id, r.name, r.debit_account_id, da.name, r.credit_account_id, ca.name, r.amount,
+                r.description_template, r.frequency, r.day_of_month, r.start_date, r.end_date, r.is_active,
+                (SELECT MAX(u.due_date) FROM recurring_entry_runs u WHERE u.recurring_id = r.id)
+         FROM recurring_entries r
+         JOIN accounts da ON da.id = r.debit_account_id
+         JOIN accounts ca ON ca.id = r.credit_account_id
+         ORDER BY r.id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(RecurringEntry {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            debit_account_id: row.get(2)?,
+            debit_account_name: row.get(3)?,
+            credit_account_id: row.get(4)?,
+            credit_account_name: row.get(5)?,
+            amount: row.get(6)?,
+            description_template: row.get(7)?,
+            frequency: row.get(8)?,
+            day_of_month: row.get(9)?,
+            start_date: row.get(10)?,
+            end_date: row.get(11)?,
+            is_active: row.get::<_, i32>(12)? != 0,
+            last_due_date: row.get(13)?,
         })
     })?;
     rows.collect()
 }
 
-pub fn insert_rent_detail(
+pub fn insert_recurring_entry(
     conn: &Connection,
-    payee_address: &str,
-    payee_name: &str,
-    rent_type: &str,
-    monthly_rent: i64,
-    annual_total: i64,
-    business_ratio: i32,
-    memo: &str,
+    name: &str,
+    debit_account_id: i64,
+    credit_account_id: i64,
+    amount: i64,
+    description_template: &str,
+    frequency: &str,
+    day_of_month: u32,
+    start_date: &str,
+    end_date: Option<&str>,
 ) -> SqlResult<i64> {
     conn.execute(
-        "INSERT INTO rent_details (payee_address, payee_name, rent_type, monthly_rent,
-                annual_total, business_ratio, memo)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-        params![payee_address, payee_name, rent_type, monthly_rent, annual_total, business_ratio, memo],
+        "INSERT INTO recurring_entries (name, debit_account_id, credit_account_id, amount, description_template,
+                frequency, day_of_month, start_date, end_date)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            name,
+            debit_account_id,
+            credit_account_id,
+            amount,
+            description_template,
+            frequency,
+            day_of_month,
+            start_date,
+            end_date
+        ],
     )?;
     Ok(conn.last_insert_rowid())
 }
 
-pub fn delete_rent_detail(conn: &Connection, id: i64) -> SqlResult<usize> {
-    conn.execute("DELETE FROM rent_details WHERE id = ?1", params![id])
+pub fn update_recurring_entry(
+    conn: &Connection,
+    id: i64,
+    name: &str,
+    debit_account_id: i64,
+    credit_account_id: i64,
+    amount: i64,
+    description_template: &str,
+    frequency: &str,
+    day_of_month: u32,
+    start_date: &str,
+    end_date: Option<&str>,
+    is_active: bool,
+) -> SqlResult<usize> {
+    conn.execute(
+        "UPDATE recurring_entries
+         SET name = ?1, debit_account_id = ?2, credit_account_id = ?3, amount = ?4, description_template = ?5,
+             frequency = ?6, day_of_month = ?7, start_date = ?8, end_date = ?9, is_active = ?10
+         WHERE id = ?11",
+        params![
+            name,
+            debit_account_id,
+            credit_account_id,
+            amount,
+            description_template,
+            frequency,
+            day_of_month,
+            start_date,
+            end_date,
+            is_active,
+            id
+        ],
+    )
+}
+
+/// 定義を削除する（作成済みの仕訳は残る）
+pub fn delete_recurring_entry(conn: &Connection, id: i64) -> SqlResult<usize> {
+    conn.execute("DELETE FROM recurring_entries WHERE id = ?1", params![id])
+}
+
+fn last_day_of_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .and_then(|d| d.pred_opt())
+        .map(|d| d.day())
+        .unwrap_or(28)
+}
+
+/// 定期仕訳の start_date〜until（終了日があればそこまで）の計上日を求める
+pub fn recurring_due_dates(entry: &RecurringEntry, until: NaiveDate) -> Vec<NaiveDate> {
+    let Ok(Date(start)) = Date::parse(&entry.start_date) else {
+        return Vec::new();
+    };
+    let last = entry
+        .end_date
+        .as_deref()
+        .and_then(|d| Date::parse(d).ok())
+        .map_or(until, |Date(end)| end.min(until));
+
+    let step = match entry.frequency.as_str() {
+        "指定日" => return if start <= last { vec![start] } else { Vec::new() },
+        "隔月" => 2,
+        "毎年" => 12,
+        _ => 1,
+    };
+
+    let mut dates = Vec::new();
+    let mut index = start.year() * 12 + start.month0() as i32;
+    loop {
+        let (year, month) = (index.div_euclid(12), index.rem_euclid(12) as u32 + 1);
+        let last_day = last_day_of_month(year, month);
+        let day = if entry.frequency == "月末" { last_day } else { entry.day_of_month.min(last_day) };
+        let Some(date) = NaiveDate::from_ymd_opt(year, month, day) else { break };
+        if date > last {
+            break;
+        }
+        if date >= start {
+            dates.push(date);
+        }
+        index += step;
+    }
+    dates
+}
+
+/// 摘要のテンプレートを計上日で置き換える（{year} {month} {day}）
+fn render_description(template: &str, date: NaiveDate) -> String {
+    template
+        .replace("{year}", &date.year().to_string())
+        .replace("{month}", &date.month().to_string())
+        .replace("{day}", &date.day().to_string())
+}
+
+/// until までに計上すべきで、まだ作成していない (定義, 計上日) の一覧
+pub fn fetch_pending_recurring(conn: &Connection, until: NaiveDate) -> SqlResult<Vec<(RecurringEntry, Date)>> {
+    let mut stmt = conn.prepare("SELECT 1 FROM recurring_entry_runs WHERE recurring_id = ?1 AND due_date = ?2")?;
+    let mut pending = Vec::new();
+    for entry in fetch_recurring_entries(conn)?.into_iter().filter(|e| e.is_active) {
+        for date in recurring_due_dates(&entry, until).into_iter().map(Date) {
+            if !stmt.exists(params![entry.id, date])? {
+                pending.push((entry.clone(), date));
+            }
+        }
+    }
+    Ok(pending)
+}
+
+/// 定期仕訳を1件計上し、計上履歴に記録する
+pub fn run_recurring_entry(conn: &Connection, entry: &RecurringEntry, due_date: Date) -> SqlResult<i64> {
+    let description = render_description(&entry.description_template, due_date.0);
+    in_savepoint(conn, || {
+        let entry_id = insert_entry(
+            conn,
+            &due_date.to_string(),
+            entry.debit_account_id,
+            entry.amount,
+            entry.credit_account_id,
+            entry.amount,
+            &description,
+            None,
+            None,
+            None,
+            None,
+        )?;
+        conn.execute(
+            "INSERT INTO recurring_entry_runs (recurring_id, due_date, entry_id) VALUES (?1, ?2, ?3)",
+            params![entry.id, due_date, entry_id],
+        )?;
+        Ok(entry_id)
+    })
 }
 
 // ── 月別売上・仕入 ──
 
 pub fn calc_monthly_sales_purchases(conn: &Connection, year: i32) -> SqlResult<Vec<MonthlySalesPurchase>> {
-    // 売上 = 貸方に売上高(4100)が使われた仕訳の金額
-    // 仕入 = 借方に仕入高(5100)が使われた仕訳の金額
+    // 売上 = 貸方に売上高(4100)が使われた明細の金額
+    // 仕入 = 借方に仕入高(5100)が使われた明細の金額
     let sql = "
         SELECT
             CAST(substr(j.date, 6, 2) AS INTEGER) AS month,
-            COALESCE(SUM(CASE WHEN ca.code = 4100 THEN j.credit_amount ELSE 0 END), 0) AS sales,
-            COALESCE(SUM(CASE WHEN da.code = 5100 THEN j.debit_amount ELSE 0 END), 0) AS purchases
-        FROM journal_entries j
-        JOIN accounts da ON da.id = j.debit_account_id
-        JOIN accounts ca ON ca.id = j.credit_account_id
-        WHERE j.date >= ?1 AND j.date <= ?2
+            COALESCE(SUM(CASE WHEN a.code = 4100 AND l.side = '貸方' THEN l.amount ELSE 0 END), 0) AS sales,
+            COALESCE(SUM(CASE WHEN a.code = 5100 AND l.side = '借方' THEN l.amount ELSE 0 END), 0) AS purchases
+        FROM journal_lines l
+        JOIN journal_entries j ON j.id = l.entry_id
+        JOIN accounts a ON a.id = l.account_id
+        WHERE j.date >= ?1 AND j.date <= ?2 AND j.voided_at IS NULL
         GROUP BY month
         ORDER BY month";
 
@@ -540,6 +2915,481 @@ pub fn calc_monthly_sales_purchases(conn: &Connection, year: i32) -> SqlResult<V
     Ok(result)
 }
 
+// ── 家事按分 ──
+
+/// 家事分の振替先（事業主貸）の科目コード
+const OWNER_DRAWINGS_CODE: i32 = 3200;
+
+pub fn fetch_apportionment_rules(conn: &Connection) -> SqlResult<Vec<ApportionmentRule>> {
+    let mut stmt = conn.prepare(
+        "SELECT r.id, r.account_id, a.code, a.name, r.business_ratio, r.basis
+         FROM apportionment_rules r
+         JOIN accounts a ON a.id = r.account_id
+         ORDER BY a.code",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(ApportionmentRule {
+            id: row.get(0)?,
+            account_id: row.get(1)?,
+            account_code: row.get(2)?,
+            account_name: row.get(3)?,
+            business_ratio: row.get(4)?,
+            basis: row.get(5)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// 科目の家事按分ルールを登録する（登録済みなら事業割合と根拠を更新する）
+pub fn save_apportionment_rule(conn: &Connection, account_id: i64, business_ratio: i32, basis: &str) -> SqlResult<i64> {
+    conn.query_row(
+        "INSERT INTO apportionment_rules (account_id, business_ratio, basis) VALUES (?1, ?2, ?3)
+         ON CONFLICT (account_id) DO UPDATE SET business_ratio = excluded.business_ratio, basis = excluded.basis
+         RETURNING id",
+        params![account_id, business_ratio, basis],
+        |row| row.get(0),
+    )
+}
+
+pub fn delete_apportionment_rule(conn: &Connection, id: i64) -> SqlResult<usize> {
+    conn.execute("DELETE FROM apportionment_rules WHERE id = ?1", params![id])
+}
+
+/// 年度の家事按分を計算する（家事分 = 費用 ×（100 − 事業割合）%、1円未満切り捨て）
+/// 費用には按分の振替仕訳を含めないため、作成済みでも作り直し後の金額を返す
+pub fn calc_apportionment(conn: &Connection, year: i32) -> SqlResult<Vec<ApportionmentLine>> {
+    let (date_from, date_to) = period_bounds(year, None);
+    let mut stmt = conn.prepare(
+        "SELECT r.account_id, a.code, a.name, r.business_ratio, r.basis,
+                COALESCE((
+                    SELECT SUM(CASE WHEN jl.side = '借方' THEN jl.amount ELSE -jl.amount END)
+                    FROM journal_lines jl
+                    JOIN journal_entries j ON j.id = jl.entry_id
+                    WHERE jl.account_id = r.account_id
+                      AND j.date >= ?1 AND j.date <= ?2 AND j.voided_at IS NULL
+                      AND j.id NOT IN (SELECT entry_id FROM apportionment_entries)
+                ), 0),
+                COALESCE((
+                    SELECT SUM(j.credit_amount)
+                    FROM apportionment_entries e
+                    JOIN journal_entries j ON j.id = e.entry_id
+                    WHERE e.year = ?3 AND e.account_id = r.account_id AND j.voided_at IS NULL
+                ), 0)
+         FROM apportionment_rules r
+         JOIN accounts a ON a.id = r.account_id
+         ORDER BY a.code",
+    )?;
+    let rows = stmt.query_map(params![date_from, date_to, year], |row| {
+        let business_ratio: i32 = row.get(3)?;
+        let expense_total: i64 = row.get(5)?;
+        Ok(ApportionmentLine {
+            account_id: row.get(0)?,
+            account_code: row.get(1)?,
+            account_name: row.get(2)?,
+            business_ratio,
+            basis: row.get(4)?,
+            expense_total,
+            private_amount: (expense_total.max(0) as i128 * (100 - business_ratio) as i128 / 100) as i64,
+            posted_amount: row.get(6)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// 年度の作成済みの家事按分の振替仕訳（取消済みを除く）
+pub fn fetch_apportionment_entry_ids(conn: &Connection, year: i32) -> SqlResult<Vec<i64>> {
+    let mut stmt = conn.prepare(
+        "SELECT e.entry_id FROM apportionment_entries e
+         JOIN journal_entries j ON j.id = e.entry_id
+         WHERE e.year = ?1 AND j.voided_at IS NULL
+         ORDER BY e.entry_id",
+    )?;
+    let rows = stmt.query_map(params![year], |row| row.get(0))?;
+    rows.collect()
+}
+
+/// 年度の家事按分の振替仕訳（12月31日付 事業主貸 / 費用科目）を作り直す
+/// 作成済みの振替仕訳は取り消し、家事分が1円以上の科目ごとに1件ずつ登録する
+pub fn post_apportionment(conn: &Connection, year: i32, lines: &[ApportionmentLine]) -> SqlResult<Vec<i64>> {
+    in_savepoint(conn, || {
+        for id in fetch_apportionment_entry_ids(conn, year)? {
+            delete_entry(conn, id)?;
+        }
+        let owner_drawings_id: i64 = conn.query_row(
+            "SELECT id FROM accounts WHERE code = ?1",
+            params![OWNER_DRAWINGS_CODE],
+            |row| row.get(0),
+        )?;
+        let date = format!("{:04}-12-31", year);
+        let mut ids = Vec::new();
+        for line in lines.iter().filter(|l| l.private_amount > 0) {
+            let mut description = format!("家事按分 {}（事業割合{}%", line.account_name, line.business_ratio);
+            if !line.basis.is_empty() {
+                description.push_str(&format!("・{}", line.basis));
+            }
+            description.push('）');
+            let id = insert_entry(
+                conn, &date, owner_drawings_id, line.private_amount, line.account_id, line.private_amount,
+                &description, None, None, None, None,
+            )?;
+            conn.execute(
+                "INSERT INTO apportionment_entries (entry_id, year, account_id) VALUES (?1, ?2, ?3)",
+                params![id, year, line.account_id],
+            )?;
+            ids.push(id);
+        }
+        Ok(ids)
+    })
+}
+
+// ── 外貨建取引 ──
+
+/// 為替差益・為替差損の科目コード
+const EXCHANGE_GAIN_CODE: i32 = 4300;
+const EXCHANGE_LOSS_CODE: i32 = 5960;
+
+/// 換算に使う TTM を遡って探す日数（土日祝日や年末年始はレートがないため）
+pub const RATE_LOOKBACK_DAYS: i64 = 7;
+
+/// 外貨額・換算レートを整数で保存するときの小数点以下の桁数
+/// （外貨額は100倍、レートは10000倍した整数で保存し、浮動小数点の誤差で1円ずれないようにする）
+pub const FOREIGN_AMOUNT_SCALE: u32 = 2;
+pub const RATE_SCALE: u32 = 4;
+
+/// 外貨額（× 100）× 換算レート（× 10000）の円換算額
+/// 1円未満は切り捨てる（負の額は0に近い方へ切り捨て、正負で絶対値がそろうようにする）
+pub fn convert_to_yen(foreign_amount: i64, rate: i64) -> i64 {
+    let scale = 10_i128.pow(FOREIGN_AMOUNT_SCALE + RATE_SCALE);
+    (foreign_amount as i128 * rate as i128 / scale) as i64
+}
+
+/// 外貨建ての単一行仕訳を登録する（amount は円換算額）
+pub fn insert_foreign_entry(
+    conn: &Connection,
+    date: &str,
+    debit_account_id: i64,
+    credit_account_id: i64,
+    amount: i64,
+    description: &str,
+    partner_id: Option<i64>,
+    foreign: &ForeignAmount,
+) -> SqlResult<i64> {
+    in_savepoint(conn, || {
+        let id = insert_entry(
+            conn, date, debit_account_id, amount, credit_account_id, amount, description, partner_id, None, None, None,
+        )?;
+        conn.execute(
+            "UPDATE journal_entries SET currency = ?2, foreign_amount = ?3, exchange_rate = ?4, foreign_side = ?5
+             WHERE id = ?1",
+            params![id, foreign.currency, foreign.foreign_amount, foreign.exchange_rate, foreign.foreign_side],
+        )?;
+        Ok(id)
+    })
+}
+
+/// 為替レートの一覧（currency / year を指定するとその通貨・年のものだけ）
+pub fn fetch_exchange_rates(conn: &Connection, currency: Option<&str>, year: Option<i32>) -> SqlResult<Vec<ExchangeRate>> {
+    let mut stmt = conn.prepare(
+        "SELECT currency, date, ttm FROM exchange_rates
+         WHERE (?1 IS NULL OR currency = ?1) AND (?2 IS NULL OR substr(date, 1, 4) = printf('%04d', ?2))
+         ORDER BY currency, date",
+    )?;
+    let rows = stmt.query_map(params![currency, year], |row| {
+        Ok(ExchangeRate { currency: row.get(0)?, date: row.get(1)?, ttm: row.get(2)? })
+    })?;
+    rows.collect()
+}
+
+/// date 以前で直近の TTM（RATE_LOOKBACK_DAYS 日より前のレートは使わない）
+pub fn find_exchange_rate(conn: &Connection, currency: &str, date: NaiveDate) -> SqlResult<Option<ExchangeRate>> {
+    let oldest = date - chrono::Duration::days(RATE_LOOKBACK_DAYS);
+    conn.query_row(
+        "SELECT currency, date, ttm FROM exchange_rates
+         WHERE currency = ?1 AND date <= ?2 AND date >= ?3
+         ORDER BY date DESC LIMIT 1",
+        params![currency, Date(date), Date(oldest)],
+        |row| Ok(ExchangeRate { currency: row.get(0)?, date: row.get(1)?, ttm: row.get(2)? }),
+    )
+    .optional()
+}
+
+/// 為替レートをまとめて登録する（同じ通貨・日付のレートは上書きする）
+pub fn import_exchange_rates(conn: &Connection, rates: &[ExchangeRate]) -> SqlResult<usize> {
+    in_savepoint(conn, || {
+        let mut stmt = conn.prepare(
+            "INSERT INTO exchange_rates (currency, date, ttm) VALUES (?1, ?2, ?3)
+             ON CONFLICT (currency, date) DO UPDATE SET
+                 ttm = excluded.ttm, imported_at = datetime('now', 'localtime')",
+        )?;
+        for rate in rates {
+            stmt.execute(params![rate.currency, rate.date, rate.ttm])?;
+        }
+        Ok(rates.len())
+    })
+}
+
+/// 外貨建取引の年末レポート
+/// 資産・負債科目の外貨建取引を科目・通貨ごとに集計し、年末の TTM で換算し直した額と比べる
+/// 外貨額は foreign_side の側の科目にだけ計上する（円建ての相手科目に外貨残高は出さない）
+/// 外貨残高が0でも円換算額が残っていれば、決済時の為替差額が未計上として換算差額に出る
+pub fn calc_foreign_currency_report(conn: &Connection, year: i32) -> SqlResult<ForeignCurrencyReport> {
+    let (date_from, date_to) = period_bounds(year, None);
+    let mut stmt = conn.prepare(
+        "SELECT a.id, a.code, a.name, a.classification, x.currency, SUM(x.foreign_amount), SUM(x.amount)
+         FROM (
+             SELECT debit_account_id AS account_id, currency, foreign_amount, debit_amount AS amount
+             FROM journal_entries
+             WHERE currency IS NOT NULL AND voided_at IS NULL AND is_compound = 0 AND date <= ?1
+               AND foreign_side IN ('借方', '両方')
+             UNION ALL
+             SELECT credit_account_id, currency, -foreign_amount, -credit_amount
+             FROM journal_entries
+             WHERE currency IS NOT NULL AND voided_at IS NULL AND is_compound = 0 AND date <= ?1
+               AND foreign_side IN ('貸方', '両方')
+         ) x
+         JOIN accounts a ON a.id = x.account_id
+         WHERE a.classification IN ('資産', '負債')
+         GROUP BY a.id, x.currency
+         ORDER BY a.code, x.currency",
+    )?;
+    let rows = stmt
+        .query_map(params![date_to], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i32>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, i64>(5)?,
+                row.get::<_, i64>(6)?,
+            ))
+        })?
+        .collect::<SqlResult<Vec<_>>>()?;
+
+    let year_end = NaiveDate::from_ymd_opt(year, 12, 31);
+    let mut positions = Vec::new();
+    for (account_id, account_code, account_name, classification, currency, foreign_sum, amount_sum) in rows {
+        // 負債は貸方残高を正にする
+        let sign = if classification == "負債" { -1 } else { 1 };
+        let foreign_balance = foreign_sum * sign;
+        let book_amount = amount_sum * sign;
+        if foreign_balance == 0 && book_amount == 0 {
+            continue;
+        }
+        let year_end_rate = match year_end {
+            Some(date) => find_exchange_rate(conn, &currency, date)?.map(|r| r.ttm),
+            None => None,
+        };
+        let year_end_amount = year_end_rate.map(|rate| convert_to_yen(foreign_balance, rate));
+        positions.push(ForeignCurrencyPosition {
+            account_id,
+            account_code,
+            account_name,
+            classification,
+            currency,
+            foreign_balance,
+            book_amount,
+            year_end_rate,
+            year_end_amount,
+            difference: year_end_amount.map(|a| a - book_amount),
+        });
+    }
+
+    let (exchange_gain, exchange_loss): (i64, i64) = conn.query_row(
+        "SELECT
+             COALESCE(SUM(CASE WHEN a.code = ?3 THEN CASE WHEN l.side = '貸方' THEN l.amount ELSE -l.amount END END), 0),
+             COALESCE(SUM(CASE WHEN a.code = ?4 THEN CASE WHEN l.side = '借方' THEN l.amount ELSE -l.amount END END), 0)
+         FROM journal_lines l
+         JOIN journal_entries j ON j.id = l.entry_id
+         JOIN accounts a ON a.id = l.account_id
+         WHERE j.date >= ?1 AND j.date <= ?2 AND j.voided_at IS NULL AND a.code IN (?3, ?4)",
+        params![date_from, date_to, EXCHANGE_GAIN_CODE, EXCHANGE_LOSS_CODE],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+
+    Ok(ForeignCurrencyReport { year, positions, exchange_gain, exchange_loss })
+}
+
+// ── 消込 ──
+
+/// 未消込の年齢表の区分（経過日数の上限。None は上限なし）
+const AGING_BUCKETS: &[(&str, Option<i64>)] = &[
+    ("30日以内", Some(30)),
+    ("31〜60日", Some(60)),
+    ("61〜90日", Some(90)),
+    ("91〜180日", Some(180)),
+    ("181日超", None),
+];
+
+/// 借方・貸方に対応する item_matches の列（自分の側・相手の側）
+fn match_columns(side: &str) -> (&'static str, &'static str) {
+    if side == "借方" {
+        ("debit_entry_id", "credit_entry_id")
+    } else {
+        ("credit_entry_id", "debit_entry_id")
+    }
+}
+
+/// 仕訳の科目の借方または貸方のうち、まだ消し込まれていない金額（取消済みの仕訳は0）
+pub fn unmatched_amount(conn: &Connection, entry_id: i64, account_id: i64, side: &str) -> SqlResult<i64> {
+    let (own, other) = match_columns(side);
+    conn.query_row(
+        &format!(
+            "SELECT COALESCE(SUM(jl.amount), 0) - COALESCE((
+                    SELECT SUM(m.amount) FROM item_matches m
+                    JOIN journal_entries o ON o.id = m.{other}
+                    WHERE m.{own} = ?1 AND m.account_id = ?2 AND o.voided_at IS NULL
+                ), 0)
+             FROM journal_lines jl
+             JOIN journal_entries j ON j.id = jl.entry_id
+             WHERE jl.entry_id = ?1 AND jl.account_id = ?2 AND jl.side = ?3 AND j.voided_at IS NULL"
+        ),
+        params![entry_id, account_id, side],
+        |row| row.get(0),
+    )
+}
+
+pub fn insert_item_match(
+    conn: &Connection,
+    account_id: i64,
+    debit_entry_id: i64,
+    credit_entry_id: i64,
+    amount: i64,
+) -> SqlResult<i64> {
+    conn.execute(
+        "INSERT INTO item_matches (account_id, debit_entry_id, credit_entry_id, amount) VALUES (?1, ?2, ?3, ?4)",
+        params![account_id, debit_entry_id, credit_entry_id, amount],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn delete_item_match(conn: &Connection, id: i64) -> SqlResult<usize> {
+    conn.execute("DELETE FROM item_matches WHERE id = ?1", params![id])
+}
+
+/// 解除した消込を同じ ID で登録し直す（元に戻す操作用）
+pub fn restore_item_match(conn: &Connection, item_match: &ItemMatch) -> SqlResult<()> {
+    conn.execute(
+        "INSERT INTO item_matches (id, account_id, debit_entry_id, credit_entry_id, amount) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            item_match.id,
+            item_match.account_id,
+            item_match.debit_entry_id,
+            item_match.credit_entry_id,
+            item_match.amount
+        ],
+    )?;
+    Ok(())
+}
+
+const ITEM_MATCH_SELECT: &str = "
+    SELECT m.id, m.account_id, m.debit_entry_id, d.date, d.description,
+           m.credit_entry_id, c.date, c.description, m.amount
+    FROM item_matches m
+    JOIN journal_entries d ON d.id = m.debit_entry_id
+    JOIN journal_entries c ON c.id = m.credit_entry_id";
+
+fn map_item_match(row: &rusqlite::Row) -> SqlResult<ItemMatch> {
+    Ok(ItemMatch {
+        id: row.get(0)?,
+        account_id: row.get(1)?,
+        debit_entry_id: row.get(2)?,
+        debit_date: row.get(3)?,
+        debit_description: row.get(4)?,
+        credit_entry_id: row.get(5)?,
+        credit_date: row.get(6)?,
+        credit_description: row.get(7)?,
+        amount: row.get(8)?,
+    })
+}
+
+pub fn fetch_item_match(conn: &Connection, id: i64) -> SqlResult<Option<ItemMatch>> {
+    conn.query_row(&format!("{ITEM_MATCH_SELECT} WHERE m.id = ?1"), params![id], map_item_match)
+        .optional()
+}
+
+/// 仕訳に関係する消込（借方・貸方のどちら側でも。相手が取消済みの消込を除く）
+pub fn fetch_item_matches(conn: &Connection, entry_id: i64) -> SqlResult<Vec<ItemMatch>> {
+    let mut stmt = conn.prepare(&format!(
+        "{ITEM_MATCH_SELECT}
+         WHERE (m.debit_entry_id = ?1 OR m.credit_entry_id = ?1)
+           AND d.voided_at IS NULL AND c.voided_at IS NULL
+         ORDER BY c.date, m.id"
+    ))?;
+    let rows = stmt.query_map(params![entry_id], map_item_match)?;
+    rows.collect()
+}
+
+/// 基準日までの仕訳で、消込の対象科目の side 側に消し込まれていない金額が残るもの
+/// 基準日より後の仕訳との消込は含めない（過去の基準日でもその時点の残高になる）
+fn fetch_unmatched_items(
+    conn: &Connection,
+    as_of: NaiveDate,
+    account_id: Option<i64>,
+    side: &str,
+) -> SqlResult<Vec<UnmatchedItem>> {
+    let (own, other) = match_columns(side);
+    let mut stmt = conn.prepare(&format!(
+        "SELECT j.id, j.voucher_no, j.date, j.description, p.name, a.id, a.code, a.name, SUM(jl.amount),
+                COALESCE((
+                    SELECT SUM(m.amount) FROM item_matches m
+                    JOIN journal_entries o ON o.id = m.{other}
+                    WHERE m.{own} = j.id AND m.account_id = a.id AND o.voided_at IS NULL AND o.date <= ?1
+                ), 0) AS matched
+         FROM journal_lines jl
+         JOIN journal_entries j ON j.id = jl.entry_id
+         JOIN accounts a ON a.id = jl.account_id
+         LEFT JOIN partners p ON p.id = j.partner_id
+         WHERE jl.side = ?2 AND a.is_matching = 1
+           AND j.voided_at IS NULL AND j.date <= ?1
+           AND (?3 IS NULL OR a.id = ?3)
+         GROUP BY j.id, a.id
+         HAVING SUM(jl.amount) <> matched
+         ORDER BY a.code, j.date, j.id"
+    ))?;
+    let rows = stmt.query_map(params![as_of.to_string(), side, account_id], |row| {
+        let date: String = row.get(2)?;
+        let amount: i64 = row.get(8)?;
+        let matched_amount: i64 = row.get(9)?;
+        Ok(UnmatchedItem {
+            entry_id: row.get(0)?,
+            voucher_no: row.get(1)?,
+            age_days: Date::parse(&date).map(|d| (as_of - d.0).num_days()).unwrap_or(0),
+            date,
+            description: row.get(3)?,
+            partner_name: row.get(4)?,
+            account_id: row.get(5)?,
+            account_code: row.get(6)?,
+            account_name: row.get(7)?,
+            side: side.to_string(),
+            amount,
+            matched_amount,
+            remaining: amount - matched_amount,
+        })
+    })?;
+    rows.collect()
+}
+
+/// 基準日時点の未消込の年齢表（account_id を省略すると消込の対象科目すべて）
+pub fn calc_aging_report(conn: &Connection, as_of: NaiveDate, account_id: Option<i64>) -> SqlResult<AgingReport> {
+    let items = fetch_unmatched_items(conn, as_of, account_id, "借方")?;
+    let unapplied_credits = fetch_unmatched_items(conn, as_of, account_id, "貸方")?;
+    let mut buckets: Vec<AgingBucket> = AGING_BUCKETS
+        .iter()
+        .map(|(label, _)| AgingBucket { label: label.to_string(), count: 0, amount: 0 })
+        .collect();
+    for item in &items {
+        let index = AGING_BUCKETS
+            .iter()
+            .position(|(_, limit)| limit.is_none_or(|days| item.age_days <= days))
+            .unwrap_or(AGING_BUCKETS.len() - 1);
+        buckets[index].count += 1;
+        buckets[index].amount += item.remaining;
+    }
+    let total = items.iter().map(|i| i.remaining).sum();
+    Ok(AgingReport { as_of: as_of.to_string(), items, unapplied_credits, buckets, total })
+}
+
 // ── 純損失の繰越控除 ──
 
 pub fn fetch_loss_carryforwards(conn: &Connection) -> SqlResult<Vec<LossCarryforward>> {
@@ -589,6 +3439,24 @@ pub fn update_loss_carryforward_usage(
     )
 }
 
+/// 削除した繰越損失を同じ ID で登録し直す（元に戻す操作用）
+pub fn restore_loss_carryforward(conn: &Connection, loss: &LossCarryforward) -> SqlResult<()> {
+    conn.execute(
+        "INSERT INTO loss_carryforward (id, loss_year, loss_amount, used_year_1, used_year_2, used_year_3, memo)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            loss.id,
+            loss.loss_year,
+            loss.loss_amount,
+            loss.used_year_1,
+            loss.used_year_2,
+            loss.used_year_3,
+            loss.memo
+        ],
+    )?;
+    Ok(())
+}
+
 pub fn delete_loss_carryforward(conn: &Connection, id: i64) -> SqlResult<usize> {
     conn.execute("DELETE FROM loss_carryforward WHERE id = ?1", params![id])
 }
@@ -701,8 +3569,9 @@ pub fn calc_final_statement(conn: &Connection, year: i32) -> SqlResult<FinalStat
     let bs = calc_balance_sheet(conn, year)?;
     let monthly = calc_monthly_sales_purchases(conn, year)?;
     let dep_rows = calc_depreciation(conn, year)?;
-    let rents = fetch_rent_details(conn)?;
+    let rents = fetch_rent_details(conn, year)?;
     let loss_cf = calc_loss_carryforward(conn, year)?;
+    let open_item_count = count_open_items(conn, year)?;
 
     let annual_sales_total: i64 = monthly.iter().map(|m| m.sales).sum();
     let annual_purchases_total: i64 = monthly.iter().map(|m| m.purchases).sum();
@@ -720,5 +3589,363 @@ pub fn calc_final_statement(conn: &Connection, year: i32) -> SqlResult<FinalStat
         rent_total,
         balance_sheet: bs,
         loss_carryforward: loss_cf,
+        open_item_count,
     })
 }
+
+// ── 証憑 ──
+
+/// 証憑の一覧（entry_id を指定するとその仕訳に紐付いた証憑だけ）
+pub fn fetch_attachments(conn: &Connection, entry_id: Option<i64>) -> SqlResult<Vec<Attachment>> {
+    let mut links: HashMap<i64, Vec<i64>> = HashMap::new();
+    {
+        let mut stmt = conn.prepare("SELECT attachment_id, entry_id FROM entry_attachments ORDER BY entry_id")?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)))?;
+        for row in rows {
+            let (attachment_id, linked_entry) = row?;
+            links.entry(attachment_id).or_default().push(linked_entry);
+        }
+    }
+    let mut stmt = conn.prepare(
+        "SELECT id, file_name, stored_name, mime_type, size, sha256, received_date, memo, created_at
+         FROM attachments
+         WHERE ?1 IS NULL OR id IN (SELECT attachment_id FROM entry_attachments WHERE entry_id = ?1)
+         ORDER BY received_date DESC, id DESC",
+    )?;
+    let rows = stmt.query_map(params![entry_id], |row| {
+        let id: i64 = row.get(0)?;
+        Ok(Attachment {
+            id,
+            file_name: row.get(1)?,
+            stored_name: row.get(2)?,
+            mime_type: row.get(3)?,
+            size: row.get(4)?,
+            sha256: row.get(5)?,
+            received_date: row.get(6)?,
+            memo: row.get(7)?,
+            created_at: row.get(8)?,
+            entry_ids: links.get(&id).cloned().unwrap_or_default(),
+        })
+    })?;
+    rows.collect()
+}
+
+pub fn fetch_attachment(conn: &Connection, id: i64) -> SqlResult<Option<Attachment>> {
+    Ok(fetch_attachments(conn, None)?.into_iter().find(|a| a.id == id))
+}
+
+/// 同じ内容（ハッシュ）の証憑が登録済みならその ID
+pub fn find_attachment_by_hash(conn: &Connection, sha256: &str) -> SqlResult<Option<i64>> {
+    conn.query_row("SELECT id FROM attachments WHERE sha256 = ?1", params![sha256], |row| row.get(0))
+        .optional()
+}
+
+pub fn insert_attachment(
+    conn: &Connection,
+    file_name: &str,
+    stored_name: &str,
+    mime_type: &str,
+    size: i64,
+    sha256: &str,
+    received_date: &str,
+    memo: &str,
+) -> SqlResult<i64> {
+    conn.execute(
+        "INSERT INTO attachments (file_name, stored_name, mime_type, size, sha256, received_date, memo)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![file_name, stored_name, mime_type, size, sha256, received_date, memo],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn link_attachment(conn: &Connection, attachment_id: i64, entry_id: i64) -> SqlResult<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO entry_attachments (entry_id, attachment_id) VALUES (?1, ?2)",
+        params![entry_id, attachment_id],
+    )?;
+    Ok(())
+}
+
+pub fn unlink_attachment(conn: &Connection, attachment_id: i64, entry_id: i64) -> SqlResult<usize> {
+    conn.execute(
+        "DELETE FROM entry_attachments WHERE entry_id = ?1 AND attachment_id = ?2",
+        params![entry_id, attachment_id],
+    )
+}
+
+// ── 日付チェック ──
+
+/// YYYY-MM-DD として読み取れない保存済みの日付（取消済みの仕訳も含む）
+pub fn fetch_invalid_dates(conn: &Connection) -> SqlResult<Vec<InvalidDate>> {
+    let mut stmt = conn.prepare(
+        "SELECT '仕訳', id, 'date', date FROM journal_entries WHERE date(date) IS NOT date
+         UNION ALL
+         SELECT '固定資産', id, 'acquisition_date', acquisition_date
+         FROM fixed_assets WHERE date(acquisition_date) IS NOT acquisition_date
+         UNION ALL
+         SELECT '定期仕訳', id, 'start_date', start_date
+         FROM recurring_entries WHERE date(start_date) IS NOT start_date
+         UNION ALL
+         SELECT '定期仕訳', id, 'end_date', end_date
+         FROM recurring_entries WHERE end_date IS NOT NULL AND date(end_date) IS NOT end_date
+         UNION ALL
+         SELECT '証憑', id, 'received_date', received_date
+         FROM attachments WHERE date(received_date) IS NOT received_date",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(InvalidDate {
+            source: row.get(0)?,
+            id: row.get(1)?,
+            field: row.get(2)?,
+            value: row.get(3)?,
+        })
+    })?;
+    rows.collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate(&conn).unwrap();
+        conn
+    }
+
+    fn account_id(conn: &Connection, code: i32) -> i64 {
+        conn.query_row("SELECT id FROM accounts WHERE code = ?1", params![code], |row| row.get(0))
+            .unwrap()
+    }
+
+    fn add(conn: &Connection, date: &str, debit: i64, credit: i64, amount: i64, description: &str) -> i64 {
+        insert_entry(conn, date, debit, amount, credit, amount, description, None, None, None, None).unwrap()
+    }
+
+    fn line(side: &str, account_id: i64, amount: i64) -> JournalLineInput {
+        JournalLineInput { side: side.to_string(), account_id, sub_account_id: None, amount }
+    }
+
+    fn add_payroll(conn: &Connection, salaries: i64, withholding: i64, bank: i64) -> i64 {
+        let lines = [line("借方", salaries, 300_000), line("貸方", withholding, 40_000), line("貸方", bank, 260_000)];
+        insert_compound_entry(conn, "2024-01-25", "給与", &lines, None, None).unwrap()
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        Date::parse(s).unwrap().0
+    }
+
+    fn recurring(frequency: &str, day_of_month: u32, start_date: &str, end_date: Option<&str>) -> RecurringEntry {
+        RecurringEntry {
+            id: 0,
+            name: "家賃".to_string(),
+            debit_account_id: 0,
+            debit_account_name: String::new(),
+            credit_account_id: 0,
+            credit_account_name: String::new(),
+            amount: 80000,
+            description_template: "{month}月分家賃".to_string(),
+            frequency: frequency.to_string(),
+            day_of_month,
+            start_date: start_date.to_string(),
+            end_date: end_date.map(str::to_string),
+            is_active: true,
+            last_due_date: None,
+        }
+    }
+
+    #[test]
+    fn compound_entry_keeps_lines_and_header() {
+        let conn = open();
+        let (bank, withholding, salaries) = (account_id(&conn, 1112), account_id(&conn, 2300), account_id(&conn, 5200));
+        let id = add_payroll(&conn, salaries, withholding, bank);
+
+        let lines: Vec<(i32, String, i64, i64)> = fetch_entry_lines(&conn, id)
+            .unwrap()
+            .into_iter()
+            .map(|l| (l.line_no, l.side, l.account_id, l.amount))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                (1, "借方".to_string(), salaries, 300_000),
+                (2, "貸方".to_string(), withholding, 40_000),
+                (3, "貸方".to_string(), bank, 260_000),
+            ]
+        );
+
+        // 仕訳の debit_*/credit_* 列は先頭行の科目と合計金額を保持する
+        let header: (bool, i64, i64, i64, i64) = conn
+            .query_row(
+                "SELECT is_compound, debit_account_id, debit_amount, credit_account_id, credit_amount
+                 FROM journal_entries WHERE id = ?1",
+                params![id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+            )
+            .unwrap();
+        assert_eq!(header, (true, salaries, 300_000, withholding, 300_000));
+    }
+
+    #[test]
+    fn migration_splits_single_line_entries_into_lines() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(MIGRATION_001).unwrap();
+        let (bank, sales) = (account_id(&conn, 1112), account_id(&conn, 4100));
+        conn.execute(
+            "INSERT INTO journal_entries (date, debit_account_id, debit_amount, credit_account_id, credit_amount, description)
+             VALUES ('2024-02-01', ?1, 500, ?2, 500, '売上')",
+            params![bank, sales],
+        )
+        .unwrap();
+        let id = conn.last_insert_rowid();
+        migrate(&conn).unwrap();
+
+        let lines: Vec<(String, i64, i64)> =
+            fetch_entry_lines(&conn, id).unwrap().into_iter().map(|l| (l.side, l.account_id, l.amount)).collect();
+        assert_eq!(lines, vec![("借方".to_string(), bank, 500), ("貸方".to_string(), sales, 500)]);
+        assert_eq!(calc_trial_balance(&conn, 2024, None, false).unwrap().debit_grand_total, 500);
+    }
+
+    #[test]
+    fn trial_balance_and_balance_sheet_sum_lines() {
+        let conn = open();
+        let (bank, withholding, sales, salaries) =
+            (account_id(&conn, 1112), account_id(&conn, 2300), account_id(&conn, 4100), account_id(&conn, 5200));
+        add(&conn, "2024-01-05", bank, sales, 500_000, "売上");
+        add_payroll(&conn, salaries, withholding, bank);
+
+        let tb = calc_trial_balance(&conn, 2024, None, false).unwrap();
+        assert_eq!((tb.debit_grand_total, tb.credit_grand_total), (800_000, 800_000));
+        let row = |id: i64| tb.rows.iter().find(|r| r.account_id == id).unwrap();
+        assert_eq!((row(bank).debit_total, row(bank).credit_total), (500_000, 260_000));
+        assert_eq!((row(withholding).debit_total, row(withholding).credit_total), (0, 40_000));
+        assert_eq!(row(salaries).debit_total, 300_000);
+
+        let bs = calc_balance_sheet(&conn, 2024).unwrap();
+        assert_eq!((bs.total_assets, bs.total_liabilities, bs.net_income), (240_000, 40_000, 200_000));
+        assert_eq!(bs.total_assets, bs.total_liabilities + bs.total_equity + bs.net_income);
+    }
+
+    #[test]
+    fn close_year_carries_balances_forward() {
+        let conn = open();
+        let (bank, capital, sales, drawings) =
+            (account_id(&conn, 1112), account_id(&conn, 3100), account_id(&conn, 4100), account_id(&conn, 3200));
+        replace_opening_balances(
+            &conn,
+            2023,
+            &[
+                OpeningBalanceInput { account_id: bank, amount: 1_000_000 },
+                OpeningBalanceInput { account_id: capital, amount: 1_000_000 },
+            ],
+        )
+        .unwrap();
+        add(&conn, "2023-03-01", bank, sales, 500_000, "売上");
+        add(&conn, "2023-04-01", drawings, bank, 100_000, "生活費");
+
+        let closing = close_year(&conn, 2023).unwrap();
+        assert_eq!(closing.income, 500_000);
+        assert_eq!(closing.owner_drawings, 100_000);
+        assert_eq!(closing.next_capital, 1_400_000);
+
+        // 事業主貸は元入金に振り替えられ、翌年には残らない
+        let opening = calc_opening_balances(&conn, 2024).unwrap();
+        assert_eq!(opening.get(&bank), Some(&1_400_000));
+        assert_eq!(opening.get(&capital), Some(&1_400_000));
+        assert!(!opening.contains_key(&drawings));
+
+        // 締めていない年度は直近の期首残高から計算して繰り越す
+        add(&conn, "2024-05-01", bank, sales, 200_000, "売上");
+        let opening = calc_opening_balances(&conn, 2025).unwrap();
+        assert_eq!(opening.get(&bank), Some(&1_600_000));
+        assert_eq!(opening.get(&capital), Some(&1_600_000));
+    }
+
+    #[test]
+    fn recurring_due_dates_clamp_to_month_end() {
+        let dates = recurring_due_dates(&recurring("毎月", 31, "2024-01-31", None), date("2024-04-30"));
+        assert_eq!(dates, vec![date("2024-01-31"), date("2024-02-29"), date("2024-03-31"), date("2024-04-30")]);
+
+        let dates = recurring_due_dates(&recurring("隔月", 15, "2024-01-15", Some("2024-06-30")), date("2024-12-31"));
+        assert_eq!(dates, vec![date("2024-01-15"), date("2024-03-15"), date("2024-05-15")]);
+
+        let dates = recurring_due_dates(&recurring("月末", 1, "2023-11-10", None), date("2024-02-28"));
+        assert_eq!(dates, vec![date("2023-11-30"), date("2023-12-31"), date("2024-01-31")]);
+
+        assert!(recurring_due_dates(&recurring("指定日", 1, "2024-07-01", None), date("2024-06-30")).is_empty());
+    }
+
+    #[test]
+    fn pending_recurring_skips_dates_already_run() {
+        let conn = open();
+        let (rent, bank) = (account_id(&conn, 5300), account_id(&conn, 1112));
+        insert_recurring_entry(&conn, "家賃", rent, bank, 80000, "{month}月分家賃", "毎月", 25, "2024-01-01", None)
+            .unwrap();
+        let pending = fetch_pending_recurring(&conn, date("2024-03-31")).unwrap();
+        assert_eq!(pending.len(), 3);
+
+        let (entry, due_date) = &pending[0];
+        let entry_id = run_recurring_entry(&conn, entry, *due_date).unwrap();
+        let description: String = conn
+            .query_row("SELECT description FROM journal_entries WHERE id = ?1", params![entry_id], |row| row.get(0))
+            .unwrap();
+        assert_eq!(description, "1月分家賃");
+
+        let due_dates: Vec<NaiveDate> =
+            fetch_pending_recurring(&conn, date("2024-03-31")).unwrap().into_iter().map(|(_, d)| d.0).collect();
+        assert_eq!(due_dates, vec![date("2024-02-25"), date("2024-03-25")]);
+    }
+
+    #[test]
+    fn duplicate_pairs_within_window() {
+        let conn = open();
+        let (supplies, cash, bank) = (account_id(&conn, 5600), account_id(&conn, 1111), account_id(&conn, 1112));
+        let a = add(&conn, "2024-04-01", supplies, cash, 1500, "文具");
+        let b = add(&conn, "2024-04-01", supplies, cash, 1500, "ノート");
+        let c = add(&conn, "2024-04-05", supplies, bank, 1500, "文具");
+        // 7日を超えて離れた同額・同摘要は対象外
+        add(&conn, "2024-05-20", supplies, cash, 1500, "文具");
+
+        let pairs = find_duplicate_pairs(&conn, date("2024-01-01"), date("2024-12-31"), 7, None).unwrap();
+        let keys: Vec<(i64, i64, &str)> =
+            pairs.iter().map(|p| (p.entry.id, p.duplicate.id, p.reason.as_str())).collect();
+        assert_eq!(keys, vec![(a, b, "同日・同額・同科目"), (a, c, "同額・同摘要")]);
+        assert_eq!(pairs[1].days_apart, 4);
+    }
+
+    #[test]
+    fn convert_to_yen_truncates_toward_zero() {
+        // 100.00 ドル × 150.0000 円
+        assert_eq!(convert_to_yen(10000, 1_500_000), 15000);
+        // 1,234.56 ドル × 150.1234 円 = 185,336.344704 円
+        assert_eq!(convert_to_yen(123456, 1_501_234), 185336);
+        assert_eq!(convert_to_yen(-123456, 1_501_234), -185336);
+        assert_eq!(convert_to_yen(0, 1_501_234), 0);
+    }
+
+    #[test]
+    fn aging_report_buckets_remaining_amounts() {
+        let conn = open();
+        let (bank, receivable, sales) = (account_id(&conn, 1112), account_id(&conn, 1131), account_id(&conn, 4100));
+        let sale = add(&conn, "2024-01-31", receivable, sales, 100000, "1月分");
+        let paid = add(&conn, "2024-02-28", bank, receivable, 60000, "入金");
+        add(&conn, "2023-09-30", receivable, sales, 20000, "9月分");
+        add(&conn, "2024-03-15", bank, receivable, 5000, "入金");
+        // 基準日より後の計上は含めない
+        add(&conn, "2024-04-10", receivable, sales, 30000, "4月分");
+        insert_item_match(&conn, receivable, sale, paid, 60000).unwrap();
+
+        let report = calc_aging_report(&conn, date("2024-03-31"), None).unwrap();
+        let items: Vec<(String, i64, i64)> =
+            report.items.iter().map(|i| (i.date.clone(), i.remaining, i.age_days)).collect();
+        assert_eq!(
+            items,
+            vec![("2023-09-30".to_string(), 20000, 183), ("2024-01-31".to_string(), 40000, 60)]
+        );
+        assert_eq!(report.total, 60000);
+        let amounts: Vec<i64> = report.buckets.iter().map(|b| b.amount).collect();
+        assert_eq!(amounts, vec![0, 40000, 0, 0, 20000]);
+        assert_eq!(report.unapplied_credits.len(), 1);
+        assert_eq!(report.unapplied_credits[0].remaining, 5000);
+    }
+}
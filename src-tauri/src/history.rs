@@ -0,0 +1,85 @@
+use crate::models::*;
+
+/// 元に戻せる操作の最大数（超えた分は古いものから捨てる）
+const MAX_STEPS: usize = 100;
+
+/// 元に戻せる個々の変更
+/// Add* / Delete* は対象の削除と復元で切り替え、Update* は保持している内容と
+/// 現在の内容を入れ替える（元に戻す・やり直すたびに保持する内容が入れ替わる）
+#[derive(Debug, Clone)]
+pub enum Operation {
+    AddEntry(i64),
+    DeleteEntry(i64),
+    UpdateEntry { id: i64, snapshot: EntrySnapshot },
+    AddAccount(Account),
+    UpdateAccount(Account),
+    DeleteAccount(Account),
+    AddFixedAsset(FixedAsset),
+    DeleteFixedAsset(FixedAsset),
+    AddRentDetail(RentDetail),
+    DeleteRentDetail(RentDetail),
+    AddLossCarryforward(LossCarryforward),
+    UpdateLossCarryforward(LossCarryforward),
+    DeleteLossCarryforward(LossCarryforward),
+    UpdateEntryReview { id: i64, review_status: String, note: String },
+    AddItemMatch(ItemMatch),
+    DeleteItemMatch(ItemMatch),
+}
+
+/// 1回の操作（一括処理は複数の変更をまとめて1回として扱う）
+#[derive(Debug, Clone)]
+pub struct UndoStep {
+    pub description: String,
+    pub operations: Vec<Operation>,
+}
+
+/// アプリの起動中だけ保持する元に戻す・やり直しの履歴
+#[derive(Debug, Default)]
+pub struct UndoHistory {
+    undo: Vec<UndoStep>,
+    redo: Vec<UndoStep>,
+}
+
+impl UndoHistory {
+    /// 新しい操作を記録する（やり直しの履歴は破棄する）
+    pub fn record(&mut self, description: impl Into<String>, operations: Vec<Operation>) {
+        if operations.is_empty() {
+            return;
+        }
+        self.undo.push(UndoStep { description: description.into(), operations });
+        if self.undo.len() > MAX_STEPS {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    /// 元に戻せない操作（科目の統合など）の前の履歴をすべて捨てる
+    /// それより前の操作を元に戻すと、統合後のデータと食い違うため
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    pub fn pop_undo(&mut self) -> Option<UndoStep> {
+        self.undo.pop()
+    }
+
+    pub fn pop_redo(&mut self) -> Option<UndoStep> {
+        self.redo.pop()
+    }
+
+    /// やり直した操作、または元に戻せなかった操作を戻す
+    pub fn push_undo(&mut self, step: UndoStep) {
+        self.undo.push(step);
+    }
+
+    pub fn push_redo(&mut self, step: UndoStep) {
+        self.redo.push(step);
+    }
+
+    /// 元に戻す・やり直しの対象の説明（それぞれ次に実行されるものから順に）
+    pub fn status(&self) -> UndoStatus {
+        let descriptions = |steps: &[UndoStep]| steps.iter().rev().map(|s| s.description.clone()).collect();
+        UndoStatus { undo: descriptions(&self.undo), redo: descriptions(&self.redo) }
+    }
+}
@@ -3,11 +3,14 @@
     windows_subsystem = "windows"
 )]
 
+mod attachments;
 mod commands;
 mod db;
+mod history;
 mod models;
 
-use commands::DbState;
+use commands::{DbState, HistoryState};
+use history::UndoHistory;
 use std::sync::Mutex;
 use tauri::Manager;
 
@@ -17,31 +20,111 @@ fn main() {
             let db_path = db::get_db_path(&app.handle());
             let conn = db::init_db(&db_path).expect("failed to initialize database");
             app.manage(DbState(Mutex::new(conn)));
+            app.manage(HistoryState(Mutex::new(UndoHistory::default())));
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             commands::get_accounts,
             commands::add_account,
+            commands::update_account,
+            commands::delete_account,
+            commands::merge_accounts,
+            commands::get_sub_accounts,
+            commands::add_sub_account,
+            commands::update_sub_account,
+            commands::delete_sub_account,
+            commands::get_partners,
+            commands::add_partner,
+            commands::update_partner,
+            commands::delete_partner,
+            commands::get_segments,
+            commands::add_segment,
+            commands::update_segment,
+            commands::delete_segment,
             commands::add_entry,
+            commands::add_compound_entry,
             commands::get_entries,
+            commands::get_entries_page,
+            commands::count_entries,
+            commands::suggest_accounts,
+            commands::search_entries,
+            commands::get_entry_lines,
             commands::update_entry,
+            commands::update_compound_entry,
             commands::delete_entry,
+            commands::add_entries_batch,
+            commands::update_entries_batch,
+            commands::delete_entries_batch,
+            commands::find_duplicate_entries,
+            commands::dismiss_duplicate,
+            commands::undismiss_duplicate,
+            commands::update_entry_review,
+            commands::get_open_items,
+            commands::get_voucher_number_report,
+            commands::reverse_entry,
+            commands::get_reversals,
+            commands::get_entry_revisions,
+            commands::get_revisions,
+            commands::undo,
+            commands::redo,
+            commands::get_undo_status,
             commands::get_trial_balance,
+            commands::get_ledger,
             commands::get_profit_loss,
+            commands::get_segment_profit_loss,
             commands::get_balance_sheet,
+            commands::get_opening_balances,
+            commands::set_opening_balances,
+            commands::get_year_closings,
+            commands::close_year,
+            commands::reopen_year,
+            commands::get_period_locks,
+            commands::lock_period,
+            commands::unlock_period,
             commands::get_fixed_assets,
             commands::add_fixed_asset,
             commands::delete_fixed_asset,
             commands::get_rent_details,
             commands::add_rent_detail,
             commands::delete_rent_detail,
+            commands::get_apportionment_rules,
+            commands::save_apportionment_rule,
+            commands::delete_apportionment_rule,
+            commands::preview_apportionment,
+            commands::post_apportionment,
+            commands::get_exchange_rates,
+            commands::get_exchange_rate,
+            commands::import_exchange_rates,
+            commands::add_foreign_entry,
+            commands::get_foreign_currency_report,
+            commands::match_items,
+            commands::unmatch_items,
+            commands::get_item_matches,
+            commands::get_aging_report,
             commands::get_loss_carryforwards,
             commands::add_loss_carryforward,
             commands::update_loss_carryforward,
             commands::delete_loss_carryforward,
             commands::get_loss_carryforward_summary,
             commands::get_final_statement,
+            commands::get_journal_templates,
+            commands::add_journal_template,
+            commands::update_journal_template,
+            commands::delete_journal_template,
+            commands::add_entry_from_template,
+            commands::get_recurring_entries,
+            commands::add_recurring_entry,
+            commands::update_recurring_entry,
+            commands::delete_recurring_entry,
+            commands::generate_recurring_entries,
             commands::export_journal_csv,
+            commands::get_attachments,
+            commands::attach_file,
+            commands::link_attachment,
+            commands::unlink_attachment,
+            commands::open_attachment,
+            commands::verify_attachments,
+            commands::get_invalid_dates,
             commands::backup_database,
         ])
         .run(tauri::generate_context!())
@@ -1,7 +1,121 @@
-use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Datelike, NaiveDate};
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+// ── 日付 ──
+
+/// 暦の上で存在する日付（入出力・保存の形式は YYYY-MM-DD）
+/// 2024-02-30 や 2024/3/1 のような値はコマンドの引数を受け取る時点でエラーにする
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date(pub NaiveDate);
+
+impl Date {
+    pub const FORMAT: &'static str = "%Y-%m-%d";
+
+    pub fn parse(s: &str) -> Result<Date, String> {
+        let invalid = || format!("日付の形式が正しくありません（YYYY-MM-DD）: {}", s);
+        // chrono は月・日の1桁表記も受け付けるため、桁数もそろっているか確認する
+        if s.len() != 10 {
+            return Err(invalid());
+        }
+        NaiveDate::parse_from_str(s, Self::FORMAT).map(Date).map_err(|_| invalid())
+    }
+
+    pub fn year(self) -> i32 {
+        self.0.year()
+    }
+
+    pub fn month(self) -> i32 {
+        self.0.month() as i32
+    }
+
+    /// year 年 month 月の末日（存在しない年月なら None）
+    pub fn month_end(year: i32, month: i32) -> Option<Date> {
+        if !(1..=12).contains(&month) {
+            return None;
+        }
+        let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+        NaiveDate::from_ymd_opt(next_year, next_month as u32, 1)
+            .and_then(|d| d.pred_opt())
+            .map(Date)
+    }
+
+    /// year 年 month 月（None なら年間）の初日と末日（存在しない年月なら None）
+    pub fn period(year: i32, month: Option<i32>) -> Option<(Date, Date)> {
+        let (first_month, last_month) = month.map_or((1, 12), |m| (m, m));
+        let first = NaiveDate::from_ymd_opt(year, u32::try_from(first_month).ok()?, 1)?;
+        Some((Date(first), Date::month_end(year, last_month)?))
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.format(Self::FORMAT))
+    }
+}
+
+impl FromStr for Date {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Date, String> {
+        Date::parse(s)
+    }
+}
+
+impl Serialize for Date {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Date {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Date, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Date::parse(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+impl ToSql for Date {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.to_string()))
+    }
+}
+
+impl FromSql for Date {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Date> {
+        Date::parse(value.as_str()?).map_err(|e| FromSqlError::Other(e.into()))
+    }
+}
+
+/// 保存されている日付のうち YYYY-MM-DD として読めないもの（マイグレーションで直せなかった値）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvalidDate {
+    /// 対象（"仕訳" / "固定資産" / "定期仕訳" / "証憑"）
+    pub source: String,
+    pub id: i64,
+    pub field: String,
+    pub value: String,
+}
+
+// ── 期間ロック ──
+
+/// 期間ロック（month が None なら年度全体、unlocked_at があれば解除済み）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeriodLock {
+    pub id: i64,
+    pub year: i32,
+    pub month: Option<i32>,
+    pub locked_at: String,
+    pub unlocked_at: Option<String>,
+}
 
 // ── 固定資産 ──
 
+/// acquisition_date は String のまま読む。マイグレーションで直せなかった日付が残っていても
+/// 一覧を表示して訂正できるようにするため（該当する行は get_invalid_dates で確認できる）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FixedAsset {
     pub id: i64,
@@ -20,7 +134,7 @@ pub struct FixedAsset {
 pub struct DepreciationRow {
     pub asset_id: i64,
     pub asset_name: String,
-    pub acquisition_date: String,
+    pub acquisition_date: Date,
     pub acquisition_cost: i64,
     pub depreciation_method: String,
     pub useful_life: i32,
@@ -31,6 +145,44 @@ pub struct DepreciationRow {
     pub book_value_end: i64,
 }
 
+// ── 補助科目 ──
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubAccount {
+    pub id: i64,
+    pub account_id: i64,
+    pub account_code: i32,
+    pub account_name: String,
+    pub name: String,
+    pub is_active: bool,
+}
+
+// ── 取引先 ──
+
+/// 取引先（partner_type は "得意先" / "仕入先" / "両方"）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Partner {
+    pub id: i64,
+    pub name: String,
+    pub kana: String,
+    pub address: String,
+    pub invoice_number: String,
+    pub partner_type: String,
+    pub memo: String,
+}
+
+// ── セグメント ──
+
+/// 部門・プロジェクトなどのセグメント（segment_type は "部門" / "プロジェクト" / "タグ"）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Segment {
+    pub id: i64,
+    pub name: String,
+    pub segment_type: String,
+    pub memo: String,
+    pub is_active: bool,
+}
+
 // ── 地代家賃内訳 ──
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,6 +195,145 @@ pub struct RentDetail {
     pub annual_total: i64,
     pub business_ratio: i32,
     pub memo: String,
+    pub partner_id: Option<i64>,
+    /// 内訳を記載する決算書の年度
+    pub year: i32,
+}
+
+// ── 家事按分 ──
+
+/// 科目ごとの家事按分のルール（business_ratio は事業割合の %）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApportionmentRule {
+    pub id: i64,
+    pub account_id: i64,
+    pub account_code: i32,
+    pub account_name: String,
+    pub business_ratio: i32,
+    pub basis: String,
+}
+
+/// 家事按分の計算結果（科目ごと）
+/// expense_total は按分の振替仕訳を除いた年間の費用、posted_amount は作成済みの振替額
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApportionmentLine {
+    pub account_id: i64,
+    pub account_code: i32,
+    pub account_name: String,
+    pub business_ratio: i32,
+    pub basis: String,
+    pub expense_total: i64,
+    pub private_amount: i64,
+    pub posted_amount: i64,
+}
+
+// ── 外貨建取引 ──
+
+/// 為替レート（TTM、1通貨単位あたりの円 × 10000）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExchangeRate {
+    pub currency: String,
+    pub date: String,
+    pub ttm: i64,
+}
+
+/// 外貨建取引の通貨・外貨額（× 100）・換算レート（× 10000）
+/// foreign_side は外貨建ての科目がある側（"借方" / "貸方" / "両方"）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForeignAmount {
+    pub currency: String,
+    pub foreign_amount: i64,
+    pub exchange_rate: i64,
+    pub foreign_side: String,
+}
+
+/// 為替レートの取り込み結果（errors が1件でもあれば何も取り込まず imported は0）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExchangeRateImportResult {
+    pub imported: usize,
+    pub errors: Vec<EntryBatchError>,
+}
+
+/// 外貨建ての債権・預金などの年末残高（科目・通貨ごと）
+/// book_amount は外貨建取引の円換算額の合計、year_end_amount は年末の TTM で換算した額
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForeignCurrencyPosition {
+    pub account_id: i64,
+    pub account_code: i32,
+    pub account_name: String,
+    pub classification: String,
+    pub currency: String,
+    /// 外貨残高 × 100
+    pub foreign_balance: i64,
+    pub book_amount: i64,
+    /// 年末（12月31日以前で直近）の TTM × 10000。レートが未登録なら None
+    pub year_end_rate: Option<i64>,
+    pub year_end_amount: Option<i64>,
+    /// 換算差額（year_end_amount − book_amount。資産は正なら差益、負債は正なら差損）
+    pub difference: Option<i64>,
+}
+
+/// 外貨建取引の年末レポート（exchange_gain / exchange_loss は計上済みの為替差益・為替差損）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForeignCurrencyReport {
+    pub year: i32,
+    pub positions: Vec<ForeignCurrencyPosition>,
+    pub exchange_gain: i64,
+    pub exchange_loss: i64,
+}
+
+// ── 消込 ──
+
+/// 借方の計上と貸方の入金・精算の消込
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemMatch {
+    pub id: i64,
+    pub account_id: i64,
+    pub debit_entry_id: i64,
+    pub debit_date: String,
+    pub debit_description: String,
+    pub credit_entry_id: i64,
+    pub credit_date: String,
+    pub credit_description: String,
+    pub amount: i64,
+}
+
+/// 消し込まれていない金額が残る仕訳（仕訳・科目ごと）
+/// side が借方なら未回収・未精算の残高、貸方なら消込先が決まっていない入金・精算
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnmatchedItem {
+    pub entry_id: i64,
+    pub voucher_no: Option<i64>,
+    pub date: String,
+    pub description: String,
+    pub partner_name: Option<String>,
+    pub account_id: i64,
+    pub account_code: i32,
+    pub account_name: String,
+    pub side: String,
+    pub amount: i64,
+    pub matched_amount: i64,
+    pub remaining: i64,
+    /// 基準日時点の経過日数
+    pub age_days: i64,
+}
+
+/// 経過日数の区分ごとの未消込残高
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgingBucket {
+    pub label: String,
+    pub count: i64,
+    pub amount: i64,
+}
+
+/// 基準日時点の未消込一覧（年齢表）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgingReport {
+    pub as_of: String,
+    pub items: Vec<UnmatchedItem>,
+    pub unapplied_credits: Vec<UnmatchedItem>,
+    pub buckets: Vec<AgingBucket>,
+    pub total: i64,
 }
 
 // ── 純損失の繰越控除 ──
@@ -100,6 +391,8 @@ pub struct FinalStatement {
     pub rent_total: i64,
     pub balance_sheet: BalanceSheet,
     pub loss_carryforward: LossCarryforwardSummary,
+    /// 年度内の確認待ち（未確認・要質問）の仕訳の件数
+    pub open_item_count: i64,
 }
 
 // ── 勘定科目 ──
@@ -110,8 +403,14 @@ pub struct Account {
     pub code: i32,
     pub name: String,
     pub classification: String,
+    /// false = 非表示（入力時の選択肢に出さない）
+    pub is_active: bool,
+    /// true = 消込の対象科目（売掛金・立替金・仮払金）
+    pub is_matching: bool,
 }
 
+/// date は String のまま読む。形式の不正な日付が残っている仕訳があっても
+/// 仕訳帳の表示全体を失敗させないため（該当する行は get_invalid_dates で確認できる）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JournalEntry {
     pub id: i64,
@@ -124,6 +423,165 @@ pub struct JournalEntry {
     pub credit_amount: i64,
     pub description: String,
     pub created_at: String,
+    pub is_compound: bool,
+    pub reversal_of: Option<i64>,
+    pub reversed_by: Option<i64>,
+    pub voucher_no: Option<i64>,
+    pub partner_id: Option<i64>,
+    pub partner_name: Option<String>,
+    pub segment_id: Option<i64>,
+    pub segment_name: Option<String>,
+    /// 確認状況（"未確認" / "確認済" / "要質問"）
+    pub review_status: String,
+    /// 摘要とは別のメモ（仕訳帳・仕訳帳の CSV にも出力する）
+    pub note: String,
+    /// 外貨建取引の通貨・外貨額・換算レート（円建ての仕訳は None）
+    pub currency: Option<String>,
+    /// 外貨額 × 100
+    pub foreign_amount: Option<i64>,
+    /// 換算レート × 10000
+    pub exchange_rate: Option<i64>,
+    /// 外貨建ての科目がある側（"借方" / "貸方" / "両方"）
+    pub foreign_side: Option<String>,
+}
+
+/// 仕訳検索の条件（指定しない条件では絞り込まない）
+/// 金額は取引金額（複合仕訳は借方合計）で比較する
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EntrySearchQuery {
+    pub keyword: String,
+    pub date_from: Option<Date>,
+    pub date_to: Option<Date>,
+    pub amount_min: Option<i64>,
+    pub amount_max: Option<i64>,
+    pub account_id: Option<i64>,
+    pub partner_id: Option<i64>,
+    pub segment_id: Option<i64>,
+    /// 並び替え（"date" / "amount" / "account_code" / "created_at" / "voucher_no"、既定は日付順）
+    pub sort: Option<String>,
+    pub descending: bool,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// 仕訳検索の結果（entries は指定ページ分、total_count は条件に一致する全件数）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntrySearchResult {
+    pub entries: Vec<JournalEntry>,
+    pub total_count: i64,
+}
+
+/// 月ごとの確認待ち（未確認・要質問）の仕訳
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenItemMonth {
+    pub month: i32,
+    pub unconfirmed_count: i64,
+    pub question_count: i64,
+    pub entries: Vec<JournalEntry>,
+}
+
+/// 重複の疑いがある仕訳の組（entry は登録の古い方）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicatePair {
+    pub entry: JournalEntry,
+    pub duplicate: JournalEntry,
+    /// "同日・同額・同科目" または "同額・同摘要"
+    pub reason: String,
+    pub days_apart: i64,
+    /// 「重複ではない」と確認済み
+    pub dismissed: bool,
+}
+
+/// 仕訳の登録結果（duplicates は登録した仕訳と重複の疑いがある仕訳。登録自体は行う）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntryAddResult {
+    pub id: i64,
+    pub duplicates: Vec<DuplicatePair>,
+}
+
+/// 過去の仕訳から推定した科目・取引先の候補（score の高い順に並べる）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountSuggestion {
+    pub debit_account_id: i64,
+    pub debit_account_name: String,
+    pub credit_account_id: i64,
+    pub credit_account_name: String,
+    pub partner_id: Option<i64>,
+    pub partner_name: Option<String>,
+    pub score: f64,
+    /// 候補の根拠になった過去の仕訳の件数
+    pub match_count: i64,
+    pub last_used: String,
+}
+
+/// 一括登録する単一行仕訳の1行
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntryBatchRow {
+    pub date: Date,
+    pub debit_account_id: i64,
+    pub debit_amount: i64,
+    pub credit_account_id: i64,
+    pub credit_amount: i64,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub partner_id: Option<i64>,
+    #[serde(default)]
+    pub debit_sub_account_id: Option<i64>,
+    #[serde(default)]
+    pub credit_sub_account_id: Option<i64>,
+    #[serde(default)]
+    pub segment_id: Option<i64>,
+}
+
+/// 一括訂正する仕訳の1行（訂正後の内容）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntryBatchUpdate {
+    pub id: i64,
+    #[serde(flatten)]
+    pub row: EntryBatchRow,
+}
+
+/// 一括処理で検証エラーになった行（row は1始まりの行番号）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntryBatchError {
+    pub row: usize,
+    pub message: String,
+}
+
+/// 一括処理の結果（errors が1件でもあれば何も反映せず ids は空）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntryBatchResult {
+    pub ids: Vec<i64>,
+    pub errors: Vec<EntryBatchError>,
+    /// 一括登録した仕訳と重複の疑いがある仕訳の組（訂正・削除では常に空）
+    pub duplicates: Vec<DuplicatePair>,
+}
+
+/// 仕訳明細（複合仕訳の1行）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalLine {
+    pub id: i64,
+    pub entry_id: i64,
+    pub line_no: i32,
+    pub side: String,
+    pub account_id: i64,
+    pub account_code: i32,
+    pub account_name: String,
+    pub amount: i64,
+    pub sub_account_id: Option<i64>,
+    pub sub_account_name: Option<String>,
+}
+
+/// 複合仕訳の入力行（side は "借方" / "貸方"）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalLineInput {
+    pub side: String,
+    pub account_id: i64,
+    #[serde(default)]
+    pub sub_account_id: Option<i64>,
+    pub amount: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -135,6 +593,8 @@ pub struct TrialBalanceRow {
     pub debit_total: i64,
     pub credit_total: i64,
     pub balance: i64,
+    pub sub_account_id: Option<i64>,
+    pub sub_account_name: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -144,6 +604,35 @@ pub struct TrialBalance {
     pub credit_grand_total: i64,
 }
 
+/// 総勘定元帳の1行（reversal_of があれば赤黒訂正の取消仕訳）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerRow {
+    pub entry_id: i64,
+    pub date: String,
+    pub description: String,
+    pub counter_account_name: String,
+    pub debit_amount: i64,
+    pub credit_amount: i64,
+    pub balance: i64,
+    pub reversal_of: Option<i64>,
+    pub partner_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ledger {
+    pub account_id: i64,
+    pub partner_id: Option<i64>,
+    pub sub_account_id: Option<i64>,
+    pub account_code: i32,
+    pub account_name: String,
+    pub classification: String,
+    pub opening_balance: i64,
+    pub rows: Vec<LedgerRow>,
+    pub debit_total: i64,
+    pub credit_total: i64,
+    pub closing_balance: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProfitLossRow {
     pub account_id: i64,
@@ -161,11 +650,23 @@ pub struct ProfitLoss {
     pub net_income: i64,
 }
 
+/// セグメント別の損益計算書（segment_id が None の行は未割当）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SegmentProfitLoss {
+    pub segment_id: Option<i64>,
+    pub segment_name: String,
+    pub segment_type: Option<String>,
+    pub profit_loss: ProfitLoss,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BalanceSheetRow {
     pub account_id: i64,
     pub account_code: i32,
     pub account_name: String,
+    /// 期首残高
+    pub opening_amount: i64,
+    /// 期末残高
     pub amount: i64,
 }
 
@@ -177,5 +678,232 @@ pub struct BalanceSheet {
     pub total_assets: i64,
     pub total_liabilities: i64,
     pub total_equity: i64,
+    pub total_assets_opening: i64,
+    pub total_liabilities_opening: i64,
+    pub total_equity_opening: i64,
     pub net_income: i64,
 }
+
+// ── 期首残高 ──
+
+/// 期首残高（source は "手入力" または前年からの "繰越"）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpeningBalance {
+    pub account_id: i64,
+    pub account_code: i32,
+    pub account_name: String,
+    pub classification: String,
+    pub amount: i64,
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpeningBalanceInput {
+    pub account_id: i64,
+    pub amount: i64,
+}
+
+// ── 年度締め ──
+
+/// 年度締めの結果（翌期首元入金の計算内訳）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct YearClosing {
+    pub year: i32,
+    pub prior_capital: i64,
+    pub income: i64,
+    pub owner_borrowings: i64,
+    pub owner_drawings: i64,
+    pub next_capital: i64,
+    pub closed_at: String,
+}
+
+// ── 訂正・削除履歴 ──
+
+/// 履歴に記録する仕訳の内容（JSON で保存）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntrySnapshot {
+    pub date: String,
+    pub description: String,
+    pub is_compound: bool,
+    #[serde(default)]
+    pub partner_id: Option<i64>,
+    #[serde(default)]
+    pub segment_id: Option<i64>,
+    pub lines: Vec<JournalLineInput>,
+    /// 外貨建取引の場合のみ
+    #[serde(default)]
+    pub foreign: Option<ForeignAmount>,
+}
+
+/// 仕訳の訂正・削除履歴（action は "登録" / "訂正" / "取消" / "復元"）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntryRevision {
+    pub id: i64,
+    pub entry_id: i64,
+    pub revision_no: i32,
+    pub action: String,
+    pub before: Option<EntrySnapshot>,
+    pub after: Option<EntrySnapshot>,
+    pub changed_by: String,
+    pub changed_at: String,
+}
+
+// ── 元に戻す・やり直し ──
+
+/// 元に戻す・やり直しの対象の説明（それぞれ次に実行されるものが先頭）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UndoStatus {
+    pub undo: Vec<String>,
+    pub redo: Vec<String>,
+}
+
+// ── 伝票番号 ──
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoucherDuplicate {
+    pub voucher_no: i64,
+    pub entry_ids: Vec<i64>,
+}
+
+/// 伝票番号の連番チェック結果（gaps: 欠番, duplicates: 重複, unnumbered: 未採番の仕訳ID）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoucherNumberReport {
+    pub year: i32,
+    pub entry_count: i64,
+    pub voided_count: i64,
+    pub max_voucher_no: i64,
+    pub gaps: Vec<i64>,
+    pub duplicates: Vec<VoucherDuplicate>,
+    pub unnumbered: Vec<i64>,
+}
+
+// ── 赤黒訂正 ──
+
+/// 赤黒訂正された仕訳と取消仕訳の組
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntryReversal {
+    pub entry_id: i64,
+    pub entry_date: String,
+    pub description: String,
+    pub amount: i64,
+    pub reversal_id: i64,
+    pub reversal_date: String,
+}
+
+// ── 仕訳テンプレート ──
+
+/// かんたん入力の仕訳テンプレート（category は "monthly" / "common" / "income"）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalTemplate {
+    pub id: i64,
+    pub label: String,
+    pub category: String,
+    pub help_text: String,
+    pub debit_account_id: i64,
+    pub debit_account_name: String,
+    pub credit_account_id: i64,
+    pub credit_account_name: String,
+    pub default_description: String,
+    pub description_placeholder: String,
+    pub default_amount: Option<i64>,
+    pub tax_category: String,
+    pub sort_order: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalTemplateInput {
+    pub label: String,
+    pub category: String,
+    #[serde(default)]
+    pub help_text: String,
+    pub debit_account_id: i64,
+    pub credit_account_id: i64,
+    #[serde(default)]
+    pub default_description: String,
+    #[serde(default)]
+    pub description_placeholder: String,
+    pub default_amount: Option<i64>,
+    #[serde(default)]
+    pub tax_category: String,
+}
+
+// ── 定期仕訳 ──
+
+/// 定期仕訳の定義（frequency は "毎月" / "隔月" / "毎年" / "指定日" / "月末"）
+/// start_date・end_date は String のまま読む。形式の不正な日付が残っていても一覧を表示して訂正できるようにするため
+/// （該当する行は get_invalid_dates で確認でき、その定義は計上しない）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecurringEntry {
+    pub id: i64,
+    pub name: String,
+    pub debit_account_id: i64,
+    pub debit_account_name: String,
+    pub credit_account_id: i64,
+    pub credit_account_name: String,
+    pub amount: i64,
+    pub description_template: String,
+    pub frequency: String,
+    pub day_of_month: u32,
+    pub start_date: String,
+    pub end_date: Option<String>,
+    pub is_active: bool,
+    pub last_due_date: Option<Date>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecurringRun {
+    pub recurring_id: i64,
+    pub name: String,
+    pub due_date: Date,
+    pub entry_id: i64,
+}
+
+/// 締め済みの期間などで計上できなかった定期仕訳
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecurringSkip {
+    pub recurring_id: i64,
+    pub name: String,
+    pub due_date: Date,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecurringRunResult {
+    pub created: Vec<RecurringRun>,
+    pub skipped: Vec<RecurringSkip>,
+}
+
+// ── 証憑 ──
+
+/// received_date は String のまま読む。形式の不正な日付が残っていても証憑の一覧を表示できるようにするため
+/// （該当する行は get_invalid_dates で確認できる）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Attachment {
+    pub id: i64,
+    pub file_name: String,
+    pub stored_name: String,
+    pub mime_type: String,
+    pub size: i64,
+    pub sha256: String,
+    pub received_date: String,
+    pub memo: String,
+    pub created_at: String,
+    /// 紐付けている仕訳の ID
+    pub entry_ids: Vec<i64>,
+}
+
+/// 証憑の登録結果（reused = true なら同じ内容の登録済みの証憑を紐付けた。受領日・メモは登録済みのもの）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachFileResult {
+    pub attachment: Attachment,
+    pub reused: bool,
+}
+
+/// 保存済みの証憑ファイルの検証結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachmentCheck {
+    pub id: i64,
+    pub file_name: String,
+    /// "正常" / "不一致"（登録後に内容が変わっている） / "ファイルなし"
+    pub status: String,
+}
@@ -1,5 +1,5 @@
 import { useState, useEffect, useCallback } from "react";
-import type { PageId, Account, JournalEntry } from "./types";
+import type { PageId, Account, JournalEntry, EntrySortKey, UndoStatus } from "./types";
 import { currentYear } from "./lib/format";
 import * as api from "./lib/api";
 
@@ -7,6 +7,13 @@ import Layout from "./components/Layout";
 import JournalEntryForm from "./components/JournalEntryForm";
 import JournalEntryList from "./components/JournalEntryList";
 import SimpleEntryForm from "./components/SimpleEntryForm";
+import EntrySearch from "./components/EntrySearch";
+import DuplicateEntries from "./components/DuplicateEntries";
+import OpenItems from "./components/OpenItems";
+import Apportionment from "./components/Apportionment";
+import ForeignCurrency from "./components/ForeignCurrency";
+import ItemMatching from "./components/ItemMatching";
+import AttachmentList from "./components/AttachmentList";
 import AccountList from "./components/AccountList";
 import TrialBalance from "./components/TrialBalance";
 import ProfitLoss from "./components/ProfitLoss";
@@ -16,6 +23,8 @@ import RentDetailList from "./components/RentDetailList";
 import LossCarryforwardList from "./components/LossCarryforwardList";
 import FinalStatement from "./components/FinalStatement";
 
+const ENTRY_PAGE_SIZE = 100;
+
 export default function App() {
   const [page, setPage] = useState<PageId>("journal");
   const [year, setYear] = useState(currentYear());
@@ -25,7 +34,15 @@ export default function App() {
 
   // 仕訳帳
   const [entries, setEntries] = useState<JournalEntry[]>([]);
+  const [entryCount, setEntryCount] = useState(0);
   const [month, setMonth] = useState<number | null>(null);
+  const [sort, setSort] = useState<EntrySortKey>("date");
+  const [descending, setDescending] = useState(false);
+  const [offset, setOffset] = useState(0);
+
+  // 元に戻す・やり直し（取り消した後は表示中の画面を読み込み直す）
+  const [undoStatus, setUndoStatus] = useState<UndoStatus | null>(null);
+  const [pageKey, setPageKey] = useState(0);
 
   const loadAccounts = useCallback(() => {
     api.getAccounts().then(setAccounts).catch(console.error);
@@ -33,10 +50,25 @@ export default function App() {
 
   const loadEntries = useCallback(() => {
     api
-      .getEntries(year, month ?? undefined)
-      .then(setEntries)
+      .getEntriesPage({
+        year,
+        month: month ?? undefined,
+        sort,
+        descending,
+        limit: ENTRY_PAGE_SIZE,
+        offset,
+      })
+      .then((page) => {
+        setEntries(page.entries);
+        setEntryCount(page.total_count);
+      })
       .catch(console.error);
-  }, [year, month]);
+  }, [year, month, sort, descending, offset]);
+
+  // 期間・並び順を変えたら先頭ページに戻る
+  useEffect(() => {
+    setOffset(0);
+  }, [year, month, sort, descending]);
 
   useEffect(() => {
     loadAccounts();
@@ -46,6 +78,27 @@ export default function App() {
     loadEntries();
   }, [loadEntries]);
 
+  const loadUndoStatus = useCallback(() => {
+    api.getUndoStatus().then(setUndoStatus).catch(console.error);
+  }, []);
+
+  async function handleUndoRedo(undo: boolean) {
+    try {
+      const done = undo ? await api.undo() : await api.redo();
+      if (done.length === 0) {
+        alert(undo ? "元に戻せる操作はありません" : "やり直せる操作はありません");
+        return;
+      }
+      loadAccounts();
+      loadEntries();
+      setPageKey((k) => k + 1);
+    } catch (err) {
+      alert(String(err));
+    } finally {
+      loadUndoStatus();
+    }
+  }
+
   async function handleBackup() {
     try {
       const path = await api.backupDatabase();
@@ -79,6 +132,16 @@ export default function App() {
             <JournalEntryForm accounts={accounts} onSaved={loadEntries} />
             <JournalEntryList
               entries={entries}
+              totalCount={entryCount}
+              offset={offset}
+              pageSize={ENTRY_PAGE_SIZE}
+              onOffsetChange={setOffset}
+              sort={sort}
+              descending={descending}
+              onSortChange={(key, desc) => {
+                setSort(key);
+                setDescending(desc);
+              }}
               year={year}
               month={month}
               onMonthChange={setMonth}
@@ -89,8 +152,16 @@ export default function App() {
         );
       case "simple-entry":
         return (
-          <SimpleEntryForm accounts={accounts} onSaved={loadEntries} />
+          <SimpleEntryForm onSaved={loadEntries} />
         );
+      case "entry-search":
+        return <EntrySearch accounts={accounts} />;
+      case "duplicates":
+        return <DuplicateEntries year={year} />;
+      case "open-items":
+        return <OpenItems year={year} />;
+      case "attachments":
+        return <AttachmentList />;
       case "accounts":
         return <AccountList accounts={accounts} onAdded={loadAccounts} />;
       case "trial-balance":
@@ -102,7 +173,13 @@ export default function App() {
       case "fixed-assets":
         return <FixedAssetList />;
       case "rent-details":
-        return <RentDetailList />;
+        return <RentDetailList year={year} />;
+      case "apportionment":
+        return <Apportionment accounts={accounts} year={year} />;
+      case "foreign-currency":
+        return <ForeignCurrency accounts={accounts} year={year} />;
+      case "item-matching":
+        return <ItemMatching accounts={accounts} year={year} />;
       case "loss-carryforward":
         return <LossCarryforwardList year={year} />;
       case "final-statement":
@@ -117,8 +194,12 @@ export default function App() {
       year={year}
       onYearChange={setYear}
       onBackup={handleBackup}
+      undoStatus={undoStatus}
+      onUndo={() => handleUndoRedo(true)}
+      onRedo={() => handleUndoRedo(false)}
+      onUndoHover={loadUndoStatus}
     >
-      {renderPage()}
+      <div key={pageKey}>{renderPage()}</div>
     </Layout>
   );
 }
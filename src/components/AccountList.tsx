@@ -10,6 +10,150 @@ interface Props {
 
 const classifications = ["資産", "負債", "純資産", "収益", "費用"] as const;
 
+// 集計で科目コードを直接参照している科目（消込の対象科目とともに、コード・区分の変更、非表示、削除、統合元にできない）
+const SYSTEM_ACCOUNT_CODES = [3100, 3200, 3300, 4100, 5100, 4300, 5960];
+
+interface EditorProps {
+  account: Account;
+  accounts: Account[];
+  onChanged: () => void;
+}
+
+function AccountEditor({ account, accounts, onChanged }: EditorProps) {
+  const [code, setCode] = useState(String(account.code));
+  const [name, setName] = useState(account.name);
+  const [classification, setClassification] = useState<string>(
+    account.classification,
+  );
+  const [mergeTo, setMergeTo] = useState("");
+  const [error, setError] = useState("");
+  const isSystem = SYSTEM_ACCOUNT_CODES.includes(account.code) || account.is_matching;
+  const mergeTargets = accounts.filter(
+    (a) => a.id !== account.id && a.classification === account.classification,
+  );
+
+  async function run(action: () => Promise<unknown>) {
+    setError("");
+    try {
+      await action();
+      onChanged();
+    } catch (err) {
+      setError(String(err));
+    }
+  }
+
+  function handleSave(isActive: boolean) {
+    const codeNum = parseInt(code, 10);
+    if (isNaN(codeNum) || codeNum <= 0) {
+      setError("科目コードは正の整数を入力してください");
+      return;
+    }
+    run(() =>
+      api.updateAccount({
+        id: account.id,
+        code: codeNum,
+        name: name.trim(),
+        classification,
+        isActive,
+      }),
+    );
+  }
+
+  function handleDelete() {
+    if (!confirm(`「${account.name}」を削除しますか？`)) return;
+    run(() => api.deleteAccount(account.id));
+  }
+
+  function handleMerge() {
+    const target = accounts.find((a) => a.id === Number(mergeTo));
+    if (!target) return;
+    if (
+      !confirm(
+        `「${account.name}」の仕訳・期首残高をすべて「${target.name}」に付け替え、「${account.name}」を削除します。\n統合は元に戻せず、これまでの操作も元に戻せなくなります。よろしいですか？`,
+      )
+    )
+      return;
+    run(async () => {
+      const count = await api.mergeAccounts(account.id, target.id);
+      alert(`${count}件の仕訳を付け替えました`);
+    });
+  }
+
+  return (
+    <div className="mt-2 ml-8 space-y-2 rounded border border-gray-100 p-2.5 text-xs">
+      <div className="flex flex-wrap gap-2">
+        <input
+          type="number"
+          value={code}
+          onChange={(e) => setCode(e.target.value)}
+          disabled={isSystem}
+          className="w-20 rounded border border-gray-300 px-2 py-1 disabled:bg-gray-50"
+        />
+        <input
+          type="text"
+          value={name}
+          onChange={(e) => setName(e.target.value)}
+          className="w-32 rounded border border-gray-300 px-2 py-1"
+        />
+        <select
+          value={classification}
+          onChange={(e) => setClassification(e.target.value)}
+          disabled={isSystem}
+          className="rounded border border-gray-300 px-2 py-1 disabled:bg-gray-50"
+        >
+          {classifications.map((c) => (
+            <option key={c} value={c}>
+              {c}
+            </option>
+          ))}
+        </select>
+        <button
+          onClick={() => handleSave(account.is_active)}
+          className="rounded bg-primary-600 px-3 py-1 text-white hover:bg-primary-700 transition"
+        >
+          保存
+        </button>
+      </div>
+      {!isSystem && (
+        <div className="flex flex-wrap items-center gap-2">
+          <button
+            onClick={() => handleSave(!account.is_active)}
+            className="rounded border border-gray-300 px-3 py-1 hover:bg-gray-50"
+          >
+            {account.is_active ? "非表示にする" : "表示に戻す"}
+          </button>
+          <button
+            onClick={handleDelete}
+            className="rounded border border-red-200 px-3 py-1 text-red-600 hover:bg-red-50"
+          >
+            削除
+          </button>
+          <select
+            value={mergeTo}
+            onChange={(e) => setMergeTo(e.target.value)}
+            className="rounded border border-gray-300 px-2 py-1"
+          >
+            <option value="">統合先を選択</option>
+            {mergeTargets.map((a) => (
+              <option key={a.id} value={a.id}>
+                {a.code} {a.name}
+              </option>
+            ))}
+          </select>
+          <button
+            onClick={handleMerge}
+            disabled={!mergeTo}
+            className="rounded border border-gray-300 px-3 py-1 hover:bg-gray-50 disabled:opacity-40"
+          >
+            統合
+          </button>
+        </div>
+      )}
+      {error && <p className="text-red-600">{error}</p>}
+    </div>
+  );
+}
+
 export default function AccountList({ accounts, onAdded }: Props) {
   const [showForm, setShowForm] = useState(false);
   const [code, setCode] = useState("");
@@ -148,14 +292,21 @@ export default function AccountList({ accounts, onAdded }: Props) {
                       <span className="font-mono text-xs text-gray-400 mr-2">
                         {a.code}
                       </span>
-                      <span className="flex-1 font-medium">{a.name}</span>
-                      {help && (
-                        <span
-                          className={`ml-1 text-xs text-gray-400 transition-transform ${isExpanded ? "rotate-180" : ""}`}
-                        >
-                          ▼
+                      <span
+                        className={`flex-1 font-medium ${a.is_active ? "" : "text-gray-400 line-through"}`}
+                      >
+                        {a.name}
+                      </span>
+                      {!a.is_active && (
+                        <span className="mr-1 text-[10px] text-gray-400">
+                          非表示
                         </span>
                       )}
+                      <span
+                        className={`ml-1 text-xs text-gray-400 transition-transform ${isExpanded ? "rotate-180" : ""}`}
+                      >
+                        ▼
+                      </span>
                     </button>
                     {help && isExpanded && (
                       <div className="mt-2 ml-8 rounded bg-gray-50 p-2.5 text-xs leading-relaxed text-gray-600">
@@ -174,6 +325,14 @@ export default function AccountList({ accounts, onAdded }: Props) {
                         )}
                       </div>
                     )}
+                    {isExpanded && (
+                      <AccountEditor
+                        key={`${a.id}-${a.code}-${a.name}-${a.classification}-${a.is_active}`}
+                        account={a}
+                        accounts={accounts}
+                        onChanged={onAdded}
+                      />
+                    )}
                   </li>
                 );
               })}
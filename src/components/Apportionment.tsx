@@ -0,0 +1,257 @@
+import { useState, useEffect, useCallback } from "react";
+import type { Account, ApportionmentRule, ApportionmentLine } from "../types";
+import { formatYen } from "../lib/format";
+import * as api from "../lib/api";
+
+interface Props {
+  accounts: Account[];
+  year: number;
+}
+
+export default function Apportionment({ accounts, year }: Props) {
+  const [rules, setRules] = useState<ApportionmentRule[]>([]);
+  const [lines, setLines] = useState<ApportionmentLine[]>([]);
+  const [accountId, setAccountId] = useState<number>(0);
+  const [businessRatio, setBusinessRatio] = useState("50");
+  const [basis, setBasis] = useState("");
+  const [error, setError] = useState("");
+  const [posting, setPosting] = useState(false);
+
+  const expenseAccounts = accounts.filter(
+    (a) => a.classification === "費用" && a.is_active,
+  );
+
+  const load = useCallback(() => {
+    api.getApportionmentRules().then(setRules).catch(console.error);
+    api
+      .previewApportionment(year)
+      .then(setLines)
+      .catch((err) => setError(String(err)));
+  }, [year]);
+
+  useEffect(() => {
+    load();
+  }, [load]);
+
+  function handleEdit(rule: ApportionmentRule) {
+    setAccountId(rule.account_id);
+    setBusinessRatio(String(rule.business_ratio));
+    setBasis(rule.basis);
+  }
+
+  async function handleSave(e: React.FormEvent) {
+    e.preventDefault();
+    setError("");
+    const ratio = parseInt(businessRatio, 10);
+    if (!accountId) {
+      setError("科目を選択してください");
+      return;
+    }
+    if (isNaN(ratio) || ratio < 0 || ratio > 100) {
+      setError("事業割合は0〜100%で入力してください");
+      return;
+    }
+    try {
+      await api.saveApportionmentRule({ accountId, businessRatio: ratio, basis });
+      setAccountId(0);
+      setBusinessRatio("50");
+      setBasis("");
+      load();
+    } catch (err) {
+      setError(String(err));
+    }
+  }
+
+  async function handleDelete(rule: ApportionmentRule) {
+    if (!confirm(`「${rule.account_name}」の家事按分ルールを削除しますか？`)) return;
+    try {
+      await api.deleteApportionmentRule(rule.id);
+      load();
+    } catch (err) {
+      alert(String(err));
+    }
+  }
+
+  async function handlePost() {
+    const hasPosted = lines.some((l) => l.posted_amount > 0);
+    const message = hasPosted
+      ? `${year}年の家事按分の振替仕訳を作り直しますか？（作成済みの振替仕訳は取り消されます）`
+      : `${year}年12月31日付で家事按分の振替仕訳を作成しますか？`;
+    if (!confirm(message)) return;
+    setPosting(true);
+    try {
+      const ids = await api.postApportionment(year);
+      alert(`振替仕訳を${ids.length}件作成しました`);
+      load();
+    } catch (err) {
+      alert(String(err));
+    } finally {
+      setPosting(false);
+    }
+  }
+
+  const privateTotal = lines.reduce((s, l) => s + l.private_amount, 0);
+  const postedTotal = lines.reduce((s, l) => s + l.posted_amount, 0);
+
+  return (
+    <div className="space-y-4">
+      <h2 className="text-lg font-semibold text-gray-800">家事按分</h2>
+
+      <form
+        onSubmit={handleSave}
+        className="rounded-lg border border-gray-200 bg-white p-4 shadow-sm space-y-3"
+      >
+        <div className="flex flex-wrap items-end gap-3">
+          <div>
+            <label className="mb-1 block text-xs font-medium text-gray-500">
+              費用科目
+            </label>
+            <select
+              value={accountId}
+              onChange={(e) => setAccountId(Number(e.target.value))}
+              className="rounded border border-gray-300 px-3 py-2 text-sm"
+            >
+              <option value={0}>選択...</option>
+              {expenseAccounts.map((a) => (
+                <option key={a.id} value={a.id}>
+                  {a.code} {a.name}
+                </option>
+              ))}
+            </select>
+          </div>
+          <div>
+            <label className="mb-1 block text-xs font-medium text-gray-500">
+              事業割合（%）
+            </label>
+            <input
+              type="number"
+              min="0"
+              max="100"
+              value={businessRatio}
+              onChange={(e) => setBusinessRatio(e.target.value)}
+              className="w-24 rounded border border-gray-300 px-3 py-2 text-sm"
+            />
+          </div>
+          <div className="flex-1">
+            <label className="mb-1 block text-xs font-medium text-gray-500">
+              按分の根拠
+            </label>
+            <input
+              type="text"
+              value={basis}
+              onChange={(e) => setBasis(e.target.value)}
+              placeholder="床面積 12㎡ / 60㎡ など"
+              className="w-full rounded border border-gray-300 px-3 py-2 text-sm"
+            />
+          </div>
+          <button
+            type="submit"
+            className="rounded bg-primary-600 px-4 py-2 text-sm text-white hover:bg-primary-700 transition"
+          >
+            保存
+          </button>
+        </div>
+        {error && <p className="text-sm text-red-600">{error}</p>}
+      </form>
+
+      <div className="rounded-lg border border-gray-200 bg-white shadow-sm">
+        <div className="flex items-center justify-between border-b border-gray-200 px-5 py-3">
+          <h3 className="text-sm font-semibold text-gray-800">
+            {year}年の家事分（12月31日付で 事業主貸 / 費用科目 に振り替えます）
+          </h3>
+          <button
+            onClick={handlePost}
+            disabled={posting || lines.length === 0}
+            className="rounded bg-primary-600 px-4 py-1.5 text-sm text-white hover:bg-primary-700 disabled:opacity-50 transition"
+          >
+            {postedTotal > 0 ? "振替仕訳を作り直す" : "振替仕訳を作成"}
+          </button>
+        </div>
+        <table className="w-full text-sm">
+          <thead>
+            <tr className="border-b border-gray-100 bg-gray-50 text-left text-xs font-medium text-gray-500">
+              <th className="px-4 py-2">科目</th>
+              <th className="px-4 py-2 text-right">事業割合</th>
+              <th className="px-4 py-2">根拠</th>
+              <th className="px-4 py-2 text-right">年間の費用</th>
+              <th className="px-4 py-2 text-right">家事分</th>
+              <th className="px-4 py-2 text-right">作成済み</th>
+              <th className="px-4 py-2"></th>
+            </tr>
+          </thead>
+          <tbody>
+            {lines.length === 0 ? (
+              <tr>
+                <td colSpan={7} className="px-4 py-8 text-center text-gray-400">
+                  家事按分のルールがありません
+                </td>
+              </tr>
+            ) : (
+              lines.map((l) => {
+                const rule = rules.find((r) => r.account_id === l.account_id);
+                return (
+                  <tr key={l.account_id} className="border-b border-gray-50 hover:bg-gray-50">
+                    <td className="px-4 py-2">
+                      {l.account_code} {l.account_name}
+                    </td>
+                    <td className="px-4 py-2 text-right tabular-nums">
+                      {l.business_ratio}%
+                    </td>
+                    <td className="px-4 py-2 text-gray-600">{l.basis}</td>
+                    <td className="px-4 py-2 text-right tabular-nums">
+                      {formatYen(l.expense_total)}
+                    </td>
+                    <td className="px-4 py-2 text-right tabular-nums font-medium">
+                      {formatYen(l.private_amount)}
+                    </td>
+                    <td
+                      className={`px-4 py-2 text-right tabular-nums ${
+                        l.posted_amount !== l.private_amount ? "text-red-600" : "text-gray-500"
+                      }`}
+                    >
+                      {formatYen(l.posted_amount)}
+                    </td>
+                    <td className="px-4 py-2 text-right whitespace-nowrap space-x-2">
+                      {rule && (
+                        <>
+                          <button
+                            onClick={() => handleEdit(rule)}
+                            className="text-xs text-primary-600 hover:text-primary-800"
+                          >
+                            編集
+                          </button>
+                          <button
+                            onClick={() => handleDelete(rule)}
+                            className="text-xs text-red-500 hover:text-red-700"
+                          >
+                            削除
+                          </button>
+                        </>
+                      )}
+                    </td>
+                  </tr>
+                );
+              })
+            )}
+          </tbody>
+          {lines.length > 0 && (
+            <tfoot>
+              <tr className="border-t-2 border-gray-200 bg-gray-50 font-semibold">
+                <td className="px-4 py-2" colSpan={4}>
+                  合計
+                </td>
+                <td className="px-4 py-2 text-right tabular-nums">
+                  {formatYen(privateTotal)}
+                </td>
+                <td className="px-4 py-2 text-right tabular-nums">
+                  {formatYen(postedTotal)}
+                </td>
+                <td></td>
+              </tr>
+            </tfoot>
+          )}
+        </table>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,251 @@
+import { useState, useEffect, useCallback } from "react";
+import { open } from "@tauri-apps/api/dialog";
+import type { Attachment, AttachmentStatus } from "../types";
+import { todayString } from "../lib/format";
+import * as api from "../lib/api";
+
+const STATUS_STYLES: Record<AttachmentStatus, string> = {
+  正常: "bg-green-50 text-green-700",
+  不一致: "bg-red-50 text-red-700",
+  ファイルなし: "bg-red-50 text-red-700",
+};
+
+function formatSize(size: number): string {
+  if (size >= 1024 * 1024) return `${(size / 1024 / 1024).toFixed(1)} MB`;
+  if (size >= 1024) return `${Math.round(size / 1024)} KB`;
+  return `${size} B`;
+}
+
+export default function AttachmentList() {
+  const [attachments, setAttachments] = useState<Attachment[]>([]);
+  const [statuses, setStatuses] = useState<Record<number, AttachmentStatus>>({});
+  const [path, setPath] = useState("");
+  const [receivedDate, setReceivedDate] = useState(todayString());
+  const [entryIds, setEntryIds] = useState("");
+  const [memo, setMemo] = useState("");
+  const [error, setError] = useState("");
+
+  const load = useCallback(() => {
+    api.getAttachments().then(setAttachments).catch(console.error);
+  }, []);
+
+  useEffect(() => {
+    load();
+  }, [load]);
+
+  async function handleChooseFile() {
+    const selected = await open({ title: "証憑ファイルを選択" });
+    if (typeof selected === "string") setPath(selected);
+  }
+
+  async function handleAttach(e: React.FormEvent) {
+    e.preventDefault();
+    setError("");
+    if (!path) {
+      setError("ファイルを選択してください");
+      return;
+    }
+    const ids = entryIds
+      .split(/[,\s]+/)
+      .filter((s) => s !== "")
+      .map((s) => parseInt(s, 10));
+    if (ids.some((id) => isNaN(id))) {
+      setError("仕訳 ID は数字をカンマ区切りで入力してください");
+      return;
+    }
+    try {
+      const result = await api.attachFile({ path, entryIds: ids, receivedDate, memo });
+      if (result.reused) {
+        alert(
+          `同じ内容の証憑「${result.attachment.file_name}」が登録済みのため、それを紐付けました` +
+            `（受領日 ${result.attachment.received_date}・メモは登録済みのままです）`,
+        );
+      }
+      setPath("");
+      setEntryIds("");
+      setMemo("");
+      load();
+    } catch (err) {
+      setError(String(err));
+    }
+  }
+
+  async function handleVerify() {
+    try {
+      const checks = await api.verifyAttachments();
+      setStatuses(Object.fromEntries(checks.map((c) => [c.id, c.status])));
+      const problems = checks.filter((c) => c.status !== "正常");
+      alert(
+        problems.length === 0
+          ? `${checks.length}件の証憑はすべて登録時から変更されていません`
+          : `${problems.length}件の証憑に問題があります:\n` +
+              problems.map((p) => `${p.file_name}（${p.status}）`).join("\n"),
+      );
+    } catch (err) {
+      alert(String(err));
+    }
+  }
+
+  async function handleOpen(id: number) {
+    try {
+      await api.openAttachment(id);
+    } catch (err) {
+      alert(String(err));
+    }
+  }
+
+  async function handleUnlink(attachment: Attachment, entryId: number) {
+    if (!confirm(`「${attachment.file_name}」と仕訳 ID ${entryId} の紐付けを解除しますか？`)) return;
+    try {
+      await api.unlinkAttachment(attachment.id, entryId);
+      load();
+    } catch (err) {
+      alert(String(err));
+    }
+  }
+
+  return (
+    <div className="space-y-4">
+      <div className="flex items-center justify-between">
+        <h2 className="text-lg font-semibold text-gray-800">証憑</h2>
+        <button
+          onClick={handleVerify}
+          className="rounded border border-gray-300 bg-white px-4 py-2 text-sm hover:bg-gray-50 transition"
+        >
+          改ざんチェック
+        </button>
+      </div>
+
+      <form
+        onSubmit={handleAttach}
+        className="rounded-lg border border-gray-200 bg-white p-4 shadow-sm space-y-3"
+      >
+        <div className="flex flex-wrap items-end gap-3">
+          <div>
+            <label className="mb-1 block text-xs font-medium text-gray-500">
+              ファイル
+            </label>
+            <button
+              type="button"
+              onClick={handleChooseFile}
+              className="rounded border border-gray-300 px-3 py-2 text-sm hover:bg-gray-50"
+            >
+              {path ? path.split(/[\\/]/).pop() : "選択..."}
+            </button>
+          </div>
+          <div>
+            <label className="mb-1 block text-xs font-medium text-gray-500">
+              受領日
+            </label>
+            <input
+              type="date"
+              value={receivedDate}
+              onChange={(e) => setReceivedDate(e.target.value)}
+              className="rounded border border-gray-300 px-3 py-2 text-sm"
+            />
+          </div>
+          <div>
+            <label className="mb-1 block text-xs font-medium text-gray-500">
+              仕訳 ID（カンマ区切り）
+            </label>
+            <input
+              type="text"
+              value={entryIds}
+              onChange={(e) => setEntryIds(e.target.value)}
+              className="w-40 rounded border border-gray-300 px-3 py-2 text-sm"
+            />
+          </div>
+          <div className="flex-1">
+            <label className="mb-1 block text-xs font-medium text-gray-500">
+              メモ
+            </label>
+            <input
+              type="text"
+              value={memo}
+              onChange={(e) => setMemo(e.target.value)}
+              className="w-full rounded border border-gray-300 px-3 py-2 text-sm"
+            />
+          </div>
+          <button
+            type="submit"
+            className="rounded bg-primary-600 px-4 py-2 text-sm text-white hover:bg-primary-700 transition"
+          >
+            登録
+          </button>
+        </div>
+        {error && <p className="text-sm text-red-600">{error}</p>}
+      </form>
+
+      <div className="rounded-lg border border-gray-200 bg-white shadow-sm">
+        <table className="w-full text-sm">
+          <thead>
+            <tr className="border-b border-gray-100 bg-gray-50 text-left text-xs font-medium text-gray-500">
+              <th className="px-4 py-2">受領日</th>
+              <th className="px-4 py-2">ファイル名</th>
+              <th className="px-4 py-2 text-right">サイズ</th>
+              <th className="px-4 py-2">仕訳 ID</th>
+              <th className="px-4 py-2">メモ</th>
+              <th className="px-4 py-2">SHA-256</th>
+              <th className="px-4 py-2"></th>
+            </tr>
+          </thead>
+          <tbody>
+            {attachments.length === 0 ? (
+              <tr>
+                <td colSpan={7} className="px-4 py-8 text-center text-gray-400">
+                  登録された証憑はありません
+                </td>
+              </tr>
+            ) : (
+              attachments.map((a) => (
+                <tr key={a.id} className="border-b border-gray-50 hover:bg-gray-50">
+                  <td className="px-4 py-2 whitespace-nowrap">{a.received_date}</td>
+                  <td className="px-4 py-2">
+                    {a.file_name}
+                    {statuses[a.id] && (
+                      <span
+                        className={`ml-2 rounded px-1.5 py-0.5 text-xs ${STATUS_STYLES[statuses[a.id]]}`}
+                      >
+                        {statuses[a.id]}
+                      </span>
+                    )}
+                  </td>
+                  <td className="px-4 py-2 text-right tabular-nums text-gray-500">
+                    {formatSize(a.size)}
+                  </td>
+                  <td className="px-4 py-2 space-x-1">
+                    {a.entry_ids.map((id) => (
+                      <button
+                        key={id}
+                        onClick={() => handleUnlink(a, id)}
+                        title="クリックで紐付けを解除"
+                        className="rounded bg-gray-100 px-1.5 py-0.5 text-xs text-gray-600 hover:bg-gray-200"
+                      >
+                        {id}
+                      </button>
+                    ))}
+                  </td>
+                  <td className="px-4 py-2 text-gray-600">{a.memo}</td>
+                  <td
+                    className="px-4 py-2 font-mono text-xs text-gray-400"
+                    title={a.sha256}
+                  >
+                    {a.sha256.slice(0, 12)}…
+                  </td>
+                  <td className="px-4 py-2 text-right">
+                    <button
+                      onClick={() => handleOpen(a.id)}
+                      className="text-xs text-primary-600 hover:text-primary-800"
+                    >
+                      開く
+                    </button>
+                  </td>
+                </tr>
+              ))
+            )}
+          </tbody>
+        </table>
+      </div>
+    </div>
+  );
+}
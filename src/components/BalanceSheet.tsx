@@ -39,6 +39,13 @@ export default function BalanceSheet({ year }: Props) {
             <h3 className="text-sm font-semibold text-emerald-800">資産の部</h3>
           </div>
           <table className="w-full text-sm">
+            <thead>
+              <tr className="border-b border-gray-100 text-xs text-gray-500">
+                <th className="px-4 py-1 text-left font-medium">科目</th>
+                <th className="px-4 py-1 text-right font-medium">期首（1月1日）</th>
+                <th className="px-4 py-1 text-right font-medium">期末（12月31日）</th>
+              </tr>
+            </thead>
             <tbody>
               {data.asset_rows.map((row) => (
                 <tr
@@ -46,6 +53,9 @@ export default function BalanceSheet({ year }: Props) {
                   className="border-b border-gray-50 hover:bg-gray-50"
                 >
                   <td className="px-4 py-2">{row.account_name}</td>
+                  <td className="px-4 py-2 text-right tabular-nums text-gray-500">
+                    {formatYen(row.opening_amount)}
+                  </td>
                   <td className="px-4 py-2 text-right tabular-nums">
                     {formatYen(row.amount)}
                   </td>
@@ -53,7 +63,7 @@ export default function BalanceSheet({ year }: Props) {
               ))}
               {data.asset_rows.length === 0 && (
                 <tr>
-                  <td colSpan={2} className="px-4 py-3 text-gray-400 text-center">
+                  <td colSpan={3} className="px-4 py-3 text-gray-400 text-center">
                     資産データなし
                   </td>
                 </tr>
@@ -62,6 +72,9 @@ export default function BalanceSheet({ year }: Props) {
             <tfoot>
               <tr className="border-t-2 border-emerald-200 bg-emerald-50 font-semibold">
                 <td className="px-4 py-2">資産合計</td>
+                <td className="px-4 py-2 text-right tabular-nums">
+                  {formatYen(data.total_assets_opening)}
+                </td>
                 <td className="px-4 py-2 text-right tabular-nums">
                   {formatYen(data.total_assets)}
                 </td>
@@ -78,6 +91,13 @@ export default function BalanceSheet({ year }: Props) {
               <h3 className="text-sm font-semibold text-red-800">負債の部</h3>
             </div>
             <table className="w-full text-sm">
+              <thead>
+                <tr className="border-b border-gray-100 text-xs text-gray-500">
+                  <th className="px-4 py-1 text-left font-medium">科目</th>
+                  <th className="px-4 py-1 text-right font-medium">期首（1月1日）</th>
+                  <th className="px-4 py-1 text-right font-medium">期末（12月31日）</th>
+                </tr>
+              </thead>
               <tbody>
                 {data.liability_rows.map((row) => (
                   <tr
@@ -85,6 +105,9 @@ export default function BalanceSheet({ year }: Props) {
                     className="border-b border-gray-50 hover:bg-gray-50"
                   >
                     <td className="px-4 py-2">{row.account_name}</td>
+                    <td className="px-4 py-2 text-right tabular-nums text-gray-500">
+                      {formatYen(row.opening_amount)}
+                    </td>
                     <td className="px-4 py-2 text-right tabular-nums">
                       {formatYen(row.amount)}
                     </td>
@@ -92,7 +115,7 @@ export default function BalanceSheet({ year }: Props) {
                 ))}
                 {data.liability_rows.length === 0 && (
                   <tr>
-                    <td colSpan={2} className="px-4 py-3 text-gray-400 text-center">
+                    <td colSpan={3} className="px-4 py-3 text-gray-400 text-center">
                       負債データなし
                     </td>
                   </tr>
@@ -101,6 +124,9 @@ export default function BalanceSheet({ year }: Props) {
               <tfoot>
                 <tr className="border-t-2 border-red-200 bg-red-50 font-semibold">
                   <td className="px-4 py-2">負債合計</td>
+                  <td className="px-4 py-2 text-right tabular-nums">
+                    {formatYen(data.total_liabilities_opening)}
+                  </td>
                   <td className="px-4 py-2 text-right tabular-nums">
                     {formatYen(data.total_liabilities)}
                   </td>
@@ -117,6 +143,13 @@ export default function BalanceSheet({ year }: Props) {
               </h3>
             </div>
             <table className="w-full text-sm">
+              <thead>
+                <tr className="border-b border-gray-100 text-xs text-gray-500">
+                  <th className="px-4 py-1 text-left font-medium">科目</th>
+                  <th className="px-4 py-1 text-right font-medium">期首（1月1日）</th>
+                  <th className="px-4 py-1 text-right font-medium">期末（12月31日）</th>
+                </tr>
+              </thead>
               <tbody>
                 {data.equity_rows.map((row) => (
                   <tr
@@ -124,6 +157,9 @@ export default function BalanceSheet({ year }: Props) {
                     className="border-b border-gray-50 hover:bg-gray-50"
                   >
                     <td className="px-4 py-2">{row.account_name}</td>
+                    <td className="px-4 py-2 text-right tabular-nums text-gray-500">
+                      {formatYen(row.opening_amount)}
+                    </td>
                     <td className="px-4 py-2 text-right tabular-nums">
                       {formatYen(row.amount)}
                     </td>
@@ -133,6 +169,7 @@ export default function BalanceSheet({ year }: Props) {
                   <td className="px-4 py-2 italic text-gray-500">
                     当期純利益
                   </td>
+                  <td></td>
                   <td
                     className={`px-4 py-2 text-right tabular-nums ${
                       data.net_income < 0 ? "text-red-600" : ""
@@ -145,6 +182,9 @@ export default function BalanceSheet({ year }: Props) {
               <tfoot>
                 <tr className="border-t-2 border-purple-200 bg-purple-50 font-semibold">
                   <td className="px-4 py-2">純資産合計</td>
+                  <td className="px-4 py-2 text-right tabular-nums">
+                    {formatYen(data.total_equity_opening)}
+                  </td>
                   <td className="px-4 py-2 text-right tabular-nums">
                     {formatYen(data.total_equity + data.net_income)}
                   </td>
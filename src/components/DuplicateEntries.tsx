@@ -0,0 +1,160 @@
+import { useState, useEffect, useCallback } from "react";
+import type { DuplicatePair, JournalEntry } from "../types";
+import { formatYen } from "../lib/format";
+import * as api from "../lib/api";
+
+interface Props {
+  year: number;
+}
+
+function EntryCell({ entry }: { entry: JournalEntry }) {
+  return (
+    <div>
+      <span className="text-gray-400 tabular-nums">No.{entry.voucher_no}</span>{" "}
+      <span className="whitespace-nowrap">{entry.date}</span>
+      <div className="text-gray-600">
+        {entry.is_compound
+          ? "諸口"
+          : `${entry.debit_account_name} / ${entry.credit_account_name}`}{" "}
+        {entry.description}
+      </div>
+    </div>
+  );
+}
+
+export default function DuplicateEntries({ year }: Props) {
+  const [windowDays, setWindowDays] = useState("7");
+  const [includeDismissed, setIncludeDismissed] = useState(false);
+  const [pairs, setPairs] = useState<DuplicatePair[]>([]);
+  const [error, setError] = useState("");
+
+  const load = useCallback(() => {
+    const days = parseInt(windowDays, 10);
+    setError("");
+    api
+      .findDuplicateEntries(year, isNaN(days) ? undefined : days, includeDismissed)
+      .then(setPairs)
+      .catch((err) => setError(String(err)));
+  }, [year, windowDays, includeDismissed]);
+
+  useEffect(() => {
+    load();
+  }, [load]);
+
+  async function toggleDismissed(pair: DuplicatePair) {
+    try {
+      if (pair.dismissed) {
+        await api.undismissDuplicate(pair.entry.id, pair.duplicate.id);
+      } else {
+        await api.dismissDuplicate(pair.entry.id, pair.duplicate.id);
+      }
+      load();
+    } catch (err) {
+      setError(String(err));
+    }
+  }
+
+  async function handleDelete(entry: JournalEntry) {
+    if (!confirm(`No.${entry.voucher_no}（${entry.date} ${entry.description}）を削除しますか？`)) return;
+    try {
+      await api.deleteEntry(entry.id);
+      load();
+    } catch (err) {
+      setError(String(err));
+    }
+  }
+
+  return (
+    <div className="space-y-4">
+      <div className="flex flex-wrap items-center justify-between gap-3">
+        <h2 className="text-lg font-semibold text-gray-800">
+          重複チェック（{year}年）
+        </h2>
+        <div className="flex items-center gap-4 text-sm">
+          <label className="flex items-center gap-2">
+            同額・同摘要は
+            <input
+              type="number"
+              min="0"
+              max="90"
+              value={windowDays}
+              onChange={(e) => setWindowDays(e.target.value)}
+              className="w-16 rounded border border-gray-300 px-2 py-1"
+            />
+            日以内
+          </label>
+          <label className="flex items-center gap-1">
+            <input
+              type="checkbox"
+              checked={includeDismissed}
+              onChange={(e) => setIncludeDismissed(e.target.checked)}
+            />
+            確認済みも表示
+          </label>
+        </div>
+      </div>
+
+      {error && <p className="text-sm text-red-600">{error}</p>}
+
+      <div className="rounded-lg border border-gray-200 bg-white shadow-sm">
+        <table className="w-full text-sm">
+          <thead>
+            <tr className="border-b border-gray-100 bg-gray-50 text-left text-xs font-medium text-gray-500">
+              <th className="px-4 py-2">仕訳</th>
+              <th className="px-4 py-2">重複の疑いがある仕訳</th>
+              <th className="px-4 py-2 text-right">金額</th>
+              <th className="px-4 py-2">理由</th>
+              <th className="px-4 py-2"></th>
+            </tr>
+          </thead>
+          <tbody>
+            {pairs.length === 0 ? (
+              <tr>
+                <td colSpan={5} className="px-4 py-8 text-center text-gray-400">
+                  重複の疑いがある仕訳はありません
+                </td>
+              </tr>
+            ) : (
+              pairs.map((p) => (
+                <tr
+                  key={`${p.entry.id}-${p.duplicate.id}`}
+                  className={`border-b border-gray-50 align-top ${p.dismissed ? "text-gray-400" : ""}`}
+                >
+                  <td className="px-4 py-2">
+                    <EntryCell entry={p.entry} />
+                  </td>
+                  <td className="px-4 py-2">
+                    <EntryCell entry={p.duplicate} />
+                  </td>
+                  <td className="px-4 py-2 text-right tabular-nums">
+                    {formatYen(p.entry.debit_amount)}
+                  </td>
+                  <td className="px-4 py-2 text-xs">
+                    {p.reason}
+                    {p.days_apart > 0 && `（${p.days_apart}日差）`}
+                  </td>
+                  <td className="px-4 py-2 text-right whitespace-nowrap">
+                    <button
+                      onClick={() => toggleDismissed(p)}
+                      className="rounded border border-gray-300 px-2 py-1 text-xs hover:bg-gray-50"
+                    >
+                      {p.dismissed ? "確認を取り消す" : "重複ではない"}
+                    </button>
+                    {!p.dismissed && (
+                      <button
+                        onClick={() => handleDelete(p.duplicate)}
+                        className="ml-2 rounded border border-red-200 px-2 py-1 text-xs text-red-600 hover:bg-red-50"
+                      >
+                        新しい方を削除
+                      </button>
+                    )}
+                  </td>
+                </tr>
+              ))
+            )}
+          </tbody>
+        </table>
+      </div>
+    </div>
+  );
+}
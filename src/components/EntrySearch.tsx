@@ -0,0 +1,283 @@
+import { useState, useEffect } from "react";
+import type {
+  Account,
+  Partner,
+  Segment,
+  EntrySearchQuery,
+  EntrySearchResult,
+} from "../types";
+import { formatYen } from "../lib/format";
+import * as api from "../lib/api";
+
+const PAGE_SIZE = 50;
+
+interface Props {
+  accounts: Account[];
+}
+
+export default function EntrySearch({ accounts }: Props) {
+  const [partners, setPartners] = useState<Partner[]>([]);
+  const [segments, setSegments] = useState<Segment[]>([]);
+  const [keyword, setKeyword] = useState("");
+  const [dateFrom, setDateFrom] = useState("");
+  const [dateTo, setDateTo] = useState("");
+  const [amountMin, setAmountMin] = useState("");
+  const [amountMax, setAmountMax] = useState("");
+  const [accountId, setAccountId] = useState("");
+  const [partnerId, setPartnerId] = useState("");
+  const [segmentId, setSegmentId] = useState("");
+  const [query, setQuery] = useState<EntrySearchQuery | null>(null);
+  const [result, setResult] = useState<EntrySearchResult | null>(null);
+  const [error, setError] = useState("");
+
+  useEffect(() => {
+    api.getPartners().then(setPartners).catch(console.error);
+    api.getSegments().then(setSegments).catch(console.error);
+  }, []);
+
+  useEffect(() => {
+    if (!query) return;
+    api
+      .searchEntries(query)
+      .then(setResult)
+      .catch((err) => setError(String(err)));
+  }, [query]);
+
+  function toNumber(val: string): number | undefined {
+    const n = parseInt(val, 10);
+    return isNaN(n) ? undefined : n;
+  }
+
+  function handleSearch(e: React.FormEvent) {
+    e.preventDefault();
+    setError("");
+    setQuery({
+      keyword: keyword.trim(),
+      date_from: dateFrom || undefined,
+      date_to: dateTo || undefined,
+      amount_min: toNumber(amountMin),
+      amount_max: toNumber(amountMax),
+      account_id: toNumber(accountId),
+      partner_id: toNumber(partnerId),
+      segment_id: toNumber(segmentId),
+      limit: PAGE_SIZE,
+      offset: 0,
+    });
+  }
+
+  function goToPage(offset: number) {
+    if (query) setQuery({ ...query, offset });
+  }
+
+  const offset = query?.offset ?? 0;
+  const total = result?.total_count ?? 0;
+
+  return (
+    <div className="space-y-4">
+      <h2 className="text-lg font-semibold text-gray-800">仕訳検索</h2>
+
+      <form
+        onSubmit={handleSearch}
+        className="rounded-lg border border-gray-200 bg-white p-4 shadow-sm space-y-3"
+      >
+        <div className="grid grid-cols-1 gap-3 sm:grid-cols-2 lg:grid-cols-4">
+          <div className="lg:col-span-2">
+            <label className="mb-1 block text-xs font-medium text-gray-500">
+              摘要（空白区切りで AND 検索）
+            </label>
+            <input
+              type="text"
+              value={keyword}
+              onChange={(e) => setKeyword(e.target.value)}
+              className="w-full rounded border border-gray-300 px-3 py-2 text-sm"
+            />
+          </div>
+          <div>
+            <label className="mb-1 block text-xs font-medium text-gray-500">
+              取引年月日（から）
+            </label>
+            <input
+              type="date"
+              value={dateFrom}
+              onChange={(e) => setDateFrom(e.target.value)}
+              className="w-full rounded border border-gray-300 px-3 py-2 text-sm"
+            />
+          </div>
+          <div>
+            <label className="mb-1 block text-xs font-medium text-gray-500">
+              取引年月日（まで）
+            </label>
+            <input
+              type="date"
+              value={dateTo}
+              onChange={(e) => setDateTo(e.target.value)}
+              className="w-full rounded border border-gray-300 px-3 py-2 text-sm"
+            />
+          </div>
+          <div>
+            <label className="mb-1 block text-xs font-medium text-gray-500">
+              取引金額（以上）
+            </label>
+            <input
+              type="number"
+              min="0"
+              value={amountMin}
+              onChange={(e) => setAmountMin(e.target.value)}
+              className="w-full rounded border border-gray-300 px-3 py-2 text-sm"
+            />
+          </div>
+          <div>
+            <label className="mb-1 block text-xs font-medium text-gray-500">
+              取引金額（以下）
+            </label>
+            <input
+              type="number"
+              min="0"
+              value={amountMax}
+              onChange={(e) => setAmountMax(e.target.value)}
+              className="w-full rounded border border-gray-300 px-3 py-2 text-sm"
+            />
+          </div>
+          <div>
+            <label className="mb-1 block text-xs font-medium text-gray-500">
+              勘定科目
+            </label>
+            <select
+              value={accountId}
+              onChange={(e) => setAccountId(e.target.value)}
+              className="w-full rounded border border-gray-300 px-3 py-2 text-sm"
+            >
+              <option value="">すべて</option>
+              {accounts.map((a) => (
+                <option key={a.id} value={a.id}>
+                  {a.code} {a.name}
+                </option>
+              ))}
+            </select>
+          </div>
+          <div>
+            <label className="mb-1 block text-xs font-medium text-gray-500">
+              取引先
+            </label>
+            <select
+              value={partnerId}
+              onChange={(e) => setPartnerId(e.target.value)}
+              className="w-full rounded border border-gray-300 px-3 py-2 text-sm"
+            >
+              <option value="">すべて</option>
+              {partners.map((p) => (
+                <option key={p.id} value={p.id}>
+                  {p.name}
+                </option>
+              ))}
+            </select>
+          </div>
+          {segments.length > 0 && (
+            <div>
+              <label className="mb-1 block text-xs font-medium text-gray-500">
+                セグメント
+              </label>
+              <select
+                value={segmentId}
+                onChange={(e) => setSegmentId(e.target.value)}
+                className="w-full rounded border border-gray-300 px-3 py-2 text-sm"
+              >
+                <option value="">すべて</option>
+                {segments.map((s) => (
+                  <option key={s.id} value={s.id}>
+                    {s.name}
+                  </option>
+                ))}
+              </select>
+            </div>
+          )}
+        </div>
+        {error && <p className="text-sm text-red-600">{error}</p>}
+        <button
+          type="submit"
+          className="rounded bg-primary-600 px-4 py-2 text-sm text-white hover:bg-primary-700 transition"
+        >
+          検索
+        </button>
+      </form>
+
+      {result && (
+        <div className="rounded-lg border border-gray-200 bg-white shadow-sm">
+          <div className="flex items-center justify-between border-b border-gray-200 px-5 py-3 text-sm">
+            <span className="text-gray-600">
+              {total}件中 {total === 0 ? 0 : offset + 1}〜
+              {offset + result.entries.length}件
+            </span>
+            <div className="flex gap-2">
+              <button
+                onClick={() => goToPage(Math.max(0, offset - PAGE_SIZE))}
+                disabled={offset === 0}
+                className="rounded border border-gray-300 px-3 py-1 text-xs hover:bg-gray-50 disabled:opacity-40"
+              >
+                前へ
+              </button>
+              <button
+                onClick={() => goToPage(offset + PAGE_SIZE)}
+                disabled={offset + PAGE_SIZE >= total}
+                className="rounded border border-gray-300 px-3 py-1 text-xs hover:bg-gray-50 disabled:opacity-40"
+              >
+                次へ
+              </button>
+            </div>
+          </div>
+          <div className="overflow-x-auto">
+            <table className="w-full text-sm">
+              <thead>
+                <tr className="border-b border-gray-100 bg-gray-50 text-left text-xs font-medium uppercase text-gray-500">
+                  <th className="px-4 py-2 text-right">No.</th>
+                  <th className="px-4 py-2">日付</th>
+                  <th className="px-4 py-2">借方科目</th>
+                  <th className="px-4 py-2">貸方科目</th>
+                  <th className="px-4 py-2 text-right">金額</th>
+                  <th className="px-4 py-2">取引先</th>
+                  <th className="px-4 py-2">摘要</th>
+                </tr>
+              </thead>
+              <tbody>
+                {result.entries.length === 0 ? (
+                  <tr>
+                    <td colSpan={7} className="px-4 py-8 text-center text-gray-400">
+                      条件に一致する仕訳がありません
+                    </td>
+                  </tr>
+                ) : (
+                  result.entries.map((entry) => (
+                    <tr
+                      key={entry.id}
+                      className="border-b border-gray-50 hover:bg-gray-50 transition"
+                    >
+                      <td className="px-4 py-2 text-right tabular-nums text-gray-400">
+                        {entry.voucher_no}
+                      </td>
+                      <td className="px-4 py-2 whitespace-nowrap">{entry.date}</td>
+                      <td className="px-4 py-2">
+                        {entry.is_compound ? "諸口" : entry.debit_account_name}
+                      </td>
+                      <td className="px-4 py-2">
+                        {entry.is_compound ? "諸口" : entry.credit_account_name}
+                      </td>
+                      <td className="px-4 py-2 text-right tabular-nums">
+                        {formatYen(entry.debit_amount)}
+                      </td>
+                      <td className="px-4 py-2 text-gray-600">
+                        {entry.partner_name}
+                      </td>
+                      <td className="px-4 py-2 text-gray-600">
+                        {entry.description}
+                      </td>
+                    </tr>
+                  ))
+                )}
+              </tbody>
+            </table>
+          </div>
+        </div>
+      )}
+    </div>
+  );
+}
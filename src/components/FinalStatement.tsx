@@ -47,6 +47,13 @@ export default function FinalStatement({ year }: Props) {
         </button>
       </div>
 
+      {data.open_item_count > 0 && (
+        <p className="rounded border border-yellow-300 bg-yellow-50 px-4 py-2 text-sm text-yellow-800 print:hidden">
+          確認待ち（未確認・要質問）の仕訳が{data.open_item_count}件あります。
+          「確認待ち」で内容を確認してから決算書を作成してください
+        </p>
+      )}
+
       {/* ページタブ */}
       <div className="flex gap-1 border-b border-gray-200 print:hidden">
         {([1, 2, 3, 4] as Page[]).map((p) => (
@@ -399,21 +406,30 @@ function Page4BalanceSheet({ data, year }: { data: FS; year: number }) {
             <h4 className="text-sm font-semibold text-emerald-800">資産の部</h4>
           </div>
           <table className="w-full text-sm">
+            <thead>
+              <tr className="border-b border-gray-100 text-xs text-gray-500">
+                <th className="px-4 py-1 text-left font-medium">科目</th>
+                <th className="px-4 py-1 text-right font-medium">1月1日（期首）</th>
+                <th className="px-4 py-1 text-right font-medium">12月31日（期末）</th>
+              </tr>
+            </thead>
             <tbody>
               {bs.asset_rows.map((r) => (
                 <tr key={r.account_id} className="border-b border-gray-50">
                   <td className="px-4 py-1.5">{r.account_name}</td>
+                  <td className="px-4 py-1.5 text-right tabular-nums w-32 text-gray-500">{formatYen(r.opening_amount)}</td>
                   <td className="px-4 py-1.5 text-right tabular-nums w-32">{formatYen(r.amount)}</td>
                 </tr>
               ))}
               {bs.asset_rows.length === 0 && (
-                <tr><td colSpan={2} className="px-4 py-3 text-center text-gray-400">データなし</td></tr>
+                <tr><td colSpan={3} className="px-4 py-3 text-center text-gray-400">データなし</td></tr>
               )}
             </tbody>
             <tfoot>
               <tr className="border-t-2 border-emerald-200 bg-emerald-50 font-semibold">
                 <td className="px-4 py-2">資産合計</td>
-                <td className="px-4 py-2 text-right tabular-nums">{formatYen(bs.total_assets)}</td>
+                <td className="px-4 py-2 text-right tabular-nums">{formatYen(bs.total_assets_opening)}</td>
+                  <td className="px-4 py-2 text-right tabular-nums">{formatYen(bs.total_assets)}</td>
               </tr>
             </tfoot>
           </table>
@@ -426,20 +442,29 @@ function Page4BalanceSheet({ data, year }: { data: FS; year: number }) {
               <h4 className="text-sm font-semibold text-red-800">負債の部</h4>
             </div>
             <table className="w-full text-sm">
+              <thead>
+                <tr className="border-b border-gray-100 text-xs text-gray-500">
+                  <th className="px-4 py-1 text-left font-medium">科目</th>
+                  <th className="px-4 py-1 text-right font-medium">1月1日（期首）</th>
+                  <th className="px-4 py-1 text-right font-medium">12月31日（期末）</th>
+                </tr>
+              </thead>
               <tbody>
                 {bs.liability_rows.map((r) => (
                   <tr key={r.account_id} className="border-b border-gray-50">
                     <td className="px-4 py-1.5">{r.account_name}</td>
-                    <td className="px-4 py-1.5 text-right tabular-nums w-32">{formatYen(r.amount)}</td>
+                    <td className="px-4 py-1.5 text-right tabular-nums w-32 text-gray-500">{formatYen(r.opening_amount)}</td>
+                  <td className="px-4 py-1.5 text-right tabular-nums w-32">{formatYen(r.amount)}</td>
                   </tr>
                 ))}
                 {bs.liability_rows.length === 0 && (
-                  <tr><td colSpan={2} className="px-4 py-3 text-center text-gray-400">データなし</td></tr>
+                  <tr><td colSpan={3} className="px-4 py-3 text-center text-gray-400">データなし</td></tr>
                 )}
               </tbody>
               <tfoot>
                 <tr className="border-t-2 border-red-200 bg-red-50 font-semibold">
                   <td className="px-4 py-2">負債合計</td>
+                  <td className="px-4 py-2 text-right tabular-nums">{formatYen(bs.total_liabilities_opening)}</td>
                   <td className="px-4 py-2 text-right tabular-nums">{formatYen(bs.total_liabilities)}</td>
                 </tr>
               </tfoot>
@@ -451,15 +476,24 @@ function Page4BalanceSheet({ data, year }: { data: FS; year: number }) {
               <h4 className="text-sm font-semibold text-purple-800">純資産の部</h4>
             </div>
             <table className="w-full text-sm">
+              <thead>
+                <tr className="border-b border-gray-100 text-xs text-gray-500">
+                  <th className="px-4 py-1 text-left font-medium">科目</th>
+                  <th className="px-4 py-1 text-right font-medium">1月1日（期首）</th>
+                  <th className="px-4 py-1 text-right font-medium">12月31日（期末）</th>
+                </tr>
+              </thead>
               <tbody>
                 {bs.equity_rows.map((r) => (
                   <tr key={r.account_id} className="border-b border-gray-50">
                     <td className="px-4 py-1.5">{r.account_name}</td>
-                    <td className="px-4 py-1.5 text-right tabular-nums w-32">{formatYen(r.amount)}</td>
+                    <td className="px-4 py-1.5 text-right tabular-nums w-32 text-gray-500">{formatYen(r.opening_amount)}</td>
+                  <td className="px-4 py-1.5 text-right tabular-nums w-32">{formatYen(r.amount)}</td>
                   </tr>
                 ))}
                 <tr className="border-b border-gray-50">
                   <td className="px-4 py-1.5 italic text-gray-500">青色申告特別控除前の所得金額</td>
+                  <td></td>
                   <td className={`px-4 py-1.5 text-right tabular-nums ${bs.net_income < 0 ? "text-red-600" : ""}`}>
                     {formatYen(bs.net_income)}
                   </td>
@@ -468,6 +502,7 @@ function Page4BalanceSheet({ data, year }: { data: FS; year: number }) {
               <tfoot>
                 <tr className="border-t-2 border-purple-200 bg-purple-50 font-semibold">
                   <td className="px-4 py-2">純資産合計</td>
+                  <td className="px-4 py-2 text-right tabular-nums">{formatYen(bs.total_equity_opening)}</td>
                   <td className="px-4 py-2 text-right tabular-nums">{formatYen(bs.total_equity + bs.net_income)}</td>
                 </tr>
               </tfoot>
@@ -0,0 +1,401 @@
+import { useState, useEffect, useCallback } from "react";
+import type { Account, ExchangeRate, ForeignCurrencyReport, ForeignSide } from "../types";
+import {
+  formatYen,
+  formatScaled,
+  todayString,
+  FOREIGN_AMOUNT_SCALE,
+  RATE_SCALE,
+} from "../lib/format";
+import * as api from "../lib/api";
+
+interface Props {
+  accounts: Account[];
+  year: number;
+}
+
+/** 入力された小数を 10^scale 倍した整数にする（桁が多すぎるなど読み取れなければ null） */
+function toScaled(value: string, scale: number): bigint | null {
+  const match = value.trim().match(/^(\d*)(?:\.(\d*))?$/);
+  if (!match || (match[1] === "" && !match[2]) || (match[2] ?? "").length > scale) {
+    return null;
+  }
+  return BigInt((match[1] || "0") + (match[2] ?? "").padEnd(scale, "0"));
+}
+
+export default function ForeignCurrency({ accounts, year }: Props) {
+  const [rates, setRates] = useState<ExchangeRate[]>([]);
+  const [report, setReport] = useState<ForeignCurrencyReport | null>(null);
+  const [date, setDate] = useState(todayString());
+  const [debitAccountId, setDebitAccountId] = useState<number>(0);
+  const [creditAccountId, setCreditAccountId] = useState<number>(0);
+  const [description, setDescription] = useState("");
+  const [currency, setCurrency] = useState("USD");
+  const [foreignAmount, setForeignAmount] = useState("");
+  const [rate, setRate] = useState("");
+  const [foreignSide, setForeignSide] = useState<ForeignSide>("借方");
+  const [yenAmount, setYenAmount] = useState("");
+  const [error, setError] = useState("");
+  const [importMessage, setImportMessage] = useState("");
+
+  const activeAccounts = accounts.filter((a) => a.is_active);
+
+  const load = useCallback(() => {
+    api.getExchangeRates(undefined, year).then(setRates).catch(console.error);
+    api
+      .getForeignCurrencyReport(year)
+      .then(setReport)
+      .catch((err) => setError(String(err)));
+  }, [year]);
+
+  useEffect(() => {
+    load();
+  }, [load]);
+
+  // 取引日・通貨が変わったら直近の TTM を入れ直す
+  useEffect(() => {
+    if (!/^[A-Za-z]{3}$/.test(currency) || !date) return;
+    api
+      .getExchangeRate(currency.toUpperCase(), date)
+      .then((r) => setRate(r ? (r.ttm / 10 ** RATE_SCALE).toFixed(RATE_SCALE) : ""))
+      .catch(() => setRate(""));
+  }, [currency, date]);
+
+  async function handleImport(e: React.ChangeEvent<HTMLInputElement>) {
+    const file = e.target.files?.[0];
+    e.target.value = "";
+    if (!file) return;
+    setImportMessage("");
+    try {
+      const result = await api.importExchangeRates(await file.text());
+      if (result.errors.length > 0) {
+        setImportMessage(
+          "取り込めない行があるため、取り込みを中止しました:\n" +
+            result.errors.map((err) => `${err.row}行目: ${err.message}`).join("\n"),
+        );
+        return;
+      }
+      setImportMessage(`${result.imported}件の TTM を取り込みました`);
+      load();
+    } catch (err) {
+      setImportMessage(String(err));
+    }
+  }
+
+  // 円換算額の目安（サーバーと同じく整数で計算し、1円未満を切り捨てる）
+  const scaledForeign = toScaled(foreignAmount, FOREIGN_AMOUNT_SCALE);
+  const scaledRate = toScaled(rate, RATE_SCALE);
+  const convertedYen =
+    scaledForeign !== null && scaledRate !== null
+      ? Number((scaledForeign * scaledRate) / 10n ** BigInt(FOREIGN_AMOUNT_SCALE + RATE_SCALE))
+      : null;
+
+  async function handleSubmit(e: React.FormEvent) {
+    e.preventDefault();
+    setError("");
+    if (!debitAccountId || !creditAccountId) {
+      setError("借方・貸方の科目を選択してください");
+      return;
+    }
+    if (scaledForeign === null) {
+      setError("外貨額は小数点以下2桁までの数値で入力してください");
+      return;
+    }
+    if (rate.trim() !== "" && scaledRate === null) {
+      setError("換算レートは小数点以下4桁までの数値で入力してください");
+      return;
+    }
+    const amount = yenAmount === "" ? undefined : parseInt(yenAmount, 10);
+    if (amount !== undefined && (isNaN(amount) || amount <= 0)) {
+      setError("円換算額は1円以上で入力してください");
+      return;
+    }
+    try {
+      const result = await api.addForeignEntry({
+        date,
+        debitAccountId,
+        creditAccountId,
+        description,
+        currency: currency.toUpperCase(),
+        foreignAmount: foreignAmount.trim(),
+        exchangeRate: rate.trim() === "" ? undefined : rate.trim(),
+        foreignSide,
+        amount,
+      });
+      if (result.duplicates.length > 0) {
+        alert(
+          "登録しました。次の仕訳と重複していないか確認してください:\n" +
+            result.duplicates.map((d) => `${d.entry.date} ${d.entry.description}`).join("\n"),
+        );
+      }
+      setDescription("");
+      setForeignAmount("");
+      setYenAmount("");
+      load();
+    } catch (err) {
+      setError(String(err));
+    }
+  }
+
+  return (
+    <div className="space-y-4">
+      <h2 className="text-lg font-semibold text-gray-800">外貨建取引</h2>
+
+      <form
+        onSubmit={handleSubmit}
+        className="rounded-lg border border-gray-200 bg-white p-4 shadow-sm space-y-3"
+      >
+        <div className="flex flex-wrap items-end gap-3">
+          <div>
+            <label className="mb-1 block text-xs font-medium text-gray-500">
+              取引日
+            </label>
+            <input
+              type="date"
+              value={date}
+              onChange={(e) => setDate(e.target.value)}
+              className="rounded border border-gray-300 px-3 py-2 text-sm"
+            />
+          </div>
+          <div>
+            <label className="mb-1 block text-xs font-medium text-gray-500">
+              借方
+            </label>
+            <select
+              value={debitAccountId}
+              onChange={(e) => setDebitAccountId(Number(e.target.value))}
+              className="rounded border border-gray-300 px-3 py-2 text-sm"
+            >
+              <option value={0}>選択...</option>
+              {activeAccounts.map((a) => (
+                <option key={a.id} value={a.id}>
+                  {a.code} {a.name}
+                </option>
+              ))}
+            </select>
+          </div>
+          <div>
+            <label className="mb-1 block text-xs font-medium text-gray-500">
+              貸方
+            </label>
+            <select
+              value={creditAccountId}
+              onChange={(e) => setCreditAccountId(Number(e.target.value))}
+              className="rounded border border-gray-300 px-3 py-2 text-sm"
+            >
+              <option value={0}>選択...</option>
+              {activeAccounts.map((a) => (
+                <option key={a.id} value={a.id}>
+                  {a.code} {a.name}
+                </option>
+              ))}
+            </select>
+          </div>
+          <div className="flex-1">
+            <label className="mb-1 block text-xs font-medium text-gray-500">
+              摘要
+            </label>
+            <input
+              type="text"
+              value={description}
+              onChange={(e) => setDescription(e.target.value)}
+              className="w-full rounded border border-gray-300 px-3 py-2 text-sm"
+            />
+          </div>
+        </div>
+        <div className="flex flex-wrap items-end gap-3">
+          <div>
+            <label className="mb-1 block text-xs font-medium text-gray-500">
+              通貨
+            </label>
+            <input
+              type="text"
+              value={currency}
+              maxLength={3}
+              onChange={(e) => setCurrency(e.target.value.toUpperCase())}
+              className="w-20 rounded border border-gray-300 px-3 py-2 text-sm"
+            />
+          </div>
+          <div>
+            <label className="mb-1 block text-xs font-medium text-gray-500">
+              外貨建ての科目
+            </label>
+            <select
+              value={foreignSide}
+              onChange={(e) => setForeignSide(e.target.value as ForeignSide)}
+              className="rounded border border-gray-300 px-3 py-2 text-sm"
+            >
+              <option value="借方">借方</option>
+              <option value="貸方">貸方</option>
+              <option value="両方">両方</option>
+            </select>
+          </div>
+          <div>
+            <label className="mb-1 block text-xs font-medium text-gray-500">
+              外貨額
+            </label>
+            <input
+              type="number"
+              step="0.01"
+              min="0"
+              value={foreignAmount}
+              onChange={(e) => setForeignAmount(e.target.value)}
+              className="w-32 rounded border border-gray-300 px-3 py-2 text-sm"
+            />
+          </div>
+          <div>
+            <label className="mb-1 block text-xs font-medium text-gray-500">
+              換算レート（TTM）
+            </label>
+            <input
+              type="number"
+              step="0.0001"
+              min="0"
+              value={rate}
+              onChange={(e) => setRate(e.target.value)}
+              placeholder="未登録"
+              className="w-32 rounded border border-gray-300 px-3 py-2 text-sm"
+            />
+          </div>
+          <div>
+            <label className="mb-1 block text-xs font-medium text-gray-500">
+              円換算額（実際の受払額があれば入力）
+            </label>
+            <input
+              type="number"
+              min="1"
+              value={yenAmount}
+              onChange={(e) => setYenAmount(e.target.value)}
+              placeholder={convertedYen !== null ? String(convertedYen) : ""}
+              className="w-40 rounded border border-gray-300 px-3 py-2 text-sm"
+            />
+          </div>
+          <button
+            type="submit"
+            className="rounded bg-primary-600 px-4 py-2 text-sm text-white hover:bg-primary-700 transition"
+          >
+            登録
+          </button>
+        </div>
+        {error && <p className="text-sm text-red-600">{error}</p>}
+      </form>
+
+      <div className="rounded-lg border border-gray-200 bg-white shadow-sm">
+        <div className="border-b border-gray-200 px-5 py-3">
+          <h3 className="text-sm font-semibold text-gray-800">
+            {year}年末の外貨建資産・負債（12月31日の TTM で換算）
+          </h3>
+        </div>
+        <table className="w-full text-sm">
+          <thead>
+            <tr className="border-b border-gray-100 bg-gray-50 text-left text-xs font-medium text-gray-500">
+              <th className="px-4 py-2">科目</th>
+              <th className="px-4 py-2">通貨</th>
+              <th className="px-4 py-2 text-right">外貨残高</th>
+              <th className="px-4 py-2 text-right">帳簿価額</th>
+              <th className="px-4 py-2 text-right">年末 TTM</th>
+              <th className="px-4 py-2 text-right">年末換算額</th>
+              <th className="px-4 py-2 text-right">換算差額</th>
+            </tr>
+          </thead>
+          <tbody>
+            {!report || report.positions.length === 0 ? (
+              <tr>
+                <td colSpan={7} className="px-4 py-8 text-center text-gray-400">
+                  外貨建ての残高はありません
+                </td>
+              </tr>
+            ) : (
+              report.positions.map((p) => (
+                <tr
+                  key={`${p.account_id}-${p.currency}`}
+                  className="border-b border-gray-50 hover:bg-gray-50"
+                >
+                  <td className="px-4 py-2">
+                    {p.account_code} {p.account_name}
+                  </td>
+                  <td className="px-4 py-2">{p.currency}</td>
+                  <td className="px-4 py-2 text-right tabular-nums">
+                    {formatScaled(p.foreign_balance, FOREIGN_AMOUNT_SCALE)}
+                  </td>
+                  <td className="px-4 py-2 text-right tabular-nums">
+                    {formatYen(p.book_amount)}
+                  </td>
+                  <td className="px-4 py-2 text-right tabular-nums">
+                    {p.year_end_rate !== null ? (
+                      formatScaled(p.year_end_rate, RATE_SCALE)
+                    ) : (
+                      <span className="text-red-600">未登録</span>
+                    )}
+                  </td>
+                  <td className="px-4 py-2 text-right tabular-nums">
+                    {p.year_end_amount !== null ? formatYen(p.year_end_amount) : ""}
+                  </td>
+                  <td
+                    className={`px-4 py-2 text-right tabular-nums ${
+                      p.difference && p.difference < 0 ? "text-red-600" : ""
+                    }`}
+                  >
+                    {p.difference !== null ? formatYen(p.difference) : ""}
+                  </td>
+                </tr>
+              ))
+            )}
+          </tbody>
+        </table>
+        {report && (
+          <div className="flex justify-end gap-6 border-t border-gray-200 px-5 py-2 text-sm">
+            <span>
+              為替差益 <span className="tabular-nums">{formatYen(report.exchange_gain)}</span>
+            </span>
+            <span>
+              為替差損 <span className="tabular-nums">{formatYen(report.exchange_loss)}</span>
+            </span>
+          </div>
+        )}
+      </div>
+
+      <div className="rounded-lg border border-gray-200 bg-white shadow-sm">
+        <div className="flex items-center justify-between border-b border-gray-200 px-5 py-3">
+          <h3 className="text-sm font-semibold text-gray-800">
+            {year}年の TTM（{rates.length}件）
+          </h3>
+          <label className="cursor-pointer rounded border border-gray-300 bg-white px-4 py-1.5 text-sm hover:bg-gray-50 transition">
+            CSV から取り込む
+            <input type="file" accept=".csv,text/csv" onChange={handleImport} className="hidden" />
+          </label>
+        </div>
+        {importMessage && (
+          <p className="whitespace-pre-line border-b border-gray-100 px-5 py-2 text-sm text-gray-700">
+            {importMessage}
+          </p>
+        )}
+        <p className="px-5 py-2 text-xs text-gray-500">
+          CSV は「日付,通貨,TTM」の3列（例: 2024-12-30,USD,157.20）。1行目の見出しは読み飛ばします
+        </p>
+        <div className="max-h-80 overflow-y-auto">
+          <table className="w-full text-sm">
+            <thead>
+              <tr className="border-b border-gray-100 bg-gray-50 text-left text-xs font-medium text-gray-500">
+                <th className="px-4 py-2">日付</th>
+                <th className="px-4 py-2">通貨</th>
+                <th className="px-4 py-2 text-right">TTM</th>
+              </tr>
+            </thead>
+            <tbody>
+              {rates.map((r) => (
+                <tr key={`${r.currency}-${r.date}`} className="border-b border-gray-50">
+                  <td className="px-4 py-2 whitespace-nowrap">{r.date}</td>
+                  <td className="px-4 py-2">{r.currency}</td>
+                  <td className="px-4 py-2 text-right tabular-nums">
+                    {formatScaled(r.ttm, RATE_SCALE)}
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,340 @@
+import { useState, useEffect, useCallback } from "react";
+import type { Account, AgingReport, ItemMatch, UnmatchedItem } from "../types";
+import { formatYen, todayString, currentYear } from "../lib/format";
+import * as api from "../lib/api";
+
+interface Props {
+  accounts: Account[];
+  year: number;
+}
+
+function itemKey(item: UnmatchedItem): string {
+  return `${item.entry_id}-${item.account_id}`;
+}
+
+function ItemTable({
+  items,
+  selected,
+  onSelect,
+  showAge,
+  emptyMessage,
+}: {
+  items: UnmatchedItem[];
+  selected: UnmatchedItem | null;
+  onSelect: (item: UnmatchedItem) => void;
+  showAge: boolean;
+  emptyMessage: string;
+}) {
+  return (
+    <table className="w-full text-sm">
+      <thead>
+        <tr className="border-b border-gray-100 bg-gray-50 text-left text-xs font-medium text-gray-500">
+          <th className="px-4 py-2 text-right">No.</th>
+          <th className="px-4 py-2">日付</th>
+          <th className="px-4 py-2">科目</th>
+          <th className="px-4 py-2">摘要</th>
+          <th className="px-4 py-2 text-right">金額</th>
+          <th className="px-4 py-2 text-right">未消込額</th>
+          {showAge && <th className="px-4 py-2 text-right">経過日数</th>}
+        </tr>
+      </thead>
+      <tbody>
+        {items.length === 0 ? (
+          <tr>
+            <td colSpan={showAge ? 7 : 6} className="px-4 py-8 text-center text-gray-400">
+              {emptyMessage}
+            </td>
+          </tr>
+        ) : (
+          items.map((item) => (
+            <tr
+              key={itemKey(item)}
+              onClick={() => onSelect(item)}
+              className={`cursor-pointer border-b border-gray-50 ${
+                selected && itemKey(selected) === itemKey(item)
+                  ? "bg-primary-50"
+                  : "hover:bg-gray-50"
+              }`}
+            >
+              <td className="px-4 py-2 text-right tabular-nums text-gray-400">
+                {item.voucher_no}
+              </td>
+              <td className="px-4 py-2 whitespace-nowrap">{item.date}</td>
+              <td className="px-4 py-2 whitespace-nowrap">{item.account_name}</td>
+              <td className="px-4 py-2 text-gray-600">
+                {item.description}
+                {item.partner_name && (
+                  <span className="ml-2 text-xs text-gray-400">{item.partner_name}</span>
+                )}
+              </td>
+              <td className="px-4 py-2 text-right tabular-nums">
+                {formatYen(item.amount)}
+              </td>
+              <td className="px-4 py-2 text-right tabular-nums font-medium">
+                {formatYen(item.remaining)}
+              </td>
+              {showAge && (
+                <td
+                  className={`px-4 py-2 text-right tabular-nums ${
+                    item.age_days > 90 ? "text-red-600" : ""
+                  }`}
+                >
+                  {item.age_days}日
+                </td>
+              )}
+            </tr>
+          ))
+        )}
+      </tbody>
+    </table>
+  );
+}
+
+export default function ItemMatching({ accounts, year }: Props) {
+  const [asOf, setAsOf] = useState(
+    year === currentYear() ? todayString() : `${year}-12-31`,
+  );
+  const [accountId, setAccountId] = useState<number>(0);
+  const [report, setReport] = useState<AgingReport | null>(null);
+  const [debit, setDebit] = useState<UnmatchedItem | null>(null);
+  const [credit, setCredit] = useState<UnmatchedItem | null>(null);
+  const [amount, setAmount] = useState("");
+  const [matches, setMatches] = useState<ItemMatch[]>([]);
+  const [error, setError] = useState("");
+
+  const matchingAccounts = accounts.filter((a) => a.is_matching);
+
+  useEffect(() => {
+    setAsOf(year === currentYear() ? todayString() : `${year}-12-31`);
+  }, [year]);
+
+  const load = useCallback(() => {
+    setError("");
+    api
+      .getAgingReport(asOf, accountId || undefined)
+      .then(setReport)
+      .catch((err) => setError(String(err)));
+  }, [asOf, accountId]);
+
+  useEffect(() => {
+    load();
+  }, [load]);
+
+  const loadMatches = useCallback(() => {
+    if (!debit) {
+      setMatches([]);
+      return;
+    }
+    api
+      .getItemMatches(debit.entry_id)
+      .then((list) => setMatches(list.filter((m) => m.account_id === debit.account_id)))
+      .catch(console.error);
+  }, [debit]);
+
+  useEffect(() => {
+    loadMatches();
+  }, [loadMatches]);
+
+  function selectDebit(item: UnmatchedItem) {
+    setDebit(item);
+    if (credit && credit.account_id !== item.account_id) setCredit(null);
+  }
+
+  function selectCredit(item: UnmatchedItem) {
+    setCredit(item);
+    if (debit && debit.account_id !== item.account_id) setDebit(null);
+  }
+
+  const limit = debit && credit ? Math.min(debit.remaining, credit.remaining) : 0;
+
+  async function handleMatch() {
+    if (!debit || !credit) return;
+    setError("");
+    const value = amount === "" ? undefined : parseInt(amount, 10);
+    if (value !== undefined && (isNaN(value) || value <= 0 || value > limit)) {
+      setError(`消込額は1円以上${formatYen(limit)}以下で入力してください`);
+      return;
+    }
+    try {
+      await api.matchItems({
+        accountId: debit.account_id,
+        debitEntryId: debit.entry_id,
+        creditEntryId: credit.entry_id,
+        amount: value,
+      });
+      setCredit(null);
+      setAmount("");
+      setDebit(null);
+      load();
+    } catch (err) {
+      setError(String(err));
+    }
+  }
+
+  async function handleUnmatch(match: ItemMatch) {
+    if (!confirm(`${match.credit_date}「${match.credit_description}」との消込を解除しますか？`)) return;
+    try {
+      await api.unmatchItems(match.id);
+      loadMatches();
+      load();
+    } catch (err) {
+      alert(String(err));
+    }
+  }
+
+  return (
+    <div className="space-y-4">
+      <div className="flex flex-wrap items-end justify-between gap-3">
+        <h2 className="text-lg font-semibold text-gray-800">消込・年齢表</h2>
+        <div className="flex items-end gap-3">
+          <div>
+            <label className="mb-1 block text-xs font-medium text-gray-500">
+              科目
+            </label>
+            <select
+              value={accountId}
+              onChange={(e) => setAccountId(Number(e.target.value))}
+              className="rounded border border-gray-300 px-3 py-2 text-sm"
+            >
+              <option value={0}>すべて</option>
+              {matchingAccounts.map((a) => (
+                <option key={a.id} value={a.id}>
+                  {a.code} {a.name}
+                </option>
+              ))}
+            </select>
+          </div>
+          <div>
+            <label className="mb-1 block text-xs font-medium text-gray-500">
+              基準日
+            </label>
+            <input
+              type="date"
+              value={asOf}
+              onChange={(e) => setAsOf(e.target.value)}
+              className="rounded border border-gray-300 px-3 py-2 text-sm"
+            />
+          </div>
+        </div>
+      </div>
+
+      {error && <p className="text-sm text-red-600">{error}</p>}
+
+      {report && (
+        <div className="grid grid-cols-2 gap-3 md:grid-cols-6">
+          {report.buckets.map((b) => (
+            <div
+              key={b.label}
+              className="rounded-lg border border-gray-200 bg-white px-4 py-3 shadow-sm"
+            >
+              <div className="text-xs text-gray-500">
+                {b.label}（{b.count}件）
+              </div>
+              <div className="text-right tabular-nums font-medium">
+                {formatYen(b.amount)}
+              </div>
+            </div>
+          ))}
+          <div className="rounded-lg border border-gray-300 bg-gray-50 px-4 py-3 shadow-sm">
+            <div className="text-xs text-gray-500">未消込の合計</div>
+            <div className="text-right tabular-nums font-semibold">
+              {formatYen(report.total)}
+            </div>
+          </div>
+        </div>
+      )}
+
+      <div className="rounded-lg border border-gray-200 bg-white shadow-sm">
+        <div className="border-b border-gray-200 px-5 py-3">
+          <h3 className="text-sm font-semibold text-gray-800">
+            未回収・未精算（{asOf}時点）
+          </h3>
+        </div>
+        <ItemTable
+          items={report?.items ?? []}
+          selected={debit}
+          onSelect={selectDebit}
+          showAge
+          emptyMessage="未消込の残高はありません"
+        />
+      </div>
+
+      <div className="rounded-lg border border-gray-200 bg-white shadow-sm">
+        <div className="border-b border-gray-200 px-5 py-3">
+          <h3 className="text-sm font-semibold text-gray-800">
+            消込先が決まっていない入金・精算
+          </h3>
+        </div>
+        <ItemTable
+          items={report?.unapplied_credits ?? []}
+          selected={credit}
+          onSelect={selectCredit}
+          showAge={false}
+          emptyMessage="消込先が決まっていない入金・精算はありません"
+        />
+      </div>
+
+      {debit && (
+        <div className="rounded-lg border border-gray-200 bg-white p-4 shadow-sm space-y-3">
+          <div className="flex flex-wrap items-end gap-3 text-sm">
+            <div className="flex-1">
+              <span className="text-gray-500">借方 </span>
+              {debit.date} {debit.description}（{formatYen(debit.remaining)}）
+              <span className="mx-2 text-gray-400">←</span>
+              <span className="text-gray-500">貸方 </span>
+              {credit
+                ? `${credit.date} ${credit.description}（${formatYen(credit.remaining)}）`
+                : "入金・精算を選択してください"}
+            </div>
+            <input
+              type="number"
+              min="1"
+              value={amount}
+              onChange={(e) => setAmount(e.target.value)}
+              placeholder={credit ? String(limit) : "消込額"}
+              className="w-32 rounded border border-gray-300 px-3 py-2 text-sm"
+            />
+            <button
+              onClick={handleMatch}
+              disabled={!credit}
+              className="rounded bg-primary-600 px-4 py-2 text-sm text-white hover:bg-primary-700 disabled:opacity-50 transition"
+            >
+              消込
+            </button>
+          </div>
+          {matches.length > 0 && (
+            <table className="w-full text-sm">
+              <thead>
+                <tr className="border-b border-gray-100 bg-gray-50 text-left text-xs font-medium text-gray-500">
+                  <th className="px-4 py-2">消込済みの入金・精算</th>
+                  <th className="px-4 py-2 text-right">消込額</th>
+                  <th className="px-4 py-2"></th>
+                </tr>
+              </thead>
+              <tbody>
+                {matches.map((m) => (
+                  <tr key={m.id} className="border-b border-gray-50">
+                    <td className="px-4 py-2">
+                      {m.credit_date} {m.credit_description}
+                    </td>
+                    <td className="px-4 py-2 text-right tabular-nums">
+                      {formatYen(m.amount)}
+                    </td>
+                    <td className="px-4 py-2 text-right">
+                      <button
+                        onClick={() => handleUnmatch(m)}
+                        className="text-xs text-red-500 hover:text-red-700"
+                      >
+                        解除
+                      </button>
+                    </td>
+                  </tr>
+                ))}
+              </tbody>
+            </table>
+          )}
+        </div>
+      )}
+    </div>
+  );
+}
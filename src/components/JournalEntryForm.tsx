@@ -1,5 +1,5 @@
-import { useState } from "react";
-import type { Account } from "../types";
+import { useState, useEffect } from "react";
+import type { Account, AccountSuggestion, DuplicatePair } from "../types";
 import { todayString } from "../lib/format";
 import * as api from "../lib/api";
 
@@ -50,9 +50,17 @@ export default function JournalEntryForm({ accounts, onSaved }: Props) {
   const [description, setDescription] = useState("");
   const [error, setError] = useState("");
   const [saving, setSaving] = useState(false);
+  const [partnerId, setPartnerId] = useState<number | null>(null);
+  const [suggestions, setSuggestions] = useState<AccountSuggestion[]>([]);
+  const [duplicates, setDuplicates] = useState<DuplicatePair[]>([]);
+  const [reviewLater, setReviewLater] = useState(false);
+  const [note, setNote] = useState("");
+
+  // 非表示にした科目は選択肢に出さない
+  const activeAccounts = accounts.filter((a) => a.is_active);
 
   function findAccountByCode(code: number): Account | undefined {
-    return accounts.find((a) => a.code === code);
+    return activeAccounts.find((a) => a.code === code);
   }
 
   function applyTemplate(t: (typeof templates)[0]) {
@@ -63,6 +71,28 @@ export default function JournalEntryForm({ accounts, onSaved }: Props) {
     setDescription(t.desc);
   }
 
+  // 摘要・金額の入力が止まったら過去の仕訳から科目の候補を取得する
+  useEffect(() => {
+    const amountNum = parseInt(amount, 10);
+    if (!description.trim()) {
+      setSuggestions([]);
+      return;
+    }
+    const timer = setTimeout(() => {
+      api
+        .suggestAccounts(description, isNaN(amountNum) ? undefined : amountNum)
+        .then(setSuggestions)
+        .catch(console.error);
+    }, 300);
+    return () => clearTimeout(timer);
+  }, [description, amount]);
+
+  function applySuggestion(s: AccountSuggestion) {
+    setDebitAccountId(s.debit_account_id);
+    setCreditAccountId(s.credit_account_id);
+    setPartnerId(s.partner_id);
+  }
+
   async function handleSubmit(e: React.FormEvent) {
     e.preventDefault();
     setError("");
@@ -86,18 +116,27 @@ export default function JournalEntryForm({ accounts, onSaved }: Props) {
     }
 
     setSaving(true);
+    setDuplicates([]);
     try {
-      await api.addEntry({
+      const result = await api.addEntry({
         date,
         debitAccountId,
         debitAmount: amountNum,
         creditAccountId,
         creditAmount: amountNum,
         description,
+        partnerId: partnerId ?? undefined,
       });
+      if (reviewLater || note) {
+        await api.updateEntryReview(result.id, reviewLater ? "未確認" : "確認済", note);
+      }
       // reset form
       setAmount("");
       setDescription("");
+      setPartnerId(null);
+      setReviewLater(false);
+      setNote("");
+      setDuplicates(result.duplicates);
       onSaved();
     } catch (err) {
       setError(String(err));
@@ -150,7 +189,7 @@ export default function JournalEntryForm({ accounts, onSaved }: Props) {
               className="w-full rounded border border-gray-300 px-3 py-2 text-sm focus:border-primary-500 focus:outline-none focus:ring-1 focus:ring-primary-500"
             >
               <option value={0}>-- 選択 --</option>
-              {accounts.map((a) => (
+              {activeAccounts.map((a) => (
                 <option key={a.id} value={a.id}>
                   {a.code} {a.name}
                 </option>
@@ -169,7 +208,7 @@ export default function JournalEntryForm({ accounts, onSaved }: Props) {
               className="w-full rounded border border-gray-300 px-3 py-2 text-sm focus:border-primary-500 focus:outline-none focus:ring-1 focus:ring-primary-500"
             >
               <option value={0}>-- 選択 --</option>
-              {accounts.map((a) => (
+              {activeAccounts.map((a) => (
                 <option key={a.id} value={a.id}>
                   {a.code} {a.name}
                 </option>
@@ -208,17 +247,78 @@ export default function JournalEntryForm({ accounts, onSaved }: Props) {
           </div>
         </div>
 
+        {/* 過去の仕訳からの候補 */}
+        {suggestions.length > 0 && (
+          <div className="flex flex-wrap items-center gap-2 text-xs">
+            <span className="text-gray-500">候補:</span>
+            {suggestions.map((s) => (
+              <button
+                key={`${s.debit_account_id}-${s.credit_account_id}-${s.partner_id}`}
+                type="button"
+                onClick={() => applySuggestion(s)}
+                title={`${s.match_count}件 / 最終 ${s.last_used}`}
+                className={`rounded-full border px-3 py-1 transition ${
+                  debitAccountId === s.debit_account_id &&
+                  creditAccountId === s.credit_account_id &&
+                  partnerId === s.partner_id
+                    ? "border-primary-500 bg-primary-50 text-primary-700"
+                    : "border-gray-300 text-gray-600 hover:bg-gray-50"
+                }`}
+              >
+                {s.debit_account_name} / {s.credit_account_name}
+                {s.partner_name && `（${s.partner_name}）`}
+              </button>
+            ))}
+          </div>
+        )}
+
         {error && (
           <p className="text-sm font-medium text-red-600">{error}</p>
         )}
 
-        <button
-          type="submit"
-          disabled={saving}
-          className="rounded bg-primary-600 px-5 py-2 text-sm font-medium text-white hover:bg-primary-700 disabled:opacity-50 transition"
-        >
-          {saving ? "保存中..." : "仕訳を登録"}
-        </button>
+        {/* 登録した仕訳と重複の疑いがある仕訳（登録は完了している） */}
+        {duplicates.length > 0 && (
+          <div className="rounded border border-yellow-300 bg-yellow-50 p-3 text-xs text-yellow-800">
+            <p className="font-medium">
+              登録しました。次の仕訳と重複していないか確認してください
+              （重複していれば仕訳帳から削除してください）
+            </p>
+            <ul className="mt-1 list-disc pl-4">
+              {/* 登録した仕訳は ID が最も新しいので、entry 側が既存の仕訳 */}
+              {duplicates.map((d) => (
+                <li key={`${d.entry.id}-${d.duplicate.id}`}>
+                  {d.entry.date} No.{d.entry.voucher_no} {d.entry.description}
+                  （{d.reason}）
+                </li>
+              ))}
+            </ul>
+          </div>
+        )}
+
+        <div className="flex flex-wrap items-center gap-4">
+          <button
+            type="submit"
+            disabled={saving}
+            className="rounded bg-primary-600 px-5 py-2 text-sm font-medium text-white hover:bg-primary-700 disabled:opacity-50 transition"
+          >
+            {saving ? "保存中..." : "仕訳を登録"}
+          </button>
+          <label className="flex items-center gap-1 text-sm text-gray-600">
+            <input
+              type="checkbox"
+              checked={reviewLater}
+              onChange={(e) => setReviewLater(e.target.checked)}
+            />
+            後で確認する
+          </label>
+          <input
+            type="text"
+            value={note}
+            onChange={(e) => setNote(e.target.value)}
+            placeholder="メモ（摘要とは別に仕訳帳に表示されます）"
+            className="flex-1 rounded border border-gray-300 px-3 py-2 text-sm"
+          />
+        </div>
       </form>
     </div>
   );
@@ -1,10 +1,26 @@
-import { useState } from "react";
-import type { JournalEntry } from "../types";
-import { formatYen } from "../lib/format";
+import { useState, useEffect } from "react";
+import { open } from "@tauri-apps/api/dialog";
+import type { JournalEntry, EntrySortKey, InvalidDate } from "../types";
+import { formatYen, formatScaled, FOREIGN_AMOUNT_SCALE, RATE_SCALE } from "../lib/format";
 import * as api from "../lib/api";
 
+const SORT_LABELS: Record<EntrySortKey, string> = {
+  date: "日付順",
+  voucher_no: "伝票番号順",
+  amount: "金額順",
+  account_code: "科目コード順",
+  created_at: "登録順",
+};
+
 interface Props {
   entries: JournalEntry[];
+  totalCount: number;
+  offset: number;
+  pageSize: number;
+  onOffsetChange: (offset: number) => void;
+  sort: EntrySortKey;
+  descending: boolean;
+  onSortChange: (sort: EntrySortKey, descending: boolean) => void;
   year: number;
   month: number | null;
   onMonthChange: (month: number | null) => void;
@@ -14,6 +30,13 @@ interface Props {
 
 export default function JournalEntryList({
   entries,
+  totalCount,
+  offset,
+  pageSize,
+  onOffsetChange,
+  sort,
+  descending,
+  onSortChange,
   year,
   month,
   onMonthChange,
@@ -21,6 +44,11 @@ export default function JournalEntryList({
   onExportCsv,
 }: Props) {
   const [deleting, setDeleting] = useState<number | null>(null);
+  const [invalidDates, setInvalidDates] = useState<InvalidDate[]>([]);
+
+  useEffect(() => {
+    api.getInvalidDates().then(setInvalidDates).catch(console.error);
+  }, []);
 
   async function handleDelete(id: number) {
     if (!confirm("この仕訳を削除しますか？")) return;
@@ -35,6 +63,46 @@ export default function JournalEntryList({
     }
   }
 
+  async function handleReverse(entry: JournalEntry) {
+    const date = prompt(
+      "赤黒訂正（取消仕訳）の日付を入力してください",
+      entry.date,
+    );
+    if (!date) return;
+    try {
+      await api.reverseEntry(entry.id, date);
+      onDeleted();
+    } catch (err) {
+      alert(String(err));
+    }
+  }
+
+  async function handleAttach(entry: JournalEntry) {
+    const path = await open({
+      title: "証憑ファイルを選択",
+      filters: [
+        { name: "証憑", extensions: ["pdf", "jpg", "jpeg", "png", "heic", "tif", "tiff"] },
+        { name: "すべてのファイル", extensions: ["*"] },
+      ],
+    });
+    if (typeof path !== "string") return;
+    try {
+      const { attachment, reused } = await api.attachFile({
+        path,
+        entryIds: [entry.id],
+        receivedDate: entry.date,
+        memo: "",
+      });
+      alert(
+        reused
+          ? `同じ内容の証憑「${attachment.file_name}」が登録済みのため、それを添付しました（受領日 ${attachment.received_date}）`
+          : `証憑「${attachment.file_name}」を添付しました`,
+      );
+    } catch (err) {
+      alert(String(err));
+    }
+  }
+
   const totalDebit = entries.reduce((s, e) => s + e.debit_amount, 0);
   const totalCredit = entries.reduce((s, e) => s + e.credit_amount, 0);
 
@@ -59,6 +127,25 @@ export default function JournalEntryList({
               </option>
             ))}
           </select>
+          <select
+            value={sort}
+            onChange={(e) =>
+              onSortChange(e.target.value as EntrySortKey, descending)
+            }
+            className="rounded border border-gray-300 px-2 py-1 text-sm"
+          >
+            {Object.entries(SORT_LABELS).map(([key, label]) => (
+              <option key={key} value={key}>
+                {label}
+              </option>
+            ))}
+          </select>
+          <button
+            onClick={() => onSortChange(sort, !descending)}
+            className="rounded border border-gray-300 px-2 py-1 text-sm text-gray-600 hover:bg-gray-50 transition"
+          >
+            {descending ? "降順" : "昇順"}
+          </button>
           <button
             onClick={onExportCsv}
             className="rounded border border-gray-300 px-3 py-1 text-sm text-gray-600 hover:bg-gray-50 transition"
@@ -68,23 +155,33 @@ export default function JournalEntryList({
         </div>
       </div>
 
+      {invalidDates.length > 0 && (
+        <div className="border-b border-red-100 bg-red-50 px-5 py-2 text-sm text-red-700">
+          日付を読み取れないデータが{invalidDates.length}件あり、期間の集計・検索に含まれていません:{" "}
+          {invalidDates
+            .map((d) => `${d.source} ID ${d.id}「${d.value}」`)
+            .join("、")}
+        </div>
+      )}
+
       <div className="overflow-x-auto">
         <table className="w-full text-sm">
           <thead>
             <tr className="border-b border-gray-100 bg-gray-50 text-left text-xs font-medium uppercase text-gray-500">
+              <th className="px-4 py-2 text-right">No.</th>
               <th className="px-4 py-2">日付</th>
               <th className="px-4 py-2">借方科目</th>
               <th className="px-4 py-2 text-right">借方金額</th>
               <th className="px-4 py-2">貸方科目</th>
               <th className="px-4 py-2 text-right">貸方金額</th>
               <th className="px-4 py-2">摘要</th>
-              <th className="px-4 py-2 w-16"></th>
+              <th className="px-4 py-2 w-24"></th>
             </tr>
           </thead>
           <tbody>
             {entries.length === 0 ? (
               <tr>
-                <td colSpan={7} className="px-4 py-8 text-center text-gray-400">
+                <td colSpan={8} className="px-4 py-8 text-center text-gray-400">
                   仕訳データがありません
                 </td>
               </tr>
@@ -94,19 +191,75 @@ export default function JournalEntryList({
                   key={entry.id}
                   className="border-b border-gray-50 hover:bg-gray-50 transition"
                 >
+                  <td className="px-4 py-2 text-right tabular-nums text-gray-400">
+                    {entry.voucher_no}
+                  </td>
                   <td className="px-4 py-2 whitespace-nowrap">{entry.date}</td>
-                  <td className="px-4 py-2">{entry.debit_account_name}</td>
+                  <td className="px-4 py-2">
+                    {entry.is_compound ? "諸口" : entry.debit_account_name}
+                  </td>
                   <td className="px-4 py-2 text-right tabular-nums">
                     {formatYen(entry.debit_amount)}
                   </td>
-                  <td className="px-4 py-2">{entry.credit_account_name}</td>
+                  <td className="px-4 py-2">
+                    {entry.is_compound ? "諸口" : entry.credit_account_name}
+                  </td>
                   <td className="px-4 py-2 text-right tabular-nums">
                     {formatYen(entry.credit_amount)}
                   </td>
                   <td className="px-4 py-2 text-gray-500">
                     {entry.description}
+                    {entry.currency && (
+                      <span
+                        title={
+                          entry.exchange_rate !== null
+                            ? `TTM ${formatScaled(entry.exchange_rate, RATE_SCALE)}`
+                            : undefined
+                        }
+                        className="ml-2 rounded bg-blue-50 px-1.5 py-0.5 text-xs text-blue-700"
+                      >
+                        {entry.currency}{" "}
+                        {entry.foreign_amount !== null &&
+                          formatScaled(entry.foreign_amount, FOREIGN_AMOUNT_SCALE)}
+                      </span>
+                    )}
+                    {entry.review_status !== "確認済" && (
+                      <span
+                        className={`ml-2 rounded px-1.5 py-0.5 text-xs ${
+                          entry.review_status === "要質問"
+                            ? "bg-red-50 text-red-600"
+                            : "bg-yellow-50 text-yellow-700"
+                        }`}
+                      >
+                        {entry.review_status}
+                      </span>
+                    )}
+                    {entry.reversed_by !== null && (
+                      <span className="ml-2 rounded bg-red-50 px-1.5 py-0.5 text-xs text-red-600">
+                        取消済
+                      </span>
+                    )}
+                    {entry.note && (
+                      <p className="mt-0.5 text-xs whitespace-pre-wrap text-gray-400">
+                        メモ: {entry.note}
+                      </p>
+                    )}
                   </td>
-                  <td className="px-4 py-2">
+                  <td className="px-4 py-2 whitespace-nowrap space-x-2">
+                    {entry.reversal_of === null && entry.reversed_by === null && (
+                      <button
+                        onClick={() => handleReverse(entry)}
+                        className="text-xs text-gray-500 hover:text-gray-700"
+                      >
+                        赤黒
+                      </button>
+                    )}
+                    <button
+                      onClick={() => handleAttach(entry)}
+                      className="text-xs text-gray-500 hover:text-gray-700"
+                    >
+                      証憑
+                    </button>
                     <button
                       onClick={() => handleDelete(entry.id)}
                       disabled={deleting === entry.id}
@@ -122,7 +275,9 @@ export default function JournalEntryList({
           {entries.length > 0 && (
             <tfoot>
               <tr className="border-t-2 border-gray-200 bg-gray-50 font-semibold">
-                <td className="px-4 py-2">合計</td>
+                <td className="px-4 py-2" colSpan={2}>
+                  {totalCount > entries.length ? "このページの合計" : "合計"}
+                </td>
                 <td></td>
                 <td className="px-4 py-2 text-right tabular-nums">
                   {formatYen(totalDebit)}
@@ -137,6 +292,30 @@ export default function JournalEntryList({
           )}
         </table>
       </div>
+
+      {totalCount > pageSize && (
+        <div className="flex items-center justify-between border-t border-gray-200 px-5 py-3 text-sm">
+          <span className="text-gray-600">
+            {totalCount}件中 {offset + 1}〜{offset + entries.length}件
+          </span>
+          <div className="flex gap-2">
+            <button
+              onClick={() => onOffsetChange(Math.max(0, offset - pageSize))}
+              disabled={offset === 0}
+              className="rounded border border-gray-300 px-3 py-1 text-xs hover:bg-gray-50 disabled:opacity-40"
+            >
+              前へ
+            </button>
+            <button
+              onClick={() => onOffsetChange(offset + pageSize)}
+              disabled={offset + pageSize >= totalCount}
+              className="rounded border border-gray-300 px-3 py-1 text-xs hover:bg-gray-50 disabled:opacity-40"
+            >
+              次へ
+            </button>
+          </div>
+        </div>
+      )}
     </div>
   );
 }
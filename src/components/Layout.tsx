@@ -1,5 +1,5 @@
 import { type ReactNode } from "react";
-import type { PageId } from "../types";
+import type { PageId, UndoStatus } from "../types";
 
 interface NavSection {
   title: string;
@@ -12,9 +12,16 @@ const navSections: NavSection[] = [
     items: [
       { id: "journal", label: "仕訳帳" },
       { id: "simple-entry", label: "かんたん入力" },
+      { id: "entry-search", label: "仕訳検索" },
+      { id: "duplicates", label: "重複チェック" },
+      { id: "open-items", label: "確認待ち" },
+      { id: "attachments", label: "証憑" },
       { id: "accounts", label: "勘定科目" },
       { id: "fixed-assets", label: "固定資産台帳" },
       { id: "rent-details", label: "地代家賃内訳" },
+      { id: "apportionment", label: "家事按分" },
+      { id: "foreign-currency", label: "外貨建取引" },
+      { id: "item-matching", label: "消込・年齢表" },
     ],
   },
   {
@@ -40,6 +47,10 @@ interface LayoutProps {
   year: number;
   onYearChange: (year: number) => void;
   onBackup: () => void;
+  undoStatus: UndoStatus | null;
+  onUndo: () => void;
+  onRedo: () => void;
+  onUndoHover: () => void;
   children: ReactNode;
 }
 
@@ -49,6 +60,10 @@ export default function Layout({
   year,
   onYearChange,
   onBackup,
+  undoStatus,
+  onUndo,
+  onRedo,
+  onUndoHover,
   children,
 }: LayoutProps) {
   return (
@@ -68,6 +83,22 @@ export default function Layout({
               className="w-24 rounded border border-primary-300 bg-primary-600 px-2 py-1 text-white placeholder-primary-300 focus:outline-none focus:ring-2 focus:ring-primary-300"
             />
           </label>
+          <div className="flex gap-1" onMouseEnter={onUndoHover}>
+            <button
+              onClick={onUndo}
+              title={undoStatus?.undo[0] ?? "元に戻せる操作はありません"}
+              className="rounded bg-primary-600 px-3 py-1 text-sm hover:bg-primary-500 transition"
+            >
+              元に戻す
+            </button>
+            <button
+              onClick={onRedo}
+              title={undoStatus?.redo[0] ?? "やり直せる操作はありません"}
+              className="rounded bg-primary-600 px-3 py-1 text-sm hover:bg-primary-500 transition"
+            >
+              やり直す
+            </button>
+          </div>
           <button
             onClick={onBackup}
             className="rounded bg-primary-500 px-3 py-1 text-sm hover:bg-primary-400 transition"
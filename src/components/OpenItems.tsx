@@ -0,0 +1,148 @@
+import { useState, useEffect, useCallback } from "react";
+import type { JournalEntry, OpenItemMonth, ReviewStatus } from "../types";
+import { formatYen } from "../lib/format";
+import * as api from "../lib/api";
+
+interface Props {
+  year: number;
+}
+
+const REVIEW_STATUSES: ReviewStatus[] = ["未確認", "要質問", "確認済"];
+
+const STATUS_STYLES: Record<ReviewStatus, string> = {
+  未確認: "bg-yellow-50 text-yellow-700",
+  要質問: "bg-red-50 text-red-700",
+  確認済: "bg-green-50 text-green-700",
+};
+
+function ReviewRow({ entry, onSaved }: { entry: JournalEntry; onSaved: () => void }) {
+  const [status, setStatus] = useState<ReviewStatus>(entry.review_status);
+  const [note, setNote] = useState(entry.note);
+  const [error, setError] = useState("");
+
+  async function save(nextStatus: ReviewStatus) {
+    setError("");
+    try {
+      await api.updateEntryReview(entry.id, nextStatus, note);
+      setStatus(nextStatus);
+      if (nextStatus === "確認済") onSaved();
+    } catch (err) {
+      setError(String(err));
+    }
+  }
+
+  return (
+    <tr className="border-b border-gray-50 align-top">
+      <td className="px-4 py-2 text-right tabular-nums text-gray-400">
+        {entry.voucher_no}
+      </td>
+      <td className="px-4 py-2 whitespace-nowrap">{entry.date}</td>
+      <td className="px-4 py-2">
+        <div>
+          {entry.is_compound
+            ? "諸口"
+            : `${entry.debit_account_name} / ${entry.credit_account_name}`}
+        </div>
+        <div className="text-gray-500">{entry.description}</div>
+      </td>
+      <td className="px-4 py-2 text-right tabular-nums">
+        {formatYen(entry.debit_amount)}
+      </td>
+      <td className="px-4 py-2">
+        <input
+          type="text"
+          value={note}
+          onChange={(e) => setNote(e.target.value)}
+          onBlur={() => note !== entry.note && save(status)}
+          placeholder="確認したい内容"
+          className="w-full rounded border border-gray-300 px-2 py-1 text-sm"
+        />
+        {error && <p className="mt-1 text-xs text-red-600">{error}</p>}
+      </td>
+      <td className="px-4 py-2">
+        <select
+          value={status}
+          onChange={(e) => save(e.target.value as ReviewStatus)}
+          className={`rounded border border-gray-300 px-2 py-1 text-sm ${STATUS_STYLES[status]}`}
+        >
+          {REVIEW_STATUSES.map((s) => (
+            <option key={s} value={s}>
+              {s}
+            </option>
+          ))}
+        </select>
+      </td>
+    </tr>
+  );
+}
+
+export default function OpenItems({ year }: Props) {
+  const [months, setMonths] = useState<OpenItemMonth[]>([]);
+  const [error, setError] = useState("");
+
+  const load = useCallback(() => {
+    setError("");
+    api
+      .getOpenItems(year)
+      .then(setMonths)
+      .catch((err) => setError(String(err)));
+  }, [year]);
+
+  useEffect(() => {
+    load();
+  }, [load]);
+
+  const total = months.reduce((s, m) => s + m.entries.length, 0);
+
+  return (
+    <div className="space-y-4">
+      <div className="flex items-center justify-between">
+        <h2 className="text-lg font-semibold text-gray-800">
+          確認待ちの仕訳（{year}年）
+        </h2>
+        <span className="text-sm text-gray-500">
+          {total > 0 ? `${total}件` : ""}
+        </span>
+      </div>
+
+      {error && <p className="text-sm text-red-600">{error}</p>}
+
+      {months.length === 0 ? (
+        <div className="rounded-lg border border-gray-200 bg-white px-4 py-8 text-center text-sm text-gray-400 shadow-sm">
+          確認待ちの仕訳はありません
+        </div>
+      ) : (
+        months.map((m) => (
+          <div
+            key={m.month}
+            className="rounded-lg border border-gray-200 bg-white shadow-sm"
+          >
+            <div className="flex items-center justify-between border-b border-gray-200 px-5 py-2">
+              <h3 className="text-sm font-semibold text-gray-800">{m.month}月</h3>
+              <span className="text-xs text-gray-500">
+                未確認 {m.unconfirmed_count}件・要質問 {m.question_count}件
+              </span>
+            </div>
+            <table className="w-full text-sm">
+              <thead>
+                <tr className="border-b border-gray-100 bg-gray-50 text-left text-xs font-medium text-gray-500">
+                  <th className="px-4 py-2 text-right">No.</th>
+                  <th className="px-4 py-2">日付</th>
+                  <th className="px-4 py-2">仕訳</th>
+                  <th className="px-4 py-2 text-right">金額</th>
+                  <th className="px-4 py-2">メモ</th>
+                  <th className="px-4 py-2">確認状況</th>
+                </tr>
+              </thead>
+              <tbody>
+                {m.entries.map((entry) => (
+                  <ReviewRow key={entry.id} entry={entry} onSaved={load} />
+                ))}
+              </tbody>
+            </table>
+          </div>
+        ))
+      )}
+    </div>
+  );
+}
@@ -1,5 +1,5 @@
 import { useState, useEffect } from "react";
-import type { ProfitLoss as PL } from "../types";
+import type { ProfitLoss as PL, SegmentProfitLoss } from "../types";
 import { formatYen } from "../lib/format";
 import * as api from "../lib/api";
 
@@ -8,26 +8,56 @@ interface Props {
 }
 
 export default function ProfitLoss({ year }: Props) {
-  const [data, setData] = useState<PL | null>(null);
+  const [total, setTotal] = useState<PL | null>(null);
+  const [segments, setSegments] = useState<SegmentProfitLoss[]>([]);
+  // "" = 全体, "none" = 未割当, それ以外はセグメントID
+  const [segmentKey, setSegmentKey] = useState("");
   const [loading, setLoading] = useState(true);
 
   useEffect(() => {
     setLoading(true);
-    api
-      .getProfitLoss(year)
-      .then(setData)
+    Promise.all([api.getProfitLoss(year), api.getSegmentProfitLoss(year)])
+      .then(([pl, bySegment]) => {
+        setTotal(pl);
+        setSegments(bySegment);
+      })
       .catch((err) => alert(String(err)))
       .finally(() => setLoading(false));
   }, [year]);
 
+  const keyOf = (s: SegmentProfitLoss) =>
+    s.segment_id === null ? "none" : String(s.segment_id);
+  const data =
+    segmentKey === ""
+      ? total
+      : (segments.find((s) => keyOf(s) === segmentKey)?.profit_loss ?? null);
+  // 未割当だけの場合はセグメントを使っていないので切替を出さない
+  const hasSegments = segments.some((s) => s.segment_id !== null);
+
   if (loading) return <p className="text-sm text-gray-400">読み込み中...</p>;
   if (!data) return <p className="text-sm text-gray-400">データがありません</p>;
 
   return (
     <div className="space-y-6">
-      <h2 className="text-lg font-semibold text-gray-800">
-        損益計算書 — {year}年度
-      </h2>
+      <div className="flex items-center justify-between">
+        <h2 className="text-lg font-semibold text-gray-800">
+          損益計算書 — {year}年度
+        </h2>
+        {hasSegments && (
+          <select
+            value={segmentKey}
+            onChange={(e) => setSegmentKey(e.target.value)}
+            className="rounded border border-gray-300 px-3 py-1.5 text-sm"
+          >
+            <option value="">全体</option>
+            {segments.map((s) => (
+              <option key={keyOf(s)} value={keyOf(s)}>
+                {s.segment_name}
+              </option>
+            ))}
+          </select>
+        )}
+      </div>
 
       <div className="grid gap-6 lg:grid-cols-2">
         {/* 収益の部 */}
@@ -124,6 +154,56 @@ export default function ProfitLoss({ year }: Props) {
           {formatYen(Math.abs(data.net_income))}
         </p>
       </div>
+
+      {/* セグメント別 */}
+      {hasSegments && segmentKey === "" && (
+        <div className="rounded-lg border border-gray-200 bg-white shadow-sm">
+          <div className="border-b border-gray-100 bg-gray-50 px-4 py-2">
+            <h3 className="text-sm font-semibold text-gray-700">セグメント別</h3>
+          </div>
+          <table className="w-full text-sm">
+            <thead>
+              <tr className="border-b border-gray-100 text-left text-xs font-medium text-gray-500">
+                <th className="px-4 py-2">セグメント</th>
+                <th className="px-4 py-2 text-right">収益</th>
+                <th className="px-4 py-2 text-right">費用</th>
+                <th className="px-4 py-2 text-right">損益</th>
+              </tr>
+            </thead>
+            <tbody>
+              {segments.map((s) => (
+                <tr
+                  key={keyOf(s)}
+                  onClick={() => setSegmentKey(keyOf(s))}
+                  className="cursor-pointer border-b border-gray-50 hover:bg-gray-50"
+                >
+                  <td className="px-4 py-2">
+                    {s.segment_name}
+                    {s.segment_type && (
+                      <span className="ml-2 text-xs text-gray-400">
+                        {s.segment_type}
+                      </span>
+                    )}
+                  </td>
+                  <td className="px-4 py-2 text-right tabular-nums">
+                    {formatYen(s.profit_loss.total_revenue)}
+                  </td>
+                  <td className="px-4 py-2 text-right tabular-nums">
+                    {formatYen(s.profit_loss.total_expense)}
+                  </td>
+                  <td
+                    className={`px-4 py-2 text-right tabular-nums font-medium ${
+                      s.profit_loss.net_income < 0 ? "text-red-600" : ""
+                    }`}
+                  >
+                    {formatYen(s.profit_loss.net_income)}
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        </div>
+      )}
     </div>
   );
 }
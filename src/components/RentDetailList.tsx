@@ -3,7 +3,11 @@ import type { RentDetail } from "../types";
 import { formatYen } from "../lib/format";
 import * as api from "../lib/api";
 
-export default function RentDetailList() {
+interface Props {
+  year: number;
+}
+
+export default function RentDetailList({ year }: Props) {
   const [details, setDetails] = useState<RentDetail[]>([]);
   const [showForm, setShowForm] = useState(false);
   const [payeeAddress, setPayeeAddress] = useState("");
@@ -16,8 +20,8 @@ export default function RentDetailList() {
   const [error, setError] = useState("");
 
   const load = useCallback(() => {
-    api.getRentDetails().then(setDetails).catch(console.error);
-  }, []);
+    api.getRentDetails(year).then(setDetails).catch(console.error);
+  }, [year]);
 
   useEffect(() => {
     load();
@@ -53,6 +57,7 @@ export default function RentDetailList() {
 
     try {
       await api.addRentDetail({
+        year,
         payeeAddress,
         payeeName: payeeName.trim(),
         rentType,
@@ -94,7 +99,7 @@ export default function RentDetailList() {
     <div className="space-y-4">
       <div className="flex items-center justify-between">
         <h2 className="text-lg font-semibold text-gray-800">
-          地代家賃の内訳
+          地代家賃の内訳（{year}年）
         </h2>
         <button
           onClick={() => setShowForm(!showForm)}
@@ -10,16 +10,17 @@ interface Props {
 export default function TrialBalance({ year }: Props) {
   const [data, setData] = useState<TB | null>(null);
   const [month, setMonth] = useState<number | null>(null);
+  const [bySubAccount, setBySubAccount] = useState(false);
   const [loading, setLoading] = useState(true);
 
   useEffect(() => {
     setLoading(true);
     api
-      .getTrialBalance(year, month ?? undefined)
+      .getTrialBalance(year, month ?? undefined, bySubAccount)
       .then(setData)
       .catch((err) => alert(String(err)))
       .finally(() => setLoading(false));
-  }, [year, month]);
+  }, [year, month, bySubAccount]);
 
   return (
     <div className="space-y-4">
@@ -27,20 +28,30 @@ export default function TrialBalance({ year }: Props) {
         <h2 className="text-lg font-semibold text-gray-800">
           試算表 — {year}年{month ? `${month}月` : "（年間）"}
         </h2>
-        <select
-          value={month ?? ""}
-          onChange={(e) =>
-            setMonth(e.target.value ? Number(e.target.value) : null)
-          }
-          className="rounded border border-gray-300 px-2 py-1 text-sm"
-        >
-          <option value="">全月</option>
-          {Array.from({ length: 12 }, (_, i) => i + 1).map((m) => (
-            <option key={m} value={m}>
-              {m}月
-            </option>
-          ))}
-        </select>
+        <div className="flex items-center gap-3">
+          <label className="flex items-center gap-1 text-sm text-gray-600">
+            <input
+              type="checkbox"
+              checked={bySubAccount}
+              onChange={(e) => setBySubAccount(e.target.checked)}
+            />
+            補助科目別
+          </label>
+          <select
+            value={month ?? ""}
+            onChange={(e) =>
+              setMonth(e.target.value ? Number(e.target.value) : null)
+            }
+            className="rounded border border-gray-300 px-2 py-1 text-sm"
+          >
+            <option value="">全月</option>
+            {Array.from({ length: 12 }, (_, i) => i + 1).map((m) => (
+              <option key={m} value={m}>
+                {m}月
+              </option>
+            ))}
+          </select>
+        </div>
       </div>
 
       {loading ? (
@@ -63,13 +74,20 @@ export default function TrialBalance({ year }: Props) {
             <tbody>
               {data.rows.map((row) => (
                 <tr
-                  key={row.account_id}
+                  key={`${row.account_id}-${row.sub_account_id ?? ""}`}
                   className="border-b border-gray-50 hover:bg-gray-50"
                 >
                   <td className="px-4 py-2 font-mono text-xs text-gray-400">
                     {row.account_code}
                   </td>
-                  <td className="px-4 py-2">{row.account_name}</td>
+                  <td className="px-4 py-2">
+                    {row.account_name}
+                    {row.sub_account_name && (
+                      <span className="ml-1 text-xs text-gray-500">
+                        ／{row.sub_account_name}
+                      </span>
+                    )}
+                  </td>
                   <td className="px-4 py-2 text-xs text-gray-500">
                     {row.classification}
                   </td>
@@ -1,15 +1,60 @@
 import { invoke } from "@tauri-apps/api/tauri";
 import type {
   Account,
+  SubAccount,
+  Partner,
+  PartnerType,
+  Segment,
+  SegmentType,
   JournalEntry,
+  EntrySearchQuery,
+  EntrySortKey,
+  EntryBatchRow,
+  EntryBatchUpdate,
+  EntryBatchResult,
+  EntrySearchResult,
+  JournalLine,
+  JournalLineInput,
   TrialBalance,
+  Ledger,
   ProfitLoss,
+  SegmentProfitLoss,
   BalanceSheet,
+  OpeningBalance,
+  OpeningBalanceInput,
+  YearClosing,
+  PeriodLock,
+  EntryRevision,
+  UndoStatus,
+  AccountSuggestion,
+  DuplicatePair,
+  OpenItemMonth,
+  ReviewStatus,
+  EntryAddResult,
+  Attachment,
+  AttachFileResult,
+  AttachmentCheck,
+  InvalidDate,
+  EntryReversal,
+  VoucherNumberReport,
   FixedAsset,
   RentDetail,
+  ApportionmentRule,
+  ApportionmentLine,
+  ExchangeRate,
+  ExchangeRateImportResult,
+  ForeignCurrencyReport,
+  ForeignSide,
+  ItemMatch,
+  AgingReport,
   LossCarryforward,
   LossCarryforwardSummary,
   FinalStatement,
+  JournalTemplate,
+  JournalTemplateInput,
+  RecurringEntry,
+  RecurringFrequency,
+  RecurringRunResult,
 } from "../types";
 
 // ── 勘定科目 ──
@@ -26,6 +71,138 @@ export async function addAccount(
   return invoke("add_account", { code, name, classification });
 }
 
+export async function updateAccount(params: {
+  id: number;
+  code: number;
+  name: string;
+  classification: string;
+  isActive: boolean;
+}): Promise<void> {
+  return invoke("update_account", {
+    id: params.id,
+    code: params.code,
+    name: params.name,
+    classification: params.classification,
+    is_active: params.isActive,
+  });
+}
+
+export async function deleteAccount(id: number): Promise<void> {
+  return invoke("delete_account", { id });
+}
+
+/** 統合元の科目を統合先に付け替えて削除する（付け替えた仕訳の件数を返す） */
+export async function mergeAccounts(fromId: number, toId: number): Promise<number> {
+  return invoke("merge_accounts", { from_id: fromId, to_id: toId });
+}
+
+// ── 補助科目 ──
+
+export async function getSubAccounts(accountId?: number): Promise<SubAccount[]> {
+  return invoke("get_sub_accounts", { account_id: accountId });
+}
+
+export async function addSubAccount(
+  accountId: number,
+  name: string,
+): Promise<number> {
+  return invoke("add_sub_account", { account_id: accountId, name });
+}
+
+export async function updateSubAccount(
+  id: number,
+  name: string,
+  isActive: boolean,
+): Promise<void> {
+  return invoke("update_sub_account", { id, name, is_active: isActive });
+}
+
+export async function deleteSubAccount(id: number): Promise<void> {
+  return invoke("delete_sub_account", { id });
+}
+
+// ── 取引先 ──
+
+export async function getPartners(): Promise<Partner[]> {
+  return invoke("get_partners");
+}
+
+export async function addPartner(params: {
+  name: string;
+  kana: string;
+  address: string;
+  invoiceNumber: string;
+  partnerType: PartnerType;
+  memo: string;
+}): Promise<number> {
+  return invoke("add_partner", {
+    name: params.name,
+    kana: params.kana,
+    address: params.address,
+    invoice_number: params.invoiceNumber,
+    partner_type: params.partnerType,
+    memo: params.memo,
+  });
+}
+
+export async function updatePartner(params: {
+  id: number;
+  name: string;
+  kana: string;
+  address: string;
+  invoiceNumber: string;
+  partnerType: PartnerType;
+  memo: string;
+}): Promise<void> {
+  return invoke("update_partner", {
+    id: params.id,
+    name: params.name,
+    kana: params.kana,
+    address: params.address,
+    invoice_number: params.invoiceNumber,
+    partner_type: params.partnerType,
+    memo: params.memo,
+  });
+}
+
+export async function deletePartner(id: number): Promise<void> {
+  return invoke("delete_partner", { id });
+}
+
+// ── セグメント ──
+
+export async function getSegments(): Promise<Segment[]> {
+  return invoke("get_segments");
+}
+
+export async function addSegment(
+  name: string,
+  segmentType: SegmentType,
+  memo: string,
+): Promise<number> {
+  return invoke("add_segment", { name, segment_type: segmentType, memo });
+}
+
+export async function updateSegment(params: {
+  id: number;
+  name: string;
+  segmentType: SegmentType;
+  memo: string;
+  isActive: boolean;
+}): Promise<void> {
+  return invoke("update_segment", {
+    id: params.id,
+    name: params.name,
+    segment_type: params.segmentType,
+    memo: params.memo,
+    is_active: params.isActive,
+  });
+}
+
+export async function deleteSegment(id: number): Promise<void> {
+  return invoke("delete_segment", { id });
+}
+
 // ── 仕訳 ──
 
 export async function addEntry(params: {
@@ -35,7 +212,11 @@ export async function addEntry(params: {
   creditAccountId: number;
   creditAmount: number;
   description: string;
-}): Promise<number> {
+  partnerId?: number;
+  debitSubAccountId?: number;
+  creditSubAccountId?: number;
+  segmentId?: number;
+}): Promise<EntryAddResult> {
   return invoke("add_entry", {
     date: params.date,
     debit_account_id: params.debitAccountId,
@@ -43,14 +224,131 @@ export async function addEntry(params: {
     credit_account_id: params.creditAccountId,
     credit_amount: params.creditAmount,
     description: params.description,
+    partner_id: params.partnerId,
+    debit_sub_account_id: params.debitSubAccountId,
+    credit_sub_account_id: params.creditSubAccountId,
+    segment_id: params.segmentId,
+  });
+}
+
+// ── 重複チェック ──
+
+export async function findDuplicateEntries(
+  year: number,
+  windowDays?: number,
+  includeDismissed?: boolean,
+): Promise<DuplicatePair[]> {
+  return invoke("find_duplicate_entries", {
+    year,
+    window_days: windowDays,
+    include_dismissed: includeDismissed,
+  });
+}
+
+/** 仕訳の組を「重複ではない」として記録する */
+export async function dismissDuplicate(
+  entryId: number,
+  duplicateId: number,
+): Promise<void> {
+  return invoke("dismiss_duplicate", {
+    entry_id: entryId,
+    duplicate_id: duplicateId,
+  });
+}
+
+export async function undismissDuplicate(
+  entryId: number,
+  duplicateId: number,
+): Promise<void> {
+  return invoke("undismiss_duplicate", {
+    entry_id: entryId,
+    duplicate_id: duplicateId,
+  });
+}
+
+/** 摘要・金額から借方・貸方科目と取引先の候補を取得する */
+export async function suggestAccounts(
+  description: string,
+  amount?: number,
+  limit?: number,
+): Promise<AccountSuggestion[]> {
+  return invoke("suggest_accounts", { description, amount, limit });
+}
+
+export async function addCompoundEntry(params: {
+  date: string;
+  description: string;
+  lines: JournalLineInput[];
+  partnerId?: number;
+  segmentId?: number;
+}): Promise<EntryAddResult> {
+  return invoke("add_compound_entry", {
+    date: params.date,
+    description: params.description,
+    lines: params.lines,
+    partner_id: params.partnerId,
+    segment_id: params.segmentId,
   });
 }
 
+export async function getEntryLines(entryId: number): Promise<JournalLine[]> {
+  return invoke("get_entry_lines", { entry_id: entryId });
+}
+
 export async function getEntries(
   year: number,
   month?: number,
+  partnerId?: number,
+  segmentId?: number,
 ): Promise<JournalEntry[]> {
-  return invoke("get_entries", { year, month });
+  return invoke("get_entries", {
+    year,
+    month,
+    partner_id: partnerId,
+    segment_id: segmentId,
+  });
+}
+
+export async function getEntriesPage(params: {
+  year: number;
+  month?: number;
+  partnerId?: number;
+  segmentId?: number;
+  sort?: EntrySortKey;
+  descending?: boolean;
+  limit?: number;
+  offset?: number;
+}): Promise<EntrySearchResult> {
+  return invoke("get_entries_page", {
+    year: params.year,
+    month: params.month,
+    partner_id: params.partnerId,
+    segment_id: params.segmentId,
+    sort: params.sort,
+    descending: params.descending,
+    limit: params.limit,
+    offset: params.offset,
+  });
+}
+
+export async function countEntries(
+  year: number,
+  month?: number,
+  partnerId?: number,
+  segmentId?: number,
+): Promise<number> {
+  return invoke("count_entries", {
+    year,
+    month,
+    partner_id: partnerId,
+    segment_id: segmentId,
+  });
+}
+
+export async function searchEntries(
+  query: EntrySearchQuery,
+): Promise<EntrySearchResult> {
+  return invoke("search_entries", { query });
 }
 
 export async function updateEntry(params: {
@@ -61,6 +359,15 @@ export async function updateEntry(params: {
   creditAccountId: number;
   creditAmount: number;
   description: string;
+  partnerId?: number;
+  debitSubAccountId?: number;
+  creditSubAccountId?: number;
+  segmentId?: number;
+  /** 外貨建ての仕訳は通貨と外貨額を渡す（省略すると円建てになる） */
+  currency?: string;
+  foreignAmount?: string;
+  exchangeRate?: string;
+  foreignSide?: ForeignSide;
 }): Promise<void> {
   return invoke("update_entry", {
     id: params.id,
@@ -70,6 +377,32 @@ export async function updateEntry(params: {
     credit_account_id: params.creditAccountId,
     credit_amount: params.creditAmount,
     description: params.description,
+    partner_id: params.partnerId,
+    debit_sub_account_id: params.debitSubAccountId,
+    credit_sub_account_id: params.creditSubAccountId,
+    segment_id: params.segmentId,
+    currency: params.currency,
+    foreign_amount: params.foreignAmount,
+    exchange_rate: params.exchangeRate,
+    foreign_side: params.foreignSide,
+  });
+}
+
+export async function updateCompoundEntry(params: {
+  id: number;
+  date: string;
+  description: string;
+  lines: JournalLineInput[];
+  partnerId?: number;
+  segmentId?: number;
+}): Promise<void> {
+  return invoke("update_compound_entry", {
+    id: params.id,
+    date: params.date,
+    description: params.description,
+    lines: params.lines,
+    partner_id: params.partnerId,
+    segment_id: params.segmentId,
   });
 }
 
@@ -77,23 +410,180 @@ export async function deleteEntry(id: number): Promise<void> {
   return invoke("delete_entry", { id });
 }
 
+// ── 一括処理 ──
+
+export async function addEntriesBatch(
+  rows: EntryBatchRow[],
+): Promise<EntryBatchResult> {
+  return invoke("add_entries_batch", { rows });
+}
+
+export async function updateEntriesBatch(
+  updates: EntryBatchUpdate[],
+): Promise<EntryBatchResult> {
+  return invoke("update_entries_batch", { updates });
+}
+
+export async function deleteEntriesBatch(
+  ids: number[],
+): Promise<EntryBatchResult> {
+  return invoke("delete_entries_batch", { ids });
+}
+
+// ── 確認状況 ──
+
+export async function updateEntryReview(
+  id: number,
+  reviewStatus: ReviewStatus,
+  note: string,
+): Promise<void> {
+  return invoke("update_entry_review", {
+    id,
+    review_status: reviewStatus,
+    note,
+  });
+}
+
+export async function getOpenItems(year: number): Promise<OpenItemMonth[]> {
+  return invoke("get_open_items", { year });
+}
+
+// ── 伝票番号 ──
+
+export async function getVoucherNumberReport(
+  year: number,
+): Promise<VoucherNumberReport> {
+  return invoke("get_voucher_number_report", { year });
+}
+
+// ── 赤黒訂正 ──
+
+export async function reverseEntry(id: number, date?: string): Promise<number> {
+  return invoke("reverse_entry", { id, date });
+}
+
+export async function getReversals(
+  year: number,
+  month?: number,
+): Promise<EntryReversal[]> {
+  return invoke("get_reversals", { year, month });
+}
+
+// ── 訂正・削除履歴 ──
+
+export async function getEntryRevisions(entryId: number): Promise<EntryRevision[]> {
+  return invoke("get_entry_revisions", { entry_id: entryId });
+}
+
+export async function getRevisions(
+  year: number,
+  month?: number,
+): Promise<EntryRevision[]> {
+  return invoke("get_revisions", { year, month });
+}
+
+// ── 元に戻す・やり直し ──
+
+export async function undo(count?: number): Promise<string[]> {
+  return invoke("undo", { count });
+}
+
+export async function redo(count?: number): Promise<string[]> {
+  return invoke("redo", { count });
+}
+
+export async function getUndoStatus(): Promise<UndoStatus> {
+  return invoke("get_undo_status");
+}
+
 // ── 集計・レポート ──
 
 export async function getTrialBalance(
   year: number,
   month?: number,
+  bySubAccount?: boolean,
 ): Promise<TrialBalance> {
-  return invoke("get_trial_balance", { year, month });
+  return invoke("get_trial_balance", {
+    year,
+    month,
+    by_sub_account: bySubAccount,
+  });
+}
+
+export async function getLedger(
+  accountId: number,
+  year: number,
+  month?: number,
+  partnerId?: number,
+  subAccountId?: number,
+): Promise<Ledger> {
+  return invoke("get_ledger", {
+    account_id: accountId,
+    year,
+    month,
+    partner_id: partnerId,
+    sub_account_id: subAccountId,
+  });
 }
 
 export async function getProfitLoss(year: number): Promise<ProfitLoss> {
   return invoke("get_profit_loss", { year });
 }
 
+export async function getSegmentProfitLoss(
+  year: number,
+  segmentId?: number,
+): Promise<SegmentProfitLoss[]> {
+  return invoke("get_segment_profit_loss", { year, segment_id: segmentId });
+}
+
 export async function getBalanceSheet(year: number): Promise<BalanceSheet> {
   return invoke("get_balance_sheet", { year });
 }
 
+// ── 期首残高 ──
+
+export async function getOpeningBalances(
+  year: number,
+): Promise<OpeningBalance[]> {
+  return invoke("get_opening_balances", { year });
+}
+
+export async function setOpeningBalances(
+  year: number,
+  balances: OpeningBalanceInput[],
+): Promise<void> {
+  return invoke("set_opening_balances", { year, balances });
+}
+
+// ── 年度締め ──
+
+export async function getYearClosings(): Promise<YearClosing[]> {
+  return invoke("get_year_closings");
+}
+
+export async function closeYear(year: number): Promise<YearClosing> {
+  return invoke("close_year", { year });
+}
+
+export async function reopenYear(year: number): Promise<void> {
+  return invoke("reopen_year", { year });
+}
+
+// ── 期間ロック ──
+
+export async function getPeriodLocks(): Promise<PeriodLock[]> {
+  return invoke("get_period_locks");
+}
+
+export async function lockPeriod(year: number, month?: number): Promise<number> {
+  return invoke("lock_period", { year, month });
+}
+
+export async function unlockPeriod(year: number, month?: number): Promise<void> {
+  return invoke("unlock_period", { year, month });
+}
+
 // ── 固定資産 ──
 
 export async function getFixedAssets(): Promise<FixedAsset[]> {
@@ -128,11 +618,12 @@ export async function deleteFixedAsset(id: number): Promise<void> {
 
 // ── 地代家賃内訳 ──
 
-export async function getRentDetails(): Promise<RentDetail[]> {
-  return invoke("get_rent_details");
+export async function getRentDetails(year: number): Promise<RentDetail[]> {
+  return invoke("get_rent_details", { year });
 }
 
 export async function addRentDetail(params: {
+  year: number;
   payeeAddress: string;
   payeeName: string;
   rentType: string;
@@ -140,8 +631,10 @@ export async function addRentDetail(params: {
   annualTotal: number;
   businessRatio: number;
   memo: string;
+  partnerId?: number;
 }): Promise<number> {
   return invoke("add_rent_detail", {
+    year: params.year,
     payee_address: params.payeeAddress,
     payee_name: params.payeeName,
     rent_type: params.rentType,
@@ -149,6 +642,7 @@ export async function addRentDetail(params: {
     annual_total: params.annualTotal,
     business_ratio: params.businessRatio,
     memo: params.memo,
+    partner_id: params.partnerId,
   });
 }
 
@@ -156,6 +650,128 @@ export async function deleteRentDetail(id: number): Promise<void> {
   return invoke("delete_rent_detail", { id });
 }
 
+// ── 家事按分 ──
+
+export async function getApportionmentRules(): Promise<ApportionmentRule[]> {
+  return invoke("get_apportionment_rules");
+}
+
+export async function saveApportionmentRule(params: {
+  accountId: number;
+  businessRatio: number;
+  basis: string;
+}): Promise<number> {
+  return invoke("save_apportionment_rule", {
+    account_id: params.accountId,
+    business_ratio: params.businessRatio,
+    basis: params.basis,
+  });
+}
+
+export async function deleteApportionmentRule(id: number): Promise<void> {
+  return invoke("delete_apportionment_rule", { id });
+}
+
+export async function previewApportionment(
+  year: number,
+): Promise<ApportionmentLine[]> {
+  return invoke("preview_apportionment", { year });
+}
+
+/** 家事按分の振替仕訳を作り直す（作成済みの振替仕訳は取り消される） */
+export async function postApportionment(year: number): Promise<number[]> {
+  return invoke("post_apportionment", { year });
+}
+
+// ── 外貨建取引 ──
+
+export async function getExchangeRates(
+  currency?: string,
+  year?: number,
+): Promise<ExchangeRate[]> {
+  return invoke("get_exchange_rates", { currency, year });
+}
+
+/** date 以前で直近の TTM（7日以内に登録がなければ null） */
+export async function getExchangeRate(
+  currency: string,
+  date: string,
+): Promise<ExchangeRate | null> {
+  return invoke("get_exchange_rate", { currency, date });
+}
+
+/** 日付,通貨,TTM の CSV を取り込む（エラーがあれば何も取り込まない） */
+export async function importExchangeRates(
+  csv: string,
+): Promise<ExchangeRateImportResult> {
+  return invoke("import_exchange_rates", { csv });
+}
+
+export async function addForeignEntry(params: {
+  date: string;
+  debitAccountId: number;
+  creditAccountId: number;
+  description: string;
+  partnerId?: number;
+  currency: string;
+  /** 外貨額・換算レートは入力された小数の文字列のまま渡す（サーバー側で整数にする） */
+  foreignAmount: string;
+  exchangeRate?: string;
+  foreignSide: ForeignSide;
+  amount?: number;
+}): Promise<EntryAddResult> {
+  return invoke("add_foreign_entry", {
+    date: params.date,
+    debit_account_id: params.debitAccountId,
+    credit_account_id: params.creditAccountId,
+    description: params.description,
+    partner_id: params.partnerId,
+    currency: params.currency,
+    foreign_amount: params.foreignAmount,
+    exchange_rate: params.exchangeRate,
+    foreign_side: params.foreignSide,
+    amount: params.amount,
+  });
+}
+
+export async function getForeignCurrencyReport(
+  year: number,
+): Promise<ForeignCurrencyReport> {
+  return invoke("get_foreign_currency_report", { year });
+}
+
+// ── 消込 ──
+
+/** amount を省略すると双方の未消込額のうち小さい方を消し込む */
+export async function matchItems(params: {
+  accountId: number;
+  debitEntryId: number;
+  creditEntryId: number;
+  amount?: number;
+}): Promise<number> {
+  return invoke("match_items", {
+    account_id: params.accountId,
+    debit_entry_id: params.debitEntryId,
+    credit_entry_id: params.creditEntryId,
+    amount: params.amount,
+  });
+}
+
+export async function unmatchItems(id: number): Promise<void> {
+  return invoke("unmatch_items", { id });
+}
+
+export async function getItemMatches(entryId: number): Promise<ItemMatch[]> {
+  return invoke("get_item_matches", { entry_id: entryId });
+}
+
+export async function getAgingReport(
+  asOf: string,
+  accountId?: number,
+): Promise<AgingReport> {
+  return invoke("get_aging_report", { as_of: asOf, account_id: accountId });
+}
+
 // ── 純損失の繰越控除 ──
 
 export async function getLossCarryforwards(): Promise<LossCarryforward[]> {
@@ -206,6 +822,115 @@ export async function getFinalStatement(
   return invoke("get_final_statement", { year });
 }
 
+// ── 仕訳テンプレート ──
+
+export async function getJournalTemplates(): Promise<JournalTemplate[]> {
+  return invoke("get_journal_templates");
+}
+
+export async function addJournalTemplate(
+  template: JournalTemplateInput,
+): Promise<number> {
+  return invoke("add_journal_template", { template });
+}
+
+export async function updateJournalTemplate(
+  id: number,
+  template: JournalTemplateInput,
+): Promise<void> {
+  return invoke("update_journal_template", { id, template });
+}
+
+export async function deleteJournalTemplate(id: number): Promise<void> {
+  return invoke("delete_journal_template", { id });
+}
+
+export async function addEntryFromTemplate(params: {
+  templateId: number;
+  date: string;
+  amount?: number;
+  description?: string;
+  partnerId?: number;
+  segmentId?: number;
+}): Promise<EntryAddResult> {
+  return invoke("add_entry_from_template", {
+    template_id: params.templateId,
+    date: params.date,
+    amount: params.amount,
+    description: params.description,
+    partner_id: params.partnerId,
+    segment_id: params.segmentId,
+  });
+}
+
+// ── 定期仕訳 ──
+
+export async function getRecurringEntries(): Promise<RecurringEntry[]> {
+  return invoke("get_recurring_entries");
+}
+
+export async function addRecurringEntry(params: {
+  name: string;
+  debitAccountId: number;
+  creditAccountId: number;
+  amount: number;
+  descriptionTemplate: string;
+  frequency: RecurringFrequency;
+  dayOfMonth: number;
+  startDate: string;
+  endDate?: string;
+}): Promise<number> {
+  return invoke("add_recurring_entry", {
+    name: params.name,
+    debit_account_id: params.debitAccountId,
+    credit_account_id: params.creditAccountId,
+    amount: params.amount,
+    description_template: params.descriptionTemplate,
+    frequency: params.frequency,
+    day_of_month: params.dayOfMonth,
+    start_date: params.startDate,
+    end_date: params.endDate,
+  });
+}
+
+export async function updateRecurringEntry(params: {
+  id: number;
+  name: string;
+  debitAccountId: number;
+  creditAccountId: number;
+  amount: number;
+  descriptionTemplate: string;
+  frequency: RecurringFrequency;
+  dayOfMonth: number;
+  startDate: string;
+  endDate?: string;
+  isActive: boolean;
+}): Promise<void> {
+  return invoke("update_recurring_entry", {
+    id: params.id,
+    name: params.name,
+    debit_account_id: params.debitAccountId,
+    credit_account_id: params.creditAccountId,
+    amount: params.amount,
+    description_template: params.descriptionTemplate,
+    frequency: params.frequency,
+    day_of_month: params.dayOfMonth,
+    start_date: params.startDate,
+    end_date: params.endDate,
+    is_active: params.isActive,
+  });
+}
+
+export async function deleteRecurringEntry(id: number): Promise<void> {
+  return invoke("delete_recurring_entry", { id });
+}
+
+export async function generateRecurringEntries(
+  until: string,
+): Promise<RecurringRunResult> {
+  return invoke("generate_recurring_entries", { until });
+}
+
 // ── エクスポート ──
 
 export async function exportJournalCsv(
@@ -215,6 +940,61 @@ export async function exportJournalCsv(
   return invoke("export_journal_csv", { year, month });
 }
 
+// ── 証憑 ──
+
+export async function getAttachments(entryId?: number): Promise<Attachment[]> {
+  return invoke("get_attachments", { entry_id: entryId });
+}
+
+/** ファイルを証憑として保存し、仕訳に紐付ける */
+export async function attachFile(params: {
+  path: string;
+  entryIds: number[];
+  receivedDate: string;
+  memo: string;
+}): Promise<AttachFileResult> {
+  return invoke("attach_file", {
+    path: params.path,
+    entry_ids: params.entryIds,
+    received_date: params.receivedDate,
+    memo: params.memo,
+  });
+}
+
+export async function linkAttachment(
+  attachmentId: number,
+  entryId: number,
+): Promise<void> {
+  return invoke("link_attachment", {
+    attachment_id: attachmentId,
+    entry_id: entryId,
+  });
+}
+
+export async function unlinkAttachment(
+  attachmentId: number,
+  entryId: number,
+): Promise<void> {
+  return invoke("unlink_attachment", {
+    attachment_id: attachmentId,
+    entry_id: entryId,
+  });
+}
+
+export async function openAttachment(id: number): Promise<void> {
+  return invoke("open_attachment", { id });
+}
+
+export async function verifyAttachments(): Promise<AttachmentCheck[]> {
+  return invoke("verify_attachments");
+}
+
+// ── 日付チェック ──
+
+export async function getInvalidDates(): Promise<InvalidDate[]> {
+  return invoke("get_invalid_dates");
+}
+
 // ── バックアップ ──
 
 export async function backupDatabase(): Promise<string> {
@@ -3,6 +3,18 @@ export function formatYen(amount: number): string {
   return `¥${amount.toLocaleString("ja-JP")}`;
 }
 
+/** 外貨額・為替レートを整数で保存するときの小数点以下の桁数（外貨額は100倍、レートは10000倍） */
+export const FOREIGN_AMOUNT_SCALE = 2;
+export const RATE_SCALE = 4;
+
+/** 10^scale 倍した整数を小数に戻して 1,234.56 形式にフォーマット */
+export function formatScaled(value: number, scale: number): string {
+  return (value / 10 ** scale).toLocaleString("ja-JP", {
+    minimumFractionDigits: scale,
+    maximumFractionDigits: scale,
+  });
+}
+
 /** 今日の日付を YYYY-MM-DD 形式で返す */
 export function todayString(): string {
   const d = new Date();
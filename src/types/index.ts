@@ -4,6 +4,42 @@ export interface Account {
   code: number;
   name: string;
   classification: "資産" | "負債" | "純資産" | "収益" | "費用";
+  is_active: boolean;
+  is_matching: boolean; // 消込の対象科目（売掛金・立替金・仮払金）
+}
+
+// 補助科目
+export interface SubAccount {
+  id: number;
+  account_id: number;
+  account_code: number;
+  account_name: string;
+  name: string;
+  is_active: boolean;
+}
+
+// 取引先
+export type PartnerType = "得意先" | "仕入先" | "両方";
+
+export interface Partner {
+  id: number;
+  name: string;
+  kana: string;
+  address: string;
+  invoice_number: string;
+  partner_type: PartnerType;
+  memo: string;
+}
+
+// セグメント（部門・プロジェクト）
+export type SegmentType = "部門" | "プロジェクト" | "タグ";
+
+export interface Segment {
+  id: number;
+  name: string;
+  segment_type: SegmentType;
+  memo: string;
+  is_active: boolean;
 }
 
 // 仕訳
@@ -18,6 +54,211 @@ export interface JournalEntry {
   credit_amount: number;
   description: string;
   created_at: string;
+  is_compound: boolean;
+  reversal_of: number | null;
+  reversed_by: number | null;
+  voucher_no: number | null;
+  partner_id: number | null;
+  partner_name: string | null;
+  segment_id: number | null;
+  segment_name: string | null;
+  review_status: ReviewStatus;
+  note: string;
+  // 外貨建取引（foreign_amount は外貨額 × 100、exchange_rate は換算レート × 10000）
+  currency: string | null;
+  foreign_amount: number | null;
+  exchange_rate: number | null;
+  foreign_side: ForeignSide | null;
+}
+
+// 仕訳の確認状況（note は摘要とは別のメモで、仕訳帳にも表示する）
+export type ReviewStatus = "未確認" | "確認済" | "要質問";
+
+// 月ごとの確認待ち（未確認・要質問）の仕訳
+export interface OpenItemMonth {
+  month: number;
+  unconfirmed_count: number;
+  question_count: number;
+  entries: JournalEntry[];
+}
+
+// 仕訳の一括登録・訂正（単一行仕訳）
+export interface EntryBatchRow {
+  date: string;
+  debit_account_id: number;
+  debit_amount: number;
+  credit_account_id: number;
+  credit_amount: number;
+  description?: string;
+  partner_id?: number;
+  debit_sub_account_id?: number;
+  credit_sub_account_id?: number;
+  segment_id?: number;
+}
+
+export interface EntryBatchUpdate extends EntryBatchRow {
+  id: number;
+}
+
+// row は1始まりの行番号
+export interface EntryBatchError {
+  row: number;
+  message: string;
+}
+
+// errors が1件でもあれば何も反映されず ids は空
+// duplicates は一括登録した仕訳と重複の疑いがある組（訂正・削除では常に空）
+export interface EntryBatchResult {
+  ids: number[];
+  errors: EntryBatchError[];
+  duplicates: DuplicatePair[];
+}
+
+// 重複チェック（entry は登録の古い方）
+export interface DuplicatePair {
+  entry: JournalEntry;
+  duplicate: JournalEntry;
+  reason: "同日・同額・同科目" | "同額・同摘要";
+  days_apart: number;
+  dismissed: boolean;
+}
+
+// 仕訳の登録結果（duplicates は重複の疑いがある仕訳。登録自体は完了している）
+export interface EntryAddResult {
+  id: number;
+  duplicates: DuplicatePair[];
+}
+
+// 過去の仕訳から推定した科目・取引先の候補
+export interface AccountSuggestion {
+  debit_account_id: number;
+  debit_account_name: string;
+  credit_account_id: number;
+  credit_account_name: string;
+  partner_id: number | null;
+  partner_name: string | null;
+  score: number;
+  match_count: number;
+  last_used: string;
+}
+
+// 仕訳一覧の並び替え項目
+export type EntrySortKey =
+  | "date"
+  | "amount"
+  | "account_code"
+  | "created_at"
+  | "voucher_no";
+
+// 仕訳検索（未指定の条件では絞り込まない。金額は取引金額で比較）
+export interface EntrySearchQuery {
+  keyword?: string;
+  date_from?: string;
+  date_to?: string;
+  amount_min?: number;
+  amount_max?: number;
+  account_id?: number;
+  partner_id?: number;
+  segment_id?: number;
+  sort?: EntrySortKey;
+  descending?: boolean;
+  limit?: number;
+  offset?: number;
+}
+
+export interface EntrySearchResult {
+  entries: JournalEntry[];
+  total_count: number;
+}
+
+// 仕訳明細（複合仕訳）
+export type EntrySide = "借方" | "貸方";
+
+export interface JournalLine {
+  id: number;
+  entry_id: number;
+  line_no: number;
+  side: EntrySide;
+  account_id: number;
+  account_code: number;
+  account_name: string;
+  amount: number;
+  sub_account_id: number | null;
+  sub_account_name: string | null;
+}
+
+export interface JournalLineInput {
+  side: EntrySide;
+  account_id: number;
+  sub_account_id?: number | null;
+  amount: number;
+}
+
+// 訂正・削除履歴
+export type RevisionAction = "登録" | "訂正" | "取消" | "復元";
+
+// 外貨建ての科目がある側（外貨残高はこの側の科目にだけ計上する）
+export type ForeignSide = "借方" | "貸方" | "両方";
+
+// 外貨建取引の通貨・外貨額（× 100）・換算レート（× 10000）
+export interface ForeignAmount {
+  currency: string;
+  foreign_amount: number;
+  exchange_rate: number;
+  foreign_side: ForeignSide;
+}
+
+export interface EntrySnapshot {
+  date: string;
+  description: string;
+  is_compound: boolean;
+  partner_id: number | null;
+  segment_id: number | null;
+  lines: JournalLineInput[];
+  foreign: ForeignAmount | null;
+}
+
+export interface EntryRevision {
+  id: number;
+  entry_id: number;
+  revision_no: number;
+  action: RevisionAction;
+  before: EntrySnapshot | null;
+  after: EntrySnapshot | null;
+  changed_by: string;
+  changed_at: string;
+}
+
+// 元に戻す・やり直し（それぞれ次に実行される操作から順に並ぶ）
+export interface UndoStatus {
+  undo: string[];
+  redo: string[];
+}
+
+// 伝票番号の連番チェック
+export interface VoucherDuplicate {
+  voucher_no: number;
+  entry_ids: number[];
+}
+
+export interface VoucherNumberReport {
+  year: number;
+  entry_count: number;
+  voided_count: number;
+  max_voucher_no: number;
+  gaps: number[];
+  duplicates: VoucherDuplicate[];
+  unnumbered: number[];
+}
+
+// 赤黒訂正
+export interface EntryReversal {
+  entry_id: number;
+  entry_date: string;
+  description: string;
+  amount: number;
+  reversal_id: number;
+  reversal_date: string;
 }
 
 // 試算表
@@ -29,6 +270,8 @@ export interface TrialBalanceRow {
   debit_total: number;
   credit_total: number;
   balance: number;
+  sub_account_id: number | null;
+  sub_account_name: string | null;
 }
 
 export interface TrialBalance {
@@ -37,6 +280,33 @@ export interface TrialBalance {
   credit_grand_total: number;
 }
 
+// 総勘定元帳
+export interface LedgerRow {
+  entry_id: number;
+  date: string;
+  description: string;
+  counter_account_name: string;
+  debit_amount: number;
+  credit_amount: number;
+  balance: number;
+  reversal_of: number | null;
+  partner_name: string | null;
+}
+
+export interface Ledger {
+  account_id: number;
+  partner_id: number | null;
+  sub_account_id: number | null;
+  account_code: number;
+  account_name: string;
+  classification: string;
+  opening_balance: number;
+  rows: LedgerRow[];
+  debit_total: number;
+  credit_total: number;
+  closing_balance: number;
+}
+
 // 損益計算書
 export interface ProfitLossRow {
   account_id: number;
@@ -53,11 +323,20 @@ export interface ProfitLoss {
   net_income: number;
 }
 
+// セグメント別損益計算書（segment_id が null の行は未割当）
+export interface SegmentProfitLoss {
+  segment_id: number | null;
+  segment_name: string;
+  segment_type: SegmentType | null;
+  profit_loss: ProfitLoss;
+}
+
 // 貸借対照表
 export interface BalanceSheetRow {
   account_id: number;
   account_code: number;
   account_name: string;
+  opening_amount: number;
   amount: number;
 }
 
@@ -68,9 +347,47 @@ export interface BalanceSheet {
   total_assets: number;
   total_liabilities: number;
   total_equity: number;
+  total_assets_opening: number;
+  total_liabilities_opening: number;
+  total_equity_opening: number;
   net_income: number;
 }
 
+// 期首残高
+export interface OpeningBalance {
+  account_id: number;
+  account_code: number;
+  account_name: string;
+  classification: string;
+  amount: number;
+  source: "手入力" | "繰越";
+}
+
+export interface OpeningBalanceInput {
+  account_id: number;
+  amount: number;
+}
+
+// 年度締め
+export interface YearClosing {
+  year: number;
+  prior_capital: number;
+  income: number;
+  owner_borrowings: number;
+  owner_drawings: number;
+  next_capital: number;
+  closed_at: string;
+}
+
+// 期間ロック（month が null なら年度全体）
+export interface PeriodLock {
+  id: number;
+  year: number;
+  month: number | null;
+  locked_at: string;
+  unlocked_at: string | null;
+}
+
 // 固定資産
 export interface FixedAsset {
   id: number;
@@ -109,6 +426,107 @@ export interface RentDetail {
   annual_total: number;
   business_ratio: number;
   memo: string;
+  partner_id: number | null;
+  year: number;
+}
+
+// 家事按分のルール（business_ratio は事業割合の %）
+export interface ApportionmentRule {
+  id: number;
+  account_id: number;
+  account_code: number;
+  account_name: string;
+  business_ratio: number;
+  basis: string;
+}
+
+// 家事按分の計算結果（expense_total は振替仕訳を除いた費用、posted_amount は作成済みの振替額）
+export interface ApportionmentLine {
+  account_id: number;
+  account_code: number;
+  account_name: string;
+  business_ratio: number;
+  basis: string;
+  expense_total: number;
+  private_amount: number;
+  posted_amount: number;
+}
+
+// 外貨建取引（ttm は1通貨単位あたりの円 × 10000）
+export interface ExchangeRate {
+  currency: string;
+  date: string;
+  ttm: number;
+}
+
+export interface ExchangeRateImportResult {
+  imported: number;
+  errors: EntryBatchError[];
+}
+
+// foreign_balance は外貨残高 × 100、year_end_rate は TTM × 10000（null なら年末日前後の TTM が未登録）
+export interface ForeignCurrencyPosition {
+  account_id: number;
+  account_code: number;
+  account_name: string;
+  classification: string;
+  currency: string;
+  foreign_balance: number;
+  book_amount: number;
+  year_end_rate: number | null;
+  year_end_amount: number | null;
+  difference: number | null;
+}
+
+export interface ForeignCurrencyReport {
+  year: number;
+  positions: ForeignCurrencyPosition[];
+  exchange_gain: number;
+  exchange_loss: number;
+}
+
+// 消込（借方の売掛金・立替金・仮払金を、貸方の入金・精算と結び付ける）
+export interface ItemMatch {
+  id: number;
+  account_id: number;
+  debit_entry_id: number;
+  debit_date: string;
+  debit_description: string;
+  credit_entry_id: number;
+  credit_date: string;
+  credit_description: string;
+  amount: number;
+}
+
+// side が借方なら未回収・未精算、貸方なら消込先が決まっていない入金・精算
+export interface UnmatchedItem {
+  entry_id: number;
+  voucher_no: number | null;
+  date: string;
+  description: string;
+  partner_name: string | null;
+  account_id: number;
+  account_code: number;
+  account_name: string;
+  side: "借方" | "貸方";
+  amount: number;
+  matched_amount: number;
+  remaining: number;
+  age_days: number;
+}
+
+export interface AgingBucket {
+  label: string;
+  count: number;
+  amount: number;
+}
+
+export interface AgingReport {
+  as_of: string;
+  items: UnmatchedItem[];
+  unapplied_credits: UnmatchedItem[];
+  buckets: AgingBucket[];
+  total: number;
 }
 
 // 純損失の繰越控除
@@ -156,17 +574,131 @@ export interface FinalStatement {
   rent_total: number;
   balance_sheet: BalanceSheet;
   loss_carryforward: LossCarryforwardSummary;
+  open_item_count: number;
+}
+
+// 仕訳テンプレート（かんたん入力）
+export type TemplateCategory = "monthly" | "common" | "income";
+
+export interface JournalTemplate {
+  id: number;
+  label: string;
+  category: TemplateCategory;
+  help_text: string;
+  debit_account_id: number;
+  debit_account_name: string;
+  credit_account_id: number;
+  credit_account_name: string;
+  default_description: string;
+  description_placeholder: string;
+  default_amount: number | null;
+  tax_category: string;
+  sort_order: number;
+}
+
+export interface JournalTemplateInput {
+  label: string;
+  category: TemplateCategory;
+  help_text: string;
+  debit_account_id: number;
+  credit_account_id: number;
+  default_description: string;
+  description_placeholder: string;
+  default_amount: number | null;
+  tax_category: string;
+}
+
+// 定期仕訳
+export type RecurringFrequency = "毎月" | "隔月" | "毎年" | "指定日" | "月末";
+
+export interface RecurringEntry {
+  id: number;
+  name: string;
+  debit_account_id: number;
+  debit_account_name: string;
+  credit_account_id: number;
+  credit_account_name: string;
+  amount: number;
+  description_template: string;
+  frequency: RecurringFrequency;
+  day_of_month: number;
+  start_date: string;
+  end_date: string | null;
+  is_active: boolean;
+  last_due_date: string | null;
+}
+
+export interface RecurringRun {
+  recurring_id: number;
+  name: string;
+  due_date: string;
+  entry_id: number;
+}
+
+export interface RecurringSkip {
+  recurring_id: number;
+  name: string;
+  due_date: string;
+  reason: string;
+}
+
+export interface RecurringRunResult {
+  created: RecurringRun[];
+  skipped: RecurringSkip[];
 }
 
 // ナビゲーション
 export type PageId =
   | "journal"
   | "simple-entry"
+  | "entry-search"
+  | "duplicates"
+  | "open-items"
+  | "attachments"
   | "accounts"
   | "trial-balance"
   | "profit-loss"
   | "balance-sheet"
   | "fixed-assets"
   | "rent-details"
+  | "apportionment"
+  | "foreign-currency"
+  | "item-matching"
   | "loss-carryforward"
   | "final-statement";
+
+// 証憑（登録後は内容を変更できない）
+export interface Attachment {
+  id: number;
+  file_name: string;
+  stored_name: string;
+  mime_type: string;
+  size: number;
+  sha256: string;
+  received_date: string;
+  memo: string;
+  created_at: string;
+  entry_ids: number[];
+}
+
+// reused なら同じ内容の登録済みの証憑を紐付けた（受領日・メモは登録済みのまま）
+export interface AttachFileResult {
+  attachment: Attachment;
+  reused: boolean;
+}
+
+export type AttachmentStatus = "正常" | "不一致" | "ファイルなし";
+
+export interface AttachmentCheck {
+  id: number;
+  file_name: string;
+  status: AttachmentStatus;
+}
+
+// YYYY-MM-DD として読み取れない保存済みの日付（期間での集計・検索の対象外になる）
+export interface InvalidDate {
+  source: "仕訳" | "固定資産" | "定期仕訳" | "証憑";
+  id: number;
+  field: string;
+  value: string;
+}